@@ -0,0 +1,35 @@
+//! Compare the construction of a `Counter` from sorted input, with and
+//! without `from_sorted_iter`.
+//!
+//! Run with `cargo bench`.
+//!
+use collectors::Counter;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 20;
+
+fn bench<F: FnMut() -> Counter<u64>>(name: &str, mut f: F) {
+    let mut elapsed = Duration::default();
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        let _ = black_box(f());
+        elapsed += start.elapsed();
+    }
+    println!("{:<40} {:>10.3?}/iter", name, elapsed / ITERATIONS);
+}
+
+fn main() {
+    for &distinct in &[10u64, 10_000, 1_000_000] {
+        let column: Vec<u64> = (0..1_000_000).map(|i| i * distinct / 1_000_000).collect();
+
+        bench(&format!("update_from_iter ({} keys)", distinct), || {
+            let mut counter: Counter<u64> = Counter::new();
+            counter.update_from_iter(column.iter().cloned());
+            counter
+        });
+        bench(&format!("from_sorted_iter ({} keys)", distinct), || {
+            Counter::from_sorted_iter(column.iter().cloned())
+        });
+    }
+}
@@ -0,0 +1,45 @@
+//! This module implements running aggregates of an iterator, after
+//! Python's `itertools.accumulate`.
+//!
+//! Where `Iterator::fold` only returns the final aggregate, `accumulate`
+//! keeps every intermediate one, starting with the initial value, so a
+//! stream of `n` events gives a cumulative series of `n + 1` points, such
+//! as running totals or a running maximum.
+//!
+/// Returns the running aggregates of the elements of an iterator, the
+/// initial value first and then every aggregate `f` gives from the
+/// previous one and the next element.
+///
+/// # Arguments
+/// * iter - The elements aggregated
+/// * init - The initial aggregate
+/// * f - A function giving the next aggregate from the previous one and an
+///   element
+///
+/// # Examples
+/// ```
+/// # use collectors::accumulate;
+/// // Balance of an account after every transaction.
+/// let transactions = vec![100, -30, 45, -80];
+/// let balances = accumulate(transactions, 0, |balance, amount| balance + amount);
+/// assert_eq!(balances, vec![0, 100, 70, 115, 35]);
+///
+/// // Highest temperature seen so far.
+/// let temperatures = vec![12.5, 14.0, 13.2, 17.8, 16.1];
+/// let highest = accumulate(temperatures, f64::MIN, |max: &f64, t| max.max(t));
+/// assert_eq!(&highest[1..], &[12.5, 14.0, 14.0, 17.8, 17.8]);
+/// ```
+pub fn accumulate<I, A, F>(iter: I, init: A, mut f: F) -> Vec<A>
+where
+    I: IntoIterator,
+    F: FnMut(&A, I::Item) -> A,
+{
+    let iter = iter.into_iter();
+    let mut aggregates: Vec<A> = Vec::with_capacity(iter.size_hint().0 + 1);
+    aggregates.push(init);
+    for elem in iter {
+        let next = f(aggregates.last().expect("the initial value is kept"), elem);
+        aggregates.push(next);
+    }
+    aggregates
+}
@@ -0,0 +1,263 @@
+//! This module implements a `Counter` whose counts are atomic integers.
+//!
+//! Elements are stored in an append-only hash trie: every node holds at
+//! most one element and sixteen children, picked by the next four bits of
+//! the hash of the element, and no node is ever removed before the whole
+//! `AtomicCounter` is dropped. Every cell of the trie is a `OnceLock`, so
+//! once set it is read with an atomic load alone. Incrementing an element
+//! which is already counted then walks initialized cells and does an
+//! atomic increment, without taking any lock, whatever other threads are
+//! doing. Only inserting a new element initializes cells, which briefly
+//! blocks the threads initializing the same cell.
+//!
+use crate::Counter;
+use std::borrow::Borrow;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+/// The number of bits of the hash picking a child.
+const BITS_PER_LEVEL: u32 = 4;
+
+/// The number of levels a 64 bits hash picks children for.
+const LEVELS_PER_HASH: usize = (u64::BITS / BITS_PER_LEVEL) as usize;
+
+#[derive(Debug)]
+struct Node<T> {
+    entry: OnceLock<(T, AtomicU64)>,
+    children: [OnceLock<Box<Node<T>>>; 1 << BITS_PER_LEVEL],
+}
+
+impl<T> Node<T> {
+    fn new() -> Self {
+        Node {
+            entry: OnceLock::new(),
+            children: std::array::from_fn(|_| OnceLock::new()),
+        }
+    }
+}
+
+/// Returns the index of the child of the node at `level` on the path of
+/// an element, rehashing the element with a new seed once the bits of its
+/// hash are used up, so that elements whose hashes collide still part.
+fn child_index<Q: Hash + ?Sized>(elem: &Q, level: usize, hash: &mut u64) -> usize {
+    let shift = level % LEVELS_PER_HASH;
+    if shift == 0 {
+        let mut hasher = DefaultHasher::new();
+        (level / LEVELS_PER_HASH).hash(&mut hasher);
+        elem.hash(&mut hasher);
+        *hash = hasher.finish();
+    }
+    ((*hash >> (shift as u32 * BITS_PER_LEVEL)) & ((1 << BITS_PER_LEVEL) - 1)) as usize
+}
+
+/// Structure that count occurences of `T` elements with atomic counts
+#[derive(Debug)]
+pub struct AtomicCounter<T> {
+    root: Node<T>,
+    len: AtomicUsize,
+}
+
+impl<T: Ord + Hash> AtomicCounter<T> {
+    /// Create a new empty `AtomicCounter`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::AtomicCounter;
+    /// let counter: AtomicCounter<&str> = AtomicCounter::new();
+    /// # assert_eq!(counter.len(), 0);
+    /// ```
+    pub fn new() -> Self {
+        AtomicCounter {
+            root: Node::new(),
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of elements in the map.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::AtomicCounter;
+    /// let counter: AtomicCounter<&str> = AtomicCounter::new();
+    /// counter.update("GET");
+    /// counter.update("GET");
+    /// assert_eq!(counter.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the `AtomicCounter` is empty, `false` otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::AtomicCounter;
+    /// let counter: AtomicCounter<&str> = AtomicCounter::new();
+    /// assert_eq!(counter.is_empty(), true);
+    /// counter.update("GET");
+    /// assert_eq!(counter.is_empty(), false);
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Update the `AtomicCounter` with a value.
+    ///
+    /// Counts wrap around on overflow.
+    ///
+    /// # Arguments
+    /// * elem - A value used to update the `AtomicCounter`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::AtomicCounter;
+    /// let counter: AtomicCounter<u16> = AtomicCounter::new();
+    /// std::thread::scope(|scope| {
+    ///     for _ in 0..4 {
+    ///         scope.spawn(|| {
+    ///             for _ in 0..1000 {
+    ///                 counter.update(200);
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    /// assert_eq!(counter.get(&200), 4000);
+    /// ```
+    pub fn update(&self, mut elem: T) {
+        let mut node = &self.root;
+        let mut hash: u64 = 0;
+        let mut level = 0;
+        loop {
+            let (key, count) = match node.entry.get() {
+                Some(entry) => entry,
+                None => {
+                    let mut pending = Some(elem);
+                    let entry = node.entry.get_or_init(|| {
+                        let elem = pending.take().expect("the element is pending");
+                        (elem, AtomicU64::new(0))
+                    });
+                    match pending {
+                        Some(other) => {
+                            elem = other;
+                            entry
+                        }
+                        None => {
+                            let _ = self.len.fetch_add(1, Ordering::Relaxed);
+                            let _ = entry.1.fetch_add(1, Ordering::Relaxed);
+                            return;
+                        }
+                    }
+                }
+            };
+            if *key == elem {
+                let _ = count.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+            let child = &node.children[child_index(&elem, level, &mut hash)];
+            node = child.get_or_init(|| Box::new(Node::new()));
+            level += 1;
+        }
+    }
+
+    /// Returns the count of an element, missing elements are counted as 0.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element to look up
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::AtomicCounter;
+    /// let counter: AtomicCounter<String> = AtomicCounter::new();
+    /// counter.update(String::from("GET"));
+    /// assert_eq!(counter.get("GET"), 1);
+    /// assert_eq!(counter.get("POST"), 0);
+    /// ```
+    pub fn get<Q>(&self, elem: &Q) -> u64
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut node = &self.root;
+        let mut hash: u64 = 0;
+        let mut level = 0;
+        // An element is stored in the first free node of its path.
+        while let Some((key, count)) = node.entry.get() {
+            if key.borrow() == elem {
+                return count.load(Ordering::Relaxed);
+            }
+            match node.children[child_index(elem, level, &mut hash)].get() {
+                Some(child) => node = child,
+                None => break,
+            }
+            level += 1;
+        }
+        0
+    }
+
+    /// Copy the current counts into a new `Counter`, without consuming
+    /// the `AtomicCounter`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::AtomicCounter;
+    /// let counter: AtomicCounter<char> = AtomicCounter::new();
+    /// counter.update('a');
+    /// let snapshot = counter.snapshot();
+    /// counter.update('a');
+    /// assert_eq!(snapshot[&'a'], 1);
+    /// assert_eq!(counter.get(&'a'), 2);
+    /// ```
+    pub fn snapshot(&self) -> Counter<T>
+    where
+        T: Clone,
+    {
+        let mut counter: Counter<T> = Counter::new();
+        let mut stack: Vec<&Node<T>> = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            if let Some((key, count)) = node.entry.get() {
+                counter.extend(Some((
+                    key.clone(),
+                    u128::from(count.load(Ordering::Relaxed)),
+                )));
+            }
+            stack.extend(
+                node.children
+                    .iter()
+                    .filter_map(|child| child.get().map(|child| &**child)),
+            );
+        }
+        counter
+    }
+
+    /// Convert into a `Counter`, consuming the `AtomicCounter`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::AtomicCounter;
+    /// let counter: AtomicCounter<char> = AtomicCounter::new();
+    /// counter.update('a');
+    /// assert_eq!(counter.into_counter()[&'a'], 1);
+    /// ```
+    pub fn into_counter(self) -> Counter<T> {
+        let mut counter: Counter<T> = Counter::new();
+        let mut stack: Vec<Node<T>> = vec![self.root];
+        while let Some(node) = stack.pop() {
+            if let Some((key, count)) = node.entry.into_inner() {
+                counter.extend(Some((key, u128::from(count.into_inner()))));
+            }
+            stack.extend(
+                IntoIterator::into_iter(node.children)
+                    .filter_map(|child| child.into_inner().map(|child| *child)),
+            );
+        }
+        counter
+    }
+}
+
+impl<T: Ord + Hash> Default for AtomicCounter<T> {
+    fn default() -> Self {
+        AtomicCounter::new()
+    }
+}
@@ -0,0 +1,176 @@
+//! This module implements a compact binary snapshot format for `Counter`.
+//!
+//! A snapshot starts with the `CNTR` magic bytes, a format version and the
+//! overflow policy, followed by the number of elements. Each element is
+//! then stored as the length of its string form, that string in UTF-8, and
+//! its count. Lengths and counts are LEB128 variable length integers, so
+//! small counts only take a byte.
+//!
+use crate::{Counter, OverflowPolicy};
+use std::fmt::Display;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::str::FromStr;
+
+const MAGIC: &[u8; 4] = b"CNTR";
+const VERSION: u8 = 1;
+
+impl<T: Ord + Display> Counter<T> {
+    /// Write a binary snapshot of the `Counter`.
+    ///
+    /// # Arguments
+    /// * writer - Where the snapshot is written
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<char> = "abracadabra".chars().collect();
+    /// let mut snapshot: Vec<u8> = Vec::new();
+    /// counter.save(&mut snapshot).unwrap();
+    /// assert_eq!(snapshot.len(), 22);
+    /// ```
+    pub fn save<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = BufWriter::new(writer);
+        writer.write_all(MAGIC)?;
+        let policy = match self.overflow_policy() {
+            OverflowPolicy::Checked => 0,
+            OverflowPolicy::Saturating => 1,
+            OverflowPolicy::Wrapping => 2,
+        };
+        writer.write_all(&[VERSION, policy])?;
+        write_varint(&mut writer, self.len() as u128)?;
+        for (key, count) in self.iter() {
+            write_entry(&mut writer, key, *count)?;
+        }
+        writer.flush()
+    }
+}
+
+impl<T: Ord + FromStr> Counter<T> {
+    /// Create a new `Counter` from a binary snapshot written by `save`.
+    ///
+    /// # Arguments
+    /// * reader - Where the snapshot is read from
+    ///
+    /// # Errors
+    /// An error of kind `InvalidData` is returned when the snapshot is
+    /// malformed, when a key can't be parsed or when the counts of a
+    /// repeated key overflow, and of kind `UnexpectedEof` when it is
+    /// truncated.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, OverflowPolicy};
+    /// let mut counter: Counter<String> = Counter::with_overflow_policy(OverflowPolicy::Saturating);
+    /// counter["GET /"] += 1_000_000;
+    /// counter["POST /login"] += 3;
+    /// let mut snapshot: Vec<u8> = Vec::new();
+    /// counter.save(&mut snapshot).unwrap();
+    ///
+    /// let restored: Counter<String> = Counter::load(snapshot.as_slice()).unwrap();
+    /// assert_eq!(restored, counter);
+    /// assert_eq!(restored.overflow_policy(), OverflowPolicy::Saturating);
+    ///
+    /// assert!(Counter::<String>::load(&snapshot[..snapshot.len() - 1]).is_err());
+    /// assert!(Counter::<String>::load("not a snapshot".as_bytes()).is_err());
+    ///
+    /// // A corrupt snapshot repeating a key whose counts overflow.
+    /// let mut corrupt: Vec<u8> = b"CNTR\x01\x00\x02".to_vec();
+    /// for count in vec![u128::MAX, 1] {
+    ///     let mut single = Counter::new();
+    ///     single["a"] = count;
+    ///     let mut entry: Vec<u8> = Vec::new();
+    ///     single.save(&mut entry).unwrap();
+    ///     corrupt.extend_from_slice(&entry[7..]);
+    /// }
+    /// let error = Counter::<String>::load(corrupt.as_slice()).unwrap_err();
+    /// assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    /// ```
+    pub fn load<R: Read>(reader: R) -> io::Result<Self> {
+        let mut reader = BufReader::new(reader);
+        let mut header = [0; 6];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid_data("not a counter snapshot"));
+        }
+        if header[4] != VERSION {
+            return Err(invalid_data("unsupported snapshot version"));
+        }
+        let policy = match header[5] {
+            0 => OverflowPolicy::Checked,
+            1 => OverflowPolicy::Saturating,
+            2 => OverflowPolicy::Wrapping,
+            _ => return Err(invalid_data("invalid overflow policy")),
+        };
+
+        let mut counter: Counter<T> = Counter::with_overflow_policy(policy);
+        let len = read_varint(&mut reader)?;
+        for _ in 0..len {
+            let (key, count) = read_entry(&mut reader)?;
+            counter
+                .try_add_count(key, count)
+                .map_err(|_| invalid_data("count overflow"))?;
+        }
+        Ok(counter)
+    }
+}
+
+/// Write an element, as the length of its string form, that string and
+/// its count.
+pub(crate) fn write_entry<W, T>(writer: &mut W, key: &T, count: u128) -> io::Result<()>
+where
+    W: Write,
+    T: Display + ?Sized,
+{
+    let key = key.to_string();
+    write_varint(writer, key.len() as u128)?;
+    writer.write_all(key.as_bytes())?;
+    write_varint(writer, count)
+}
+
+/// Read an element written by `write_entry`.
+pub(crate) fn read_entry<R: Read, T: FromStr>(reader: &mut R) -> io::Result<(T, u128)> {
+    let key_len = read_varint(reader)?;
+    let mut key = Vec::new();
+    let _ = reader.by_ref().take(key_len as u64).read_to_end(&mut key)?;
+    if key.len() as u128 != key_len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    let key = String::from_utf8(key).map_err(|_| invalid_data("invalid UTF-8 key"))?;
+    let key = key
+        .parse()
+        .map_err(|_| invalid_data(&format!("invalid key {:?}", key)))?;
+    let count = read_varint(reader)?;
+    Ok((key, count))
+}
+
+fn write_varint<W: Write>(writer: &mut W, mut value: u128) -> io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> io::Result<u128> {
+    let mut value: u128 = 0;
+    let mut shift = 0;
+    loop {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        if shift >= 128 || (shift == 126 && byte[0] & 0x7f > 0b11) {
+            return Err(invalid_data("integer overflow"));
+        }
+        value |= u128::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+pub(crate) fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
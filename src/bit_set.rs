@@ -0,0 +1,378 @@
+//! This module implements a set of small integers packed as bits.
+//!
+//! Element `i` is bit `i % 64` of word `i / 64`, words being added as larger
+//! elements are inserted, so the memory used is proportional to the largest
+//! element rather than to the number of elements.
+//!
+use crate::Bits;
+use std::iter::FromIterator;
+
+const WORD_BITS: usize = 64;
+
+/// Structure that hold a set of `usize` as a growable bit array
+#[derive(Debug, Clone, Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    /// Create a new empty `BitSet`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::BitSet;
+    /// let set = BitSet::new();
+    /// # assert!(set.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        BitSet { words: Vec::new() }
+    }
+
+    /// Create a new empty `BitSet` able to hold the elements below `bits`
+    /// without reallocating.
+    ///
+    /// # Arguments
+    /// * bits - The number of elements to reserve room for
+    pub fn with_capacity(bits: usize) -> Self {
+        BitSet {
+            words: Vec::with_capacity(bits.div_ceil(WORD_BITS)),
+        }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns `true` if the `BitSet` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Insert an element, returning `true` if it was absent.
+    ///
+    /// # Arguments
+    /// * elem - The element inserted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::BitSet;
+    /// let mut set = BitSet::new();
+    /// assert!(set.insert(130));
+    /// assert!(!set.insert(130));
+    /// assert!(set.contains(130));
+    /// assert!(!set.contains(129));
+    /// ```
+    pub fn insert(&mut self, elem: usize) -> bool {
+        let (word, mask) = (elem / WORD_BITS, 1 << (elem % WORD_BITS));
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let absent = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        absent
+    }
+
+    /// Remove an element, returning `true` if it was present.
+    ///
+    /// # Arguments
+    /// * elem - The element removed
+    pub fn remove(&mut self, elem: usize) -> bool {
+        let (word, mask) = (elem / WORD_BITS, 1 << (elem % WORD_BITS));
+        match self.words.get_mut(word) {
+            Some(bits) if *bits & mask != 0 => {
+                *bits &= !mask;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the element is present, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * elem - The element looked up
+    pub fn contains(&self, elem: usize) -> bool {
+        self.words
+            .get(elem / WORD_BITS)
+            .is_some_and(|word| word & (1 << (elem % WORD_BITS)) != 0)
+    }
+
+    /// Remove every element.
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+
+    /// Iterate over the elements, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(idx, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(idx * WORD_BITS + bit)
+            })
+        })
+    }
+
+    /// Returns the smallest element, `None` if empty.
+    pub fn min(&self) -> Option<usize> {
+        self.iter().next()
+    }
+
+    /// Returns the largest element, `None` if empty.
+    pub fn max(&self) -> Option<usize> {
+        let (idx, word) = self
+            .words
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, word)| **word != 0)?;
+        Some(idx * WORD_BITS + WORD_BITS - 1 - word.leading_zeros() as usize)
+    }
+
+    /// Returns the number of elements strictly smaller than `elem`.
+    ///
+    /// # Arguments
+    /// * elem - The upper bound, excluded
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::BitSet;
+    /// let set: BitSet = vec![3, 64, 70, 200].into_iter().collect();
+    /// assert_eq!(set.rank(0), 0);
+    /// assert_eq!(set.rank(70), 2);
+    /// assert_eq!(set.rank(71), 3);
+    /// assert_eq!(set.select(2), Some(70));
+    /// assert_eq!(set.select(4), None);
+    /// ```
+    pub fn rank(&self, elem: usize) -> usize {
+        let (word, bit) = (elem / WORD_BITS, elem % WORD_BITS);
+        let full: usize = self
+            .words
+            .iter()
+            .take(word)
+            .map(|word| word.count_ones() as usize)
+            .sum();
+        let partial = self
+            .words
+            .get(word)
+            .map_or(0, |word| (word & ((1 << bit) - 1)).count_ones() as usize);
+        full + partial
+    }
+
+    /// Returns the element of rank `rank`, the smallest being of rank 0,
+    /// `None` if there are not that many elements.
+    ///
+    /// # Arguments
+    /// * rank - The number of smaller elements
+    pub fn select(&self, rank: usize) -> Option<usize> {
+        let mut remaining = rank;
+        for (idx, word) in self.words.iter().enumerate() {
+            let ones = word.count_ones() as usize;
+            if remaining < ones {
+                let mut word = *word;
+                for _ in 0..remaining {
+                    word &= word - 1;
+                }
+                return Some(idx * WORD_BITS + word.trailing_zeros() as usize);
+            }
+            remaining -= ones;
+        }
+        None
+    }
+
+    /// Add the elements of `other` to the `BitSet`.
+    ///
+    /// # Arguments
+    /// * other - The `BitSet` whose elements are added
+    pub fn union_with(&mut self, other: &BitSet) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= other;
+        }
+    }
+
+    /// Keep only the elements also in `other`.
+    ///
+    /// # Arguments
+    /// * other - The `BitSet` whose elements are kept
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        self.words.truncate(other.words.len());
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word &= other;
+        }
+    }
+
+    /// Remove the elements of `other` from the `BitSet`.
+    ///
+    /// # Arguments
+    /// * other - The `BitSet` whose elements are removed
+    pub fn difference_with(&mut self, other: &BitSet) {
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word &= !other;
+        }
+    }
+
+    /// Keep only the elements in exactly one of the two sets.
+    ///
+    /// # Arguments
+    /// * other - The other `BitSet`
+    pub fn symmetric_difference_with(&mut self, other: &BitSet) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word ^= other;
+        }
+    }
+
+    /// Returns the elements in either set.
+    ///
+    /// # Arguments
+    /// * other - The other `BitSet`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::BitSet;
+    /// let a: BitSet = vec![1, 2, 100].into_iter().collect();
+    /// let b: BitSet = vec![2, 3].into_iter().collect();
+    /// assert_eq!(a.union(&b).iter().collect::<Vec<_>>(), vec![1, 2, 3, 100]);
+    /// assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), vec![2]);
+    /// assert_eq!(a.difference(&b).iter().collect::<Vec<_>>(), vec![1, 100]);
+    /// assert_eq!(a.symmetric_difference(&b).iter().collect::<Vec<_>>(), vec![1, 3, 100]);
+    /// ```
+    pub fn union(&self, other: &BitSet) -> BitSet {
+        let mut set = self.clone();
+        set.union_with(other);
+        set
+    }
+
+    /// Returns the elements in both sets.
+    ///
+    /// # Arguments
+    /// * other - The other `BitSet`
+    pub fn intersection(&self, other: &BitSet) -> BitSet {
+        let mut set = self.clone();
+        set.intersect_with(other);
+        set
+    }
+
+    /// Returns the elements not in `other`.
+    ///
+    /// # Arguments
+    /// * other - The other `BitSet`
+    pub fn difference(&self, other: &BitSet) -> BitSet {
+        let mut set = self.clone();
+        set.difference_with(other);
+        set
+    }
+
+    /// Returns the elements in exactly one of the two sets.
+    ///
+    /// # Arguments
+    /// * other - The other `BitSet`
+    pub fn symmetric_difference(&self, other: &BitSet) -> BitSet {
+        let mut set = self.clone();
+        set.symmetric_difference_with(other);
+        set
+    }
+
+    /// Returns `true` if every element is also in `other`, `false`
+    /// otherwise.
+    ///
+    /// # Arguments
+    /// * other - The other `BitSet`
+    pub fn is_subset(&self, other: &BitSet) -> bool {
+        self.words
+            .iter()
+            .enumerate()
+            .all(|(idx, word)| word & !other.words.get(idx).cloned().unwrap_or(0) == 0)
+    }
+
+    /// Returns `true` if the two sets have no element in common, `false`
+    /// otherwise.
+    ///
+    /// # Arguments
+    /// * other - The other `BitSet`
+    pub fn is_disjoint(&self, other: &BitSet) -> bool {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .all(|(word, other)| word & other == 0)
+    }
+
+    /// Convert into a bit stream whose bit `i` is set if `i` is an element,
+    /// padded with unset bits to a whole number of bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{BitSet, Bits};
+    /// let set: BitSet = vec![0, 3, 9].into_iter().collect();
+    /// let bits = set.to_bits();
+    /// assert_eq!(bits.to_string(), "10010000|01000000");
+    /// assert_eq!(BitSet::from(&bits), set);
+    /// ```
+    pub fn to_bits(&self) -> Bits {
+        let len = self.max().map_or(0, |max| max / 8 + 1);
+        let bytes: Vec<u8> = (0..len)
+            .map(|byte| {
+                let word = self.words[byte * 8 / WORD_BITS];
+                (word >> (byte * 8 % WORD_BITS)) as u8
+            })
+            .collect();
+        Bits::from_u8_little_endian(&bytes)
+    }
+}
+
+impl From<&Bits> for BitSet {
+    /// Create a new `BitSet` holding the positions of the set bits of a
+    /// bit stream.
+    fn from(bits: &Bits) -> Self {
+        bits.as_vec_bool()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, bit)| *bit)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = BitSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for elem in iter {
+            let _ = self.insert(elem);
+        }
+    }
+}
+
+/// Two `BitSet` are equal if they hold the same elements, whatever their
+/// capacity.
+impl PartialEq for BitSet {
+    fn eq(&self, other: &Self) -> bool {
+        let (short, long) = if self.words.len() <= other.words.len() {
+            (&self.words, &other.words)
+        } else {
+            (&other.words, &self.words)
+        };
+        short[..] == long[..short.len()] && long[short.len()..].iter().all(|word| *word == 0)
+    }
+}
+
+impl Eq for BitSet {}
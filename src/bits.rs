@@ -3,12 +3,12 @@
 //! the bit stream is encoded as a `String`
 //! instead of `Vec<bool>`.
 
-use std::fmt::Binary;
+use std::fmt::{self, Binary, Display, Formatter};
 use std::mem::size_of;
 use std::num::ParseIntError;
 
 /// Indicate the endianness of the bit stream.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Endianness {
     /// Big endian: most significant byte first
     BigEndian,
@@ -18,7 +18,7 @@ pub enum Endianness {
 }
 
 /// The structure owning the bit stream
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Bits {
     bits: String,
     delimiter: char,
@@ -47,7 +47,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:08b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:08b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -68,19 +68,14 @@ impl Bits {
     /// let bits_from_vec = Bits::from_u8_little_endian(&u8_vec);
     /// let bits_from_arr = Bits::from_u8_little_endian(&u8_arr);
     ///
-    /// assert_eq!(&bits_from_vec.to_string(), "00000000|00000001|00000010|00000011");
+    /// assert_eq!(&bits_from_vec.to_string(), "00000000|10000000|01000000|11000000");
     /// assert_eq!(&bits_from_arr.to_string(), "00000000|10000000|01000000|11000000");
     /// ```
     pub fn from_u8_little_endian(data: &[u8]) -> Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:08b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:08b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -92,7 +87,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:016b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:016b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -104,12 +99,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:016b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:016b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -121,7 +111,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:032b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:032b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -133,12 +123,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:032b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:032b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -150,7 +135,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:064b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:064b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -162,12 +147,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:064b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:064b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -179,7 +159,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:0128b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:0128b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -191,12 +171,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:0128b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:0128b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -210,7 +185,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:08b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:08b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -223,12 +198,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:08b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:08b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -242,7 +212,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:016b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:016b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -255,12 +225,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:016b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:016b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -274,7 +239,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:032b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:032b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -287,12 +252,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:032b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:032b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -305,7 +265,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:064b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:064b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -318,12 +278,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:064b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:064b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -337,7 +292,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:0128b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:0128b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -350,12 +305,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:0128b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:0128b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -367,7 +317,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:08b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:08b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -379,12 +329,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:08b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:08b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -396,7 +341,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:016b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:016b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -408,12 +353,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:016b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:016b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -425,7 +365,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:032b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:032b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -437,12 +377,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:032b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:032b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -454,7 +389,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:064b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:064b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -466,12 +401,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:064b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:064b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -483,7 +413,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:0128b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:0128b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -495,12 +425,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:0128b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:0128b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -514,7 +439,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:08b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:08b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -527,12 +452,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:08b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:08b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -546,7 +466,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:016b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:016b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -559,12 +479,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:016b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:016b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -578,7 +493,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:032b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:032b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -591,12 +506,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:032b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:032b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -609,7 +519,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:064b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:064b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -622,12 +532,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:064b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:064b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -641,7 +546,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| format!("{}", format!("{:0128b}", *b).chars().collect::<String>()))
+                .map(|b| format!("{:0128b}", *b))
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -654,12 +559,7 @@ impl Bits {
         Bits {
             bits: data
                 .iter()
-                .map(|b| {
-                    format!(
-                        "{}",
-                        format!("{:0128b}", *b).chars().rev().collect::<String>()
-                    )
-                })
+                .map(|b| format!("{:0128b}", *b).chars().rev().collect::<String>())
                 .collect::<Vec<String>>()
                 .join("|"),
             delimiter: '|',
@@ -1281,8 +1181,8 @@ impl Bits {
     }
 }
 
-impl ToString for Bits {
-    fn to_string(&self) -> String {
-        format!("{}", self.bits)
+impl Display for Bits {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.bits)
     }
 }
@@ -0,0 +1,151 @@
+//! This module implements a `Counter` holding a bounded number of elements.
+//!
+//! Whenever more than `max_keys` elements are counted, the least counted
+//! ones are evicted until only half of the budget is used, so the memory
+//! stays bounded over unbounded key spaces and evictions are amortized.
+//!
+//! Counts are only ever underestimated: an evicted element starts again
+//! from 0 when it comes back. The sum of the evicted counts, returned by
+//! `evicted`, bounds that underestimation for every element, including
+//! the ones no longer held.
+//!
+use crate::Counter;
+use std::borrow::Borrow;
+use std::cmp::Reverse;
+use std::ops::Index;
+
+/// Structure that count occurences of at most `max_keys` `T` elements
+#[derive(Debug, Clone)]
+pub struct BoundedCounter<T> {
+    counter: Counter<T>,
+    max_keys: usize,
+    evicted: u128,
+}
+
+impl<T: Ord> BoundedCounter<T> {
+    /// Create a new empty `BoundedCounter`.
+    ///
+    /// # Arguments
+    /// * max_keys - The maximum number of elements held, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::BoundedCounter;
+    /// let counter: BoundedCounter<String> = BoundedCounter::new(10_000);
+    /// # assert_eq!(counter.max_keys(), 10_000);
+    /// ```
+    pub fn new(max_keys: usize) -> Self {
+        assert!(max_keys > 0);
+        BoundedCounter {
+            counter: Counter::new(),
+            max_keys,
+            evicted: 0,
+        }
+    }
+
+    /// Returns the maximum number of elements held.
+    pub fn max_keys(&self) -> usize {
+        self.max_keys
+    }
+
+    /// Returns the number of elements currently held.
+    pub fn len(&self) -> usize {
+        self.counter.len()
+    }
+
+    /// Returns `true` if no element is held, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.counter.is_empty()
+    }
+
+    /// Returns the sum of the counts evicted so far, the maximum amount by
+    /// which the count of any element is underestimated.
+    pub fn evicted(&self) -> u128 {
+        self.evicted
+    }
+
+    /// Update the `BoundedCounter` with a value, evicting the least counted
+    /// elements if the budget is exceeded.
+    ///
+    /// # Arguments
+    /// * elem - A value used to update the `BoundedCounter`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::BoundedCounter;
+    /// let mut counter: BoundedCounter<&str> = BoundedCounter::new(4);
+    /// for url in vec!["/", "/", "/", "/a", "/b", "/", "/c", "/d"] {
+    ///     counter.update_from_value(url);
+    /// }
+    /// // Adding "/d" exceeded the budget, only the 2 most counted were kept.
+    /// assert_eq!(counter.len(), 2);
+    /// assert_eq!(counter[&"/"], 4);
+    /// assert_eq!(counter.evicted(), 3);
+    /// ```
+    pub fn update_from_value(&mut self, elem: T) {
+        self.counter.update_from_value(elem);
+        if self.counter.len() > self.max_keys {
+            self.evict();
+        }
+    }
+
+    /// Update the `BoundedCounter` with an iterator.
+    ///
+    /// # Arguments
+    /// * iter - An iterator used to update the `BoundedCounter`
+    pub fn update_from_iter<I>(&mut self, iter: I)
+    where
+        I: Iterator<Item = T>,
+    {
+        for elem in iter {
+            self.update_from_value(elem);
+        }
+    }
+
+    /// Returns the counts of the elements held.
+    pub fn counter(&self) -> &Counter<T> {
+        &self.counter
+    }
+
+    /// Convert the `BoundedCounter` into the `Counter` of the elements held.
+    pub fn into_counter(self) -> Counter<T> {
+        self.counter
+    }
+
+    /// Evict the least counted elements until half of the budget is used,
+    /// ties being evicted in decreasing key order.
+    fn evict(&mut self) {
+        let kept = (self.max_keys / 2).max(1);
+        let mut counts: Vec<u128> = self.counter.iter().map(|(_, count)| *count).collect();
+        counts.sort_by_key(|count| Reverse(*count));
+        let cutoff = counts[kept - 1];
+        let mut kept_ties = kept - counts.iter().filter(|count| **count > cutoff).count();
+
+        let mut evicted: u128 = 0;
+        self.counter.retain(|_, count| {
+            let keep = if count == cutoff && kept_ties > 0 {
+                kept_ties -= 1;
+                true
+            } else {
+                count > cutoff
+            };
+            if !keep {
+                evicted = evicted.saturating_add(count);
+            }
+            keep
+        });
+        self.evicted = self.evicted.saturating_add(evicted);
+    }
+}
+
+impl<T, Q> Index<&Q> for BoundedCounter<T>
+where
+    T: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = u128;
+
+    fn index(&self, index: &Q) -> &Self::Output {
+        &self.counter[index]
+    }
+}
@@ -0,0 +1,207 @@
+//! This module implements a `Counter` split in time buckets.
+//!
+//! Timestamps are plain `u64` values in any unit (seconds, milliseconds,
+//! ...). Each bucket covers `bucket_width` consecutive timestamps and only
+//! the `retention` most recent buckets are kept, older ones are dropped
+//! as newer ones are created.
+//!
+use crate::Counter;
+use std::collections::btree_map::{BTreeMap, Iter};
+use std::ops::RangeBounds;
+
+/// Structure that count occurences of `T` elements per time bucket
+#[derive(Debug, Clone)]
+pub struct BucketedCounter<T> {
+    buckets: BTreeMap<u64, Counter<T>>,
+    bucket_width: u64,
+    retention: usize,
+}
+
+impl<T: Ord> BucketedCounter<T> {
+    /// Create a new empty `BucketedCounter`.
+    ///
+    /// # Arguments
+    /// * bucket_width - The number of timestamps covered by a bucket, must be greater than 0
+    /// * retention - The maximum number of buckets kept, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::BucketedCounter;
+    /// // One bucket per minute, keeping the last hour.
+    /// let counter: BucketedCounter<&str> = BucketedCounter::new(60, 60);
+    /// # assert_eq!(counter.len(), 0);
+    /// ```
+    pub fn new(bucket_width: u64, retention: usize) -> Self {
+        assert!(bucket_width > 0);
+        assert!(retention > 0);
+        BucketedCounter {
+            buckets: BTreeMap::new(),
+            bucket_width,
+            retention,
+        }
+    }
+
+    /// Returns the number of timestamps covered by a bucket.
+    pub fn bucket_width(&self) -> u64 {
+        self.bucket_width
+    }
+
+    /// Returns the maximum number of buckets kept.
+    pub fn retention(&self) -> usize {
+        self.retention
+    }
+
+    /// Returns the number of buckets currently kept.
+    pub fn len(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Returns `true` if no bucket is kept, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// Returns the start of the bucket containing `timestamp`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::BucketedCounter;
+    /// let counter: BucketedCounter<&str> = BucketedCounter::new(60, 60);
+    /// assert_eq!(counter.bucket_start(125), 120);
+    /// ```
+    pub fn bucket_start(&self, timestamp: u64) -> u64 {
+        timestamp - timestamp % self.bucket_width
+    }
+
+    /// Count an element in the bucket containing `timestamp`.
+    ///
+    /// Elements whose bucket is older than the retained ones are ignored.
+    ///
+    /// # Arguments
+    /// * timestamp - When the element occured
+    /// * elem - A value used to update the `BucketedCounter`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::BucketedCounter;
+    /// let mut counter: BucketedCounter<&str> = BucketedCounter::new(10, 2);
+    /// counter.incr_at(1, "GET");
+    /// counter.incr_at(5, "GET");
+    /// counter.incr_at(12, "POST");
+    /// assert_eq!(counter.bucket(0).unwrap()[&"GET"], 2);
+    /// assert_eq!(counter.bucket(15).unwrap()[&"POST"], 1);
+    ///
+    /// // Creating a third bucket drops the oldest one.
+    /// counter.incr_at(25, "GET");
+    /// assert!(counter.bucket(0).is_none());
+    /// assert_eq!(counter.len(), 2);
+    /// ```
+    pub fn incr_at(&mut self, timestamp: u64, elem: T) {
+        let start = self.bucket_start(timestamp);
+        if let Some(oldest) = self.oldest_kept_start() {
+            if self.buckets.len() == self.retention && start < oldest {
+                return;
+            }
+        }
+
+        self.buckets
+            .entry(start)
+            .or_default()
+            .update_from_value(elem);
+        while self.buckets.len() > self.retention {
+            let _ = self.buckets.pop_first();
+        }
+    }
+
+    /// Drop every bucket that is out of the retention window ending at `now`.
+    ///
+    /// # Arguments
+    /// * now - The current timestamp
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::BucketedCounter;
+    /// let mut counter: BucketedCounter<&str> = BucketedCounter::new(10, 3);
+    /// counter.incr_at(0, "GET");
+    /// counter.incr_at(10, "GET");
+    /// counter.rotate(35);
+    /// assert_eq!(counter.len(), 1);
+    /// assert!(counter.bucket(10).is_some());
+    /// ```
+    pub fn rotate(&mut self, now: u64) {
+        let window = self.bucket_width.saturating_mul(self.retention as u64 - 1);
+        let min_start = self.bucket_start(now).saturating_sub(window);
+        self.buckets = self.buckets.split_off(&min_start);
+    }
+
+    /// Returns the `Counter` of the bucket containing `timestamp`, if any.
+    ///
+    /// # Arguments
+    /// * timestamp - Any timestamp covered by the bucket
+    pub fn bucket(&self, timestamp: u64) -> Option<&Counter<T>> {
+        self.buckets.get(&self.bucket_start(timestamp))
+    }
+
+    /// Iterate over the start of each bucket and its `Counter`, oldest first.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::BucketedCounter;
+    /// let mut counter: BucketedCounter<&str> = BucketedCounter::new(10, 5);
+    /// counter.incr_at(3, "GET");
+    /// counter.incr_at(27, "GET");
+    /// let starts: Vec<u64> = counter.buckets().map(|(start, _)| *start).collect();
+    /// assert_eq!(starts, vec![0, 20]);
+    /// ```
+    pub fn buckets(&self) -> Iter<'_, u64, Counter<T>> {
+        self.buckets.iter()
+    }
+
+    /// Merge every kept bucket into a new `Counter`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::BucketedCounter;
+    /// let mut counter: BucketedCounter<&str> = BucketedCounter::new(10, 5);
+    /// counter.incr_at(3, "GET");
+    /// counter.incr_at(27, "GET");
+    /// assert_eq!(counter.rolled_up()[&"GET"], 2);
+    /// ```
+    pub fn rolled_up(&self) -> Counter<T>
+    where
+        T: Clone,
+    {
+        self.rolled_up_range(..)
+    }
+
+    /// Merge the buckets whose start is within `range` into a new `Counter`.
+    ///
+    /// # Arguments
+    /// * range - The range of bucket starts to merge
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::BucketedCounter;
+    /// let mut counter: BucketedCounter<&str> = BucketedCounter::new(10, 5);
+    /// counter.incr_at(3, "GET");
+    /// counter.incr_at(15, "GET");
+    /// counter.incr_at(27, "GET");
+    /// assert_eq!(counter.rolled_up_range(10..)[&"GET"], 2);
+    /// assert_eq!(counter.rolled_up_range(..20)[&"GET"], 2);
+    /// ```
+    pub fn rolled_up_range<R>(&self, range: R) -> Counter<T>
+    where
+        T: Clone,
+        R: RangeBounds<u64>,
+    {
+        let mut counter: Counter<T> = Counter::new();
+        for (_, bucket) in self.buckets.range(range) {
+            counter.update_from_counter(bucket);
+        }
+        counter
+    }
+
+    fn oldest_kept_start(&self) -> Option<u64> {
+        self.buckets.keys().next().cloned()
+    }
+}
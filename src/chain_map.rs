@@ -0,0 +1,225 @@
+//! This module implements a map looking keys up through a chain of maps.
+//!
+//! As with python `ChainMap`, lookups go through the maps in order and the
+//! first map holding the key wins, while writes and removals only ever
+//! touch the first map. `new_child` and `parents` push and pop the first
+//! map, which models nested scopes or layered configuration.
+//!
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::ops::Index;
+
+/// Structure that look `K` keys up through a chain of maps of `V` values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainMap<K, V> {
+    maps: Vec<BTreeMap<K, V>>,
+}
+
+impl<K: Ord, V> ChainMap<K, V> {
+    /// Create a new `ChainMap` holding a single empty map.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ChainMap;
+    /// let chain: ChainMap<&str, u32> = ChainMap::new();
+    /// # assert_eq!(chain.maps().len(), 1);
+    /// ```
+    pub fn new() -> Self {
+        ChainMap {
+            maps: vec![BTreeMap::new()],
+        }
+    }
+
+    /// Create a new `ChainMap` from maps, looked up in order.
+    ///
+    /// # Arguments
+    /// * maps - The maps of the chain, an empty map is used if there is none
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ChainMap;
+    /// # use std::collections::BTreeMap;
+    /// let defaults: BTreeMap<&str, &str> = vec![("color", "red"), ("user", "guest")].into_iter().collect();
+    /// let cli: BTreeMap<&str, &str> = vec![("user", "admin")].into_iter().collect();
+    /// let config = ChainMap::from_maps(vec![cli, defaults]);
+    /// assert_eq!(config[&"user"], "admin");
+    /// assert_eq!(config[&"color"], "red");
+    /// ```
+    pub fn from_maps(maps: Vec<BTreeMap<K, V>>) -> Self {
+        if maps.is_empty() {
+            return ChainMap::new();
+        }
+        ChainMap { maps }
+    }
+
+    /// Returns the maps of the chain, in lookup order.
+    pub fn maps(&self) -> &[BTreeMap<K, V>] {
+        &self.maps
+    }
+
+    /// Returns the maps of the chain mutably, in lookup order.
+    pub fn maps_mut(&mut self) -> &mut [BTreeMap<K, V>] {
+        &mut self.maps
+    }
+
+    /// Returns the number of distinct keys across the maps.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns `true` if no map holds a key, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.maps.iter().all(BTreeMap::is_empty)
+    }
+
+    /// Returns the value of a key in the first map holding it, `None` if no
+    /// map does.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.maps.iter().find_map(|map| map.get(key))
+    }
+
+    /// Returns a mutable reference to the value of a key in the first map
+    /// holding it, `None` if no map does.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.maps.iter_mut().find_map(|map| map.get_mut(key))
+    }
+
+    /// Returns `true` if any map holds the key, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.maps.iter().any(|map| map.contains_key(key))
+    }
+
+    /// Insert a value in the first map, returning its previous value there.
+    ///
+    /// # Arguments
+    /// * key - The key of the value
+    /// * value - The value inserted
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.maps[0].insert(key, value)
+    }
+
+    /// Remove a key from the first map, returning its value there.
+    ///
+    /// The key may still be found in the following maps.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.maps[0].remove(key)
+    }
+
+    /// Returns the chain with a new empty map in front, receiving the writes.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ChainMap;
+    /// let mut scopes: ChainMap<&str, i32> = ChainMap::new();
+    /// let _ = scopes.insert("x", 1);
+    ///
+    /// scopes = scopes.new_child();
+    /// let _ = scopes.insert("x", 2);
+    /// let _ = scopes.insert("y", 3);
+    /// assert_eq!(scopes[&"x"], 2);
+    ///
+    /// scopes = scopes.parents();
+    /// assert_eq!(scopes[&"x"], 1);
+    /// assert!(!scopes.contains_key(&"y"));
+    /// ```
+    pub fn new_child(self) -> Self {
+        self.new_child_with(BTreeMap::new())
+    }
+
+    /// Returns the chain with `map` in front, receiving the writes.
+    ///
+    /// # Arguments
+    /// * map - The map looked up first
+    pub fn new_child_with(mut self, map: BTreeMap<K, V>) -> Self {
+        self.maps.insert(0, map);
+        self
+    }
+
+    /// Returns the chain without its first map, a single empty map if it was
+    /// the only one.
+    pub fn parents(mut self) -> Self {
+        let _ = self.maps.remove(0);
+        ChainMap::from_maps(self.maps)
+    }
+
+    /// Iterate over the distinct keys and the value found by a lookup, in
+    /// key order.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ChainMap;
+    /// # use std::collections::BTreeMap;
+    /// let local: BTreeMap<&str, i32> = vec![("b", 20)].into_iter().collect();
+    /// let global: BTreeMap<&str, i32> = vec![("a", 1), ("b", 2)].into_iter().collect();
+    /// let chain = ChainMap::from_maps(vec![local, global]);
+    /// assert_eq!(chain.iter().collect::<Vec<_>>(), vec![(&"a", &1), (&"b", &20)]);
+    /// assert_eq!(chain.len(), 2);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        let mut visible: BTreeMap<&K, &V> = BTreeMap::new();
+        for map in self.maps.iter().rev() {
+            visible.extend(map.iter());
+        }
+        visible.into_iter()
+    }
+
+    /// Merge the maps into a single map holding the value found by a lookup
+    /// for every key.
+    pub fn flatten(self) -> BTreeMap<K, V> {
+        let mut flat: BTreeMap<K, V> = BTreeMap::new();
+        for map in self.maps.into_iter().rev() {
+            flat.extend(map);
+        }
+        flat
+    }
+}
+
+impl<K, V, Q> Index<&Q> for ChainMap<K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    /// Returns the value of a key in the first map holding it.
+    ///
+    /// # Panics
+    /// Panics if no map holds the key.
+    fn index(&self, index: &Q) -> &Self::Output {
+        self.get(index).expect("key not found in ChainMap")
+    }
+}
+
+impl<K: Ord, V> Default for ChainMap<K, V> {
+    fn default() -> Self {
+        ChainMap::new()
+    }
+}
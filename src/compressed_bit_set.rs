@@ -0,0 +1,480 @@
+//! This module implements a compressed set of `u32`, in the manner of
+//! Roaring bitmaps.
+//!
+//! Elements are grouped by their 16 high bits into containers holding the
+//! 16 low bits, each container using the cheapest of three layouts:
+//! a sorted array for sparse groups, a 65536 bits bitmap for dense groups,
+//! and a list of runs for clustered groups. Arrays and bitmaps are switched
+//! automatically as elements are added and removed, runs are only created
+//! by `optimize`.
+//!
+use std::collections::btree_map::{BTreeMap, Entry};
+use std::iter::FromIterator;
+
+/// Maximum number of elements of an array container, above which a bitmap
+/// is smaller.
+const ARRAY_MAX: usize = 4096;
+const BITMAP_WORDS: usize = 1024;
+
+/// Low bits of the elements sharing the same high bits
+#[derive(Debug, Clone)]
+enum Container {
+    Array(Vec<u16>),
+    Bitmap(Box<[u64; BITMAP_WORDS]>),
+    Run(Vec<(u16, u16)>),
+}
+
+impl Container {
+    /// Create the cheapest array or bitmap container of sorted values.
+    fn from_sorted(values: Vec<u16>) -> Container {
+        if values.len() <= ARRAY_MAX {
+            return Container::Array(values);
+        }
+        let mut words = Box::new([0; BITMAP_WORDS]);
+        for value in values {
+            words[value as usize / 64] |= 1 << (value % 64);
+        }
+        Container::Bitmap(words)
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Container::Array(values) => values.len(),
+            Container::Bitmap(words) => words.iter().map(|word| word.count_ones() as usize).sum(),
+            Container::Run(runs) => runs
+                .iter()
+                .map(|(start, end)| (end - start) as usize + 1)
+                .sum(),
+        }
+    }
+
+    fn contains(&self, value: u16) -> bool {
+        match self {
+            Container::Array(values) => values.binary_search(&value).is_ok(),
+            Container::Bitmap(words) => words[value as usize / 64] & (1 << (value % 64)) != 0,
+            Container::Run(runs) => {
+                let idx = runs.partition_point(|(start, _)| *start <= value);
+                idx > 0 && runs[idx - 1].1 >= value
+            }
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = u16> + '_> {
+        match self {
+            Container::Array(values) => Box::new(values.iter().cloned()),
+            Container::Bitmap(words) => {
+                Box::new(words.iter().enumerate().flat_map(|(idx, word)| {
+                    let mut word = *word;
+                    std::iter::from_fn(move || {
+                        if word == 0 {
+                            return None;
+                        }
+                        let bit = word.trailing_zeros() as usize;
+                        word &= word - 1;
+                        Some((idx * 64 + bit) as u16)
+                    })
+                }))
+            }
+            Container::Run(runs) => Box::new(runs.iter().flat_map(|(start, end)| *start..=*end)),
+        }
+    }
+
+    /// Convert a run container into an array or bitmap container.
+    fn materialize(&mut self) {
+        if let Container::Run(_) = self {
+            *self = Container::from_sorted(self.iter().collect());
+        }
+    }
+
+    fn insert(&mut self, value: u16) -> bool {
+        self.materialize();
+        match self {
+            Container::Array(values) => match values.binary_search(&value) {
+                Ok(_) => false,
+                Err(idx) => {
+                    values.insert(idx, value);
+                    if values.len() > ARRAY_MAX {
+                        *self = Container::from_sorted(std::mem::take(values));
+                    }
+                    true
+                }
+            },
+            Container::Bitmap(words) => {
+                let (word, mask) = (value as usize / 64, 1 << (value % 64));
+                let absent = words[word] & mask == 0;
+                words[word] |= mask;
+                absent
+            }
+            Container::Run(_) => unreachable!("runs are materialized"),
+        }
+    }
+
+    fn remove(&mut self, value: u16) -> bool {
+        if !self.contains(value) {
+            return false;
+        }
+        self.materialize();
+        match self {
+            Container::Array(values) => {
+                let _ = values.binary_search(&value).map(|idx| values.remove(idx));
+            }
+            Container::Bitmap(words) => words[value as usize / 64] &= !(1 << (value % 64)),
+            Container::Run(_) => unreachable!("runs are materialized"),
+        }
+        if let Container::Bitmap(_) = self {
+            if self.len() <= ARRAY_MAX {
+                *self = Container::Array(self.iter().collect());
+            }
+        }
+        true
+    }
+
+    fn union(&self, other: &Container) -> Container {
+        match (self, other) {
+            (Container::Bitmap(words), other) | (other, Container::Bitmap(words)) => {
+                let mut words = words.clone();
+                for value in other.iter() {
+                    words[value as usize / 64] |= 1 << (value % 64);
+                }
+                Container::Bitmap(words)
+            }
+            (Container::Run(runs), Container::Run(other)) => {
+                let mut all: Vec<(u16, u16)> = runs.iter().chain(other.iter()).cloned().collect();
+                all.sort_unstable();
+                let mut merged: Vec<(u16, u16)> = Vec::with_capacity(all.len());
+                for (start, end) in all {
+                    match merged.last_mut() {
+                        Some(last) if start as u32 <= last.1 as u32 + 1 => {
+                            last.1 = last.1.max(end);
+                        }
+                        _ => merged.push((start, end)),
+                    }
+                }
+                Container::Run(merged)
+            }
+            (left, right) => Container::from_sorted(merge_sorted(left.iter(), right.iter(), true)),
+        }
+    }
+
+    fn intersection(&self, other: &Container) -> Container {
+        let values: Vec<u16> = match (self, other) {
+            (Container::Bitmap(words), Container::Bitmap(other)) => {
+                let mut words = words.clone();
+                for (word, other) in words.iter_mut().zip(other.iter()) {
+                    *word &= other;
+                }
+                let container = Container::Bitmap(words);
+                if container.len() > ARRAY_MAX {
+                    return container;
+                }
+                container.iter().collect()
+            }
+            (Container::Array(values), other) | (other, Container::Array(values)) => values
+                .iter()
+                .cloned()
+                .filter(|value| other.contains(*value))
+                .collect(),
+            (left, right) => merge_sorted(left.iter(), right.iter(), false),
+        };
+        Container::from_sorted(values)
+    }
+
+    fn difference(&self, other: &Container) -> Container {
+        Container::from_sorted(
+            self.iter()
+                .filter(|value| !other.contains(*value))
+                .collect(),
+        )
+    }
+
+    /// Convert the container into runs if they are its smallest layout.
+    fn optimize(&mut self) {
+        let len = self.len();
+        let mut runs: Vec<(u16, u16)> = Vec::new();
+        for value in self.iter() {
+            match runs.last_mut() {
+                Some(last) if last.1 as u32 + 1 == value as u32 => last.1 = value,
+                _ => runs.push((value, value)),
+            }
+        }
+        let best = (2 * len).min(8 * BITMAP_WORDS);
+        if 4 * runs.len() < best {
+            *self = Container::Run(runs);
+        } else if let Container::Run(_) = self {
+            self.materialize();
+        }
+    }
+}
+
+/// Returns the union, or the intersection, of two increasing sequences.
+fn merge_sorted<L, R>(left: L, right: R, union: bool) -> Vec<u16>
+where
+    L: Iterator<Item = u16>,
+    R: Iterator<Item = u16>,
+{
+    let (mut left, mut right) = (left.peekable(), right.peekable());
+    let mut merged: Vec<u16> = Vec::new();
+    loop {
+        let (value, common) = match (left.peek().cloned(), right.peek().cloned()) {
+            (Some(l), Some(r)) if l < r => (left.next(), false),
+            (Some(l), Some(r)) if l > r => (right.next(), false),
+            (Some(_), Some(_)) => (left.next().and(right.next()), true),
+            (Some(_), None) if union => (left.next(), false),
+            (None, Some(_)) if union => (right.next(), false),
+            _ => break,
+        };
+        if union || common {
+            merged.extend(value);
+        }
+    }
+    merged
+}
+
+/// Structure that hold a set of `u32` in compressed containers
+#[derive(Debug, Clone, Default)]
+pub struct CompressedBitSet {
+    containers: BTreeMap<u16, Container>,
+}
+
+impl CompressedBitSet {
+    /// Create a new empty `CompressedBitSet`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::CompressedBitSet;
+    /// let set = CompressedBitSet::new();
+    /// # assert!(set.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        CompressedBitSet {
+            containers: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.containers.values().map(Container::len).sum()
+    }
+
+    /// Returns `true` if the `CompressedBitSet` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.containers.is_empty()
+    }
+
+    /// Insert an element, returning `true` if it was absent.
+    ///
+    /// # Arguments
+    /// * elem - The element inserted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::CompressedBitSet;
+    /// let mut set = CompressedBitSet::new();
+    /// assert!(set.insert(4_000_000_000));
+    /// assert!(!set.insert(4_000_000_000));
+    /// assert!(set.contains(4_000_000_000));
+    /// assert!(set.remove(4_000_000_000));
+    /// assert!(set.is_empty());
+    /// ```
+    pub fn insert(&mut self, elem: u32) -> bool {
+        let (high, low) = split(elem);
+        self.containers
+            .entry(high)
+            .or_insert_with(|| Container::Array(Vec::new()))
+            .insert(low)
+    }
+
+    /// Remove an element, returning `true` if it was present.
+    ///
+    /// # Arguments
+    /// * elem - The element removed
+    pub fn remove(&mut self, elem: u32) -> bool {
+        let (high, low) = split(elem);
+        match self.containers.entry(high) {
+            Entry::Occupied(mut entry) => {
+                let removed = entry.get_mut().remove(low);
+                if entry.get().len() == 0 {
+                    let _ = entry.remove();
+                }
+                removed
+            }
+            Entry::Vacant(_) => false,
+        }
+    }
+
+    /// Returns `true` if the element is present, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * elem - The element looked up
+    pub fn contains(&self, elem: u32) -> bool {
+        let (high, low) = split(elem);
+        self.containers
+            .get(&high)
+            .is_some_and(|container| container.contains(low))
+    }
+
+    /// Remove every element.
+    pub fn clear(&mut self) {
+        self.containers.clear();
+    }
+
+    /// Iterate over the elements, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.containers.iter().flat_map(|(high, container)| {
+            let high = (*high as u32) << 16;
+            container.iter().map(move |low| high | low as u32)
+        })
+    }
+
+    /// Returns the smallest element, `None` if empty.
+    pub fn min(&self) -> Option<u32> {
+        self.iter().next()
+    }
+
+    /// Returns the largest element, `None` if empty.
+    pub fn max(&self) -> Option<u32> {
+        let (high, container) = self.containers.iter().next_back()?;
+        container
+            .iter()
+            .last()
+            .map(|low| ((*high as u32) << 16) | low as u32)
+    }
+
+    /// Returns the elements in either set.
+    ///
+    /// # Arguments
+    /// * other - The other `CompressedBitSet`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::CompressedBitSet;
+    /// let a: CompressedBitSet = (0..10_000).chain(vec![1 << 20]).collect();
+    /// let b: CompressedBitSet = (5_000..20_000).collect();
+    /// assert_eq!(a.union(&b).len(), 20_001);
+    /// assert_eq!(a.intersection(&b).len(), 5_000);
+    /// assert_eq!(a.difference(&b).max(), Some(1 << 20));
+    /// ```
+    pub fn union(&self, other: &CompressedBitSet) -> CompressedBitSet {
+        let mut containers = self.containers.clone();
+        for (high, container) in other.containers.iter() {
+            match containers.entry(*high) {
+                Entry::Occupied(mut entry) => {
+                    let merged = entry.get().union(container);
+                    let _ = entry.insert(merged);
+                }
+                Entry::Vacant(entry) => {
+                    let _ = entry.insert(container.clone());
+                }
+            }
+        }
+        CompressedBitSet { containers }
+    }
+
+    /// Returns the elements in both sets.
+    ///
+    /// # Arguments
+    /// * other - The other `CompressedBitSet`
+    pub fn intersection(&self, other: &CompressedBitSet) -> CompressedBitSet {
+        let containers = self
+            .containers
+            .iter()
+            .filter_map(|(high, container)| {
+                let other = other.containers.get(high)?;
+                let common = container.intersection(other);
+                if common.len() == 0 {
+                    None
+                } else {
+                    Some((*high, common))
+                }
+            })
+            .collect();
+        CompressedBitSet { containers }
+    }
+
+    /// Returns the elements not in `other`.
+    ///
+    /// # Arguments
+    /// * other - The other `CompressedBitSet`
+    pub fn difference(&self, other: &CompressedBitSet) -> CompressedBitSet {
+        let containers = self
+            .containers
+            .iter()
+            .filter_map(|(high, container)| {
+                let left = match other.containers.get(high) {
+                    Some(other) => container.difference(other),
+                    None => container.clone(),
+                };
+                if left.len() == 0 {
+                    None
+                } else {
+                    Some((*high, left))
+                }
+            })
+            .collect();
+        CompressedBitSet { containers }
+    }
+
+    /// Convert the containers holding long runs of consecutive elements
+    /// into lists of runs, when that is their smallest layout.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::CompressedBitSet;
+    /// let mut set: CompressedBitSet = (100..60_000).collect();
+    /// let before = set.size_in_bytes();
+    /// set.optimize();
+    /// assert!(set.size_in_bytes() < before);
+    /// assert_eq!(set.len(), 59_900);
+    /// assert!(set.contains(59_999) && !set.contains(60_000));
+    /// ```
+    pub fn optimize(&mut self) {
+        for container in self.containers.values_mut() {
+            container.optimize();
+        }
+    }
+
+    /// Returns the approximate number of bytes used by the containers.
+    pub fn size_in_bytes(&self) -> usize {
+        self.containers
+            .values()
+            .map(|container| {
+                2 + match container {
+                    Container::Array(values) => 2 * values.len(),
+                    Container::Bitmap(_) => 8 * BITMAP_WORDS,
+                    Container::Run(runs) => 4 * runs.len(),
+                }
+            })
+            .sum()
+    }
+}
+
+/// Split an element into the high bits of its container and its low bits.
+fn split(elem: u32) -> (u16, u16) {
+    ((elem >> 16) as u16, elem as u16)
+}
+
+impl FromIterator<u32> for CompressedBitSet {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut set = CompressedBitSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<u32> for CompressedBitSet {
+    fn extend<I: IntoIterator<Item = u32>>(&mut self, iter: I) {
+        for elem in iter {
+            let _ = self.insert(elem);
+        }
+    }
+}
+
+/// Two `CompressedBitSet` are equal if they hold the same elements, whatever
+/// the layout of their containers.
+impl PartialEq for CompressedBitSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.containers.len() == other.containers.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for CompressedBitSet {}
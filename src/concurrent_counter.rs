@@ -0,0 +1,167 @@
+//! This module implements a `Counter` that can be updated from many
+//! threads at once.
+//!
+//! Elements are dispatched by hash over several independently locked
+//! shards, so threads updating different elements rarely wait on each
+//! other.
+//!
+use crate::Counter;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+const DEFAULT_SHARDS: usize = 16;
+
+/// Structure that count occurences of `T` elements from several threads
+#[derive(Debug)]
+pub struct ConcurrentCounter<T> {
+    shards: Vec<Mutex<Counter<T>>>,
+}
+
+impl<T: Ord + Hash> ConcurrentCounter<T> {
+    /// Create a new empty `ConcurrentCounter` with a default number of shards.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ConcurrentCounter;
+    /// let counter: ConcurrentCounter<char> = ConcurrentCounter::new();
+    /// # assert_eq!(counter.shards(), 16);
+    /// ```
+    pub fn new() -> Self {
+        ConcurrentCounter::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// Create a new empty `ConcurrentCounter` split in `shards` shards.
+    ///
+    /// # Arguments
+    /// * shards - The number of independently locked shards, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ConcurrentCounter;
+    /// let counter: ConcurrentCounter<char> = ConcurrentCounter::with_shards(4);
+    /// assert_eq!(counter.shards(), 4);
+    /// ```
+    pub fn with_shards(shards: usize) -> Self {
+        assert!(shards > 0);
+        ConcurrentCounter {
+            shards: (0..shards).map(|_| Mutex::new(Counter::new())).collect(),
+        }
+    }
+
+    /// Returns the number of shards.
+    pub fn shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Update the `ConcurrentCounter` with a value.
+    ///
+    /// Only the shard owning `elem` is locked.
+    ///
+    /// # Arguments
+    /// * elem - A value used to update the `ConcurrentCounter`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ConcurrentCounter;
+    /// let counter: ConcurrentCounter<u32> = ConcurrentCounter::new();
+    /// std::thread::scope(|scope| {
+    ///     for _ in 0..4 {
+    ///         scope.spawn(|| {
+    ///             for i in 0..100 {
+    ///                 counter.update(i % 10);
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    /// let counter = counter.into_counter();
+    /// assert_eq!(counter[&3], 40);
+    /// assert_eq!(counter.len(), 10);
+    /// ```
+    pub fn update(&self, elem: T) {
+        self.lock_shard(&elem).update_from_value(elem);
+    }
+
+    /// Update the `ConcurrentCounter` with an iterator.
+    ///
+    /// # Arguments
+    /// * iter - An iterator used to update the `ConcurrentCounter`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ConcurrentCounter;
+    /// let counter: ConcurrentCounter<char> = ConcurrentCounter::new();
+    /// counter.update_from_iter("hello".chars());
+    /// assert_eq!(counter.snapshot()[&'l'], 2);
+    /// ```
+    pub fn update_from_iter<I>(&self, iter: I)
+    where
+        I: Iterator<Item = T>,
+    {
+        for elem in iter {
+            self.update(elem);
+        }
+    }
+
+    /// Merge every shard into a new `Counter`, without consuming the
+    /// `ConcurrentCounter`.
+    ///
+    /// Shards are locked one after the other, so updates running
+    /// concurrently may or may not be part of the snapshot.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ConcurrentCounter;
+    /// let counter: ConcurrentCounter<char> = ConcurrentCounter::new();
+    /// counter.update('a');
+    /// let snapshot = counter.snapshot();
+    /// counter.update('a');
+    /// assert_eq!(snapshot[&'a'], 1);
+    /// assert_eq!(counter.snapshot()[&'a'], 2);
+    /// ```
+    pub fn snapshot(&self) -> Counter<T>
+    where
+        T: Clone,
+    {
+        let mut counter: Counter<T> = Counter::new();
+        for shard in self.shards.iter() {
+            let shard = shard.lock().unwrap_or_else(PoisonError::into_inner);
+            counter.update_from_counter(&shard);
+        }
+        counter
+    }
+
+    /// Merge every shard into a new `Counter`, consuming the `ConcurrentCounter`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ConcurrentCounter;
+    /// let counter: ConcurrentCounter<char> = ConcurrentCounter::new();
+    /// counter.update_from_iter("abracadabra".chars());
+    /// let counter = counter.into_counter();
+    /// assert_eq!(counter[&'a'], 5);
+    /// assert_eq!(counter.len(), 5);
+    /// ```
+    pub fn into_counter(self) -> Counter<T> {
+        let mut counter: Counter<T> = Counter::new();
+        for shard in self.shards.into_iter() {
+            counter.extend(shard.into_inner().unwrap_or_else(PoisonError::into_inner));
+        }
+        counter
+    }
+
+    fn lock_shard(&self, elem: &T) -> MutexGuard<'_, Counter<T>> {
+        let mut hasher = DefaultHasher::new();
+        elem.hash(&mut hasher);
+        let idx = (hasher.finish() % self.shards.len() as u64) as usize;
+        self.shards[idx]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Ord + Hash> Default for ConcurrentCounter<T> {
+    fn default() -> Self {
+        ConcurrentCounter::new()
+    }
+}
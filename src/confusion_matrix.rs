@@ -0,0 +1,256 @@
+//! This module implements a confusion matrix, evaluating a classifier.
+//!
+//! A `ConfusionMatrix` counts `(actual, predicted)` label pairs in a
+//! `FrequencyTable`, actual labels as rows and predicted ones as columns,
+//! so the metrics of a label come from one cell and two totals. The
+//! metrics of a label are `None` when they would divide by zero, like the
+//! precision of a label never predicted.
+//!
+use crate::FrequencyTable;
+use std::borrow::Borrow;
+use std::collections::BTreeSet;
+use std::iter::FromIterator;
+
+/// Structure that count `(actual, predicted)` pairs of `L` labels
+#[derive(Debug, Clone)]
+pub struct ConfusionMatrix<L> {
+    table: FrequencyTable<L, L>,
+}
+
+impl<L: Ord + Clone> ConfusionMatrix<L> {
+    /// Create a new empty `ConfusionMatrix`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ConfusionMatrix;
+    /// let matrix: ConfusionMatrix<&str> = ConfusionMatrix::new();
+    /// # assert!(matrix.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        ConfusionMatrix {
+            table: FrequencyTable::new(),
+        }
+    }
+
+    /// Returns `true` if no pair was counted, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Count a prediction.
+    ///
+    /// # Arguments
+    /// * actual - The actual label
+    /// * predicted - The predicted label
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ConfusionMatrix;
+    /// let mut matrix: ConfusionMatrix<&str> = ConfusionMatrix::new();
+    /// matrix.update("spam", "spam");
+    /// matrix.update("spam", "ham");
+    /// matrix.update("ham", "ham");
+    /// matrix.update("ham", "ham");
+    /// assert_eq!(matrix.get("spam", "ham"), 1);
+    /// assert_eq!(matrix.accuracy(), Some(0.75));
+    /// assert_eq!(matrix.precision("spam"), Some(1.0));
+    /// assert_eq!(matrix.recall("spam"), Some(0.5));
+    /// ```
+    pub fn update(&mut self, actual: L, predicted: L) {
+        self.table.update(actual, predicted);
+    }
+
+    /// Count every prediction of an iterator.
+    ///
+    /// # Arguments
+    /// * iter - An iterator over `(actual, predicted)` pairs
+    pub fn update_from_iter<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (L, L)>,
+    {
+        self.table.update_from_iter(iter);
+    }
+
+    /// Returns the number of times `actual` was predicted as `predicted`.
+    ///
+    /// # Arguments
+    /// * actual - A borrowed form of the actual label
+    /// * predicted - A borrowed form of the predicted label
+    pub fn get<Q>(&self, actual: &Q, predicted: &Q) -> u128
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.table.get(actual, predicted)
+    }
+
+    /// Returns the number of predictions.
+    pub fn total(&self) -> u128 {
+        self.table.total()
+    }
+
+    /// Returns the number of correct predictions.
+    pub fn correct(&self) -> u128 {
+        self.table
+            .rows()
+            .map(|label| self.table.get(label, label))
+            .sum()
+    }
+
+    /// Returns the share of correct predictions, `None` if there is none.
+    pub fn accuracy(&self) -> Option<f64> {
+        ratio(self.correct(), self.total())
+    }
+
+    /// Returns the number of correct predictions of a label.
+    ///
+    /// # Arguments
+    /// * label - A borrowed form of the label
+    pub fn true_positives<Q>(&self, label: &Q) -> u128
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.table.get(label, label)
+    }
+
+    /// Returns the number of times a label was predicted wrongly.
+    ///
+    /// # Arguments
+    /// * label - A borrowed form of the label
+    pub fn false_positives<Q>(&self, label: &Q) -> u128
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.table.column_total(label) - self.true_positives(label)
+    }
+
+    /// Returns the number of times a label was missed.
+    ///
+    /// # Arguments
+    /// * label - A borrowed form of the label
+    pub fn false_negatives<Q>(&self, label: &Q) -> u128
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.table.row_total(label) - self.true_positives(label)
+    }
+
+    /// Returns the share of the predictions of a label that are correct,
+    /// `None` if it was never predicted.
+    ///
+    /// # Arguments
+    /// * label - A borrowed form of the label
+    pub fn precision<Q>(&self, label: &Q) -> Option<f64>
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        ratio(self.true_positives(label), self.table.column_total(label))
+    }
+
+    /// Returns the share of the occurences of a label that were predicted,
+    /// `None` if it never occured.
+    ///
+    /// # Arguments
+    /// * label - A borrowed form of the label
+    pub fn recall<Q>(&self, label: &Q) -> Option<f64>
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        ratio(self.true_positives(label), self.table.row_total(label))
+    }
+
+    /// Returns the F1 score of a label, the harmonic mean of its precision
+    /// and recall, `None` if it neither occured nor was predicted.
+    ///
+    /// # Arguments
+    /// * label - A borrowed form of the label
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ConfusionMatrix;
+    /// let pairs = vec![("cat", "cat"), ("cat", "dog"), ("dog", "dog"), ("dog", "cat")];
+    /// let matrix: ConfusionMatrix<&str> = pairs.into_iter().collect();
+    /// assert_eq!(matrix.f1("cat"), Some(0.5));
+    /// assert_eq!(matrix.f1("bird"), None);
+    /// ```
+    pub fn f1<Q>(&self, label: &Q) -> Option<f64>
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let true_positives = self.true_positives(label);
+        ratio(
+            2 * true_positives,
+            self.table.row_total(label) + self.table.column_total(label),
+        )
+    }
+
+    /// Returns the mean of the F1 scores of the labels, `None` if no pair
+    /// was counted.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ConfusionMatrix;
+    /// let pairs = vec![(0, 0), (0, 0), (0, 1), (1, 1)];
+    /// let matrix: ConfusionMatrix<u8> = pairs.into_iter().collect();
+    /// let f1 = matrix.macro_f1().unwrap();
+    /// assert!((f1 - (0.8 + 2.0 / 3.0) / 2.0).abs() < 1e-12);
+    /// ```
+    pub fn macro_f1(&self) -> Option<f64> {
+        let labels = self.labels();
+        if labels.is_empty() {
+            return None;
+        }
+        let sum: f64 = labels.iter().filter_map(|label| self.f1(*label)).sum();
+        Some(sum / labels.len() as f64)
+    }
+
+    /// Returns the labels that occured or were predicted, in order.
+    pub fn labels(&self) -> BTreeSet<&L> {
+        self.table.rows().chain(self.table.columns()).collect()
+    }
+
+    /// Returns the `FrequencyTable` of the pairs, actual labels as rows and
+    /// predicted ones as columns.
+    pub fn table(&self) -> &FrequencyTable<L, L> {
+        &self.table
+    }
+
+    /// Remove every count.
+    pub fn clear(&mut self) {
+        self.table.clear();
+    }
+}
+
+/// Returns `part / whole`, `None` if `whole` is 0.
+fn ratio(part: u128, whole: u128) -> Option<f64> {
+    if whole == 0 {
+        return None;
+    }
+    Some(part as f64 / whole as f64)
+}
+
+impl<L: Ord + Clone> FromIterator<(L, L)> for ConfusionMatrix<L> {
+    fn from_iter<I: IntoIterator<Item = (L, L)>>(iter: I) -> Self {
+        let mut matrix: ConfusionMatrix<L> = ConfusionMatrix::new();
+        matrix.update_from_iter(iter);
+        matrix
+    }
+}
+
+impl<L: Ord + Clone> Extend<(L, L)> for ConfusionMatrix<L> {
+    fn extend<I: IntoIterator<Item = (L, L)>>(&mut self, iter: I) {
+        self.update_from_iter(iter);
+    }
+}
+
+impl<L: Ord + Clone> Default for ConfusionMatrix<L> {
+    fn default() -> Self {
+        ConfusionMatrix::new()
+    }
+}
@@ -0,0 +1,181 @@
+//! This module implements a Count Sketch, estimating signed frequencies
+//! in a fixed amount of memory.
+//!
+//! The sketch holds `depth` rows of `width` signed counters. Every row
+//! hashes an item to one of its counters and to a sign, and adds the
+//! signed update to that counter. Other items colliding on a counter add
+//! noise of random sign, so the estimate of a row is unbiased, and the
+//! median over the rows discards the rows hit by large items. As updates
+//! may be negative, items can be removed from the stream as well as added.
+//!
+use crate::min_hash::mix;
+use crate::SignedCounter;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// Structure that estimate signed frequencies of `T` items
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountSketch<T> {
+    counters: Vec<i128>,
+    width: usize,
+    depth: usize,
+    total: i128,
+    items: PhantomData<T>,
+}
+
+impl<T: Hash> CountSketch<T> {
+    /// Create a new empty `CountSketch`.
+    ///
+    /// An odd `depth` is best, the median of the rows then being exact.
+    ///
+    /// # Arguments
+    /// * width - The number of counters per row, must be greater than 0
+    /// * depth - The number of rows, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::CountSketch;
+    /// let sketch: CountSketch<&str> = CountSketch::new(1024, 5);
+    /// # assert_eq!(sketch.estimate(&"anything"), 0);
+    /// ```
+    pub fn new(width: usize, depth: usize) -> Self {
+        assert!(width > 0 && depth > 0);
+        CountSketch {
+            counters: vec![0; width * depth],
+            width,
+            depth,
+            total: 0,
+            items: PhantomData,
+        }
+    }
+
+    /// Returns the number of counters per row.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the sum of the updates.
+    pub fn total(&self) -> i128 {
+        self.total
+    }
+
+    /// Add `delta` to the frequency of an item, `delta` being negative to
+    /// remove occurences.
+    ///
+    /// # Arguments
+    /// * item - The item updated
+    /// * delta - The signed change of its frequency
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::CountSketch;
+    /// // Open connections per host, from connect and disconnect events.
+    /// let mut open: CountSketch<&str> = CountSketch::new(256, 5);
+    /// open.update(&"db", 40);
+    /// open.update(&"cache", 7);
+    /// open.update(&"db", -15);
+    /// assert_eq!(open.estimate(&"db"), 25);
+    /// assert_eq!(open.estimate(&"cache"), 7);
+    /// assert_eq!(open.total(), 32);
+    /// ```
+    pub fn update(&mut self, item: &T, delta: i128) {
+        for row in 0..self.depth {
+            let (idx, sign) = self.cell(item, row);
+            self.counters[idx] += sign * delta;
+        }
+        self.total += delta;
+    }
+
+    /// Add 1 to the frequency of every item of an iterator.
+    ///
+    /// # Arguments
+    /// * iter - An iterator over the items
+    pub fn update_from_iter<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iter {
+            self.update(&item, 1);
+        }
+    }
+
+    /// Add the counts of a `SignedCounter` to the frequencies.
+    ///
+    /// # Arguments
+    /// * counter - The `SignedCounter` whose counts are added
+    pub fn update_from_signed_counter(&mut self, counter: &SignedCounter<T>) {
+        for (item, count) in counter.iter() {
+            self.update(item, *count);
+        }
+    }
+
+    /// Returns the estimated frequency of an item, the median of the
+    /// estimates of the rows.
+    ///
+    /// # Arguments
+    /// * item - The item looked up
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::CountSketch;
+    /// let mut sketch: CountSketch<u32> = CountSketch::new(64, 7);
+    /// for n in 0..10_000 {
+    ///     sketch.update(&(n % 1000), 1);
+    /// }
+    /// sketch.update(&7, 5_000);
+    /// let estimate = sketch.estimate(&7);
+    /// assert!((estimate - 5_010).abs() < 500);
+    /// ```
+    pub fn estimate(&self, item: &T) -> i128 {
+        let mut estimates: Vec<i128> = (0..self.depth)
+            .map(|row| {
+                let (idx, sign) = self.cell(item, row);
+                sign * self.counters[idx]
+            })
+            .collect();
+        estimates.sort_unstable();
+        let middle = self.depth / 2;
+        if self.depth % 2 == 1 {
+            estimates[middle]
+        } else {
+            (estimates[middle - 1] + estimates[middle]) / 2
+        }
+    }
+
+    /// Add the updates of another `CountSketch`.
+    ///
+    /// # Arguments
+    /// * other - The sketch to merge, of the same width and depth
+    pub fn merge(&mut self, other: &CountSketch<T>) {
+        assert!(self.width == other.width && self.depth == other.depth);
+        for (counter, other_counter) in self.counters.iter_mut().zip(other.counters.iter()) {
+            *counter += other_counter;
+        }
+        self.total += other.total;
+    }
+
+    /// Reset every counter.
+    pub fn clear(&mut self) {
+        for counter in self.counters.iter_mut() {
+            *counter = 0;
+        }
+        self.total = 0;
+    }
+
+    /// Returns the index of the counter of an item in a row, along with
+    /// the sign of its updates.
+    fn cell(&self, item: &T, row: usize) -> (usize, i128) {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        let hash = mix(hasher.finish() ^ mix(row as u64));
+        let sign = if hash >> 63 == 0 { 1 } else { -1 };
+        let column = (hash % self.width as u64) as usize;
+        (row * self.width + column, sign)
+    }
+}
@@ -1,21 +1,97 @@
 //! This module implements python-like `Counter` map.
 //!
-//! It can take any struct implementing `Ord + Debug` as input.
+//! It can take any struct implementing `Ord` as input.
 //!
-use std::cmp::Eq;
-use std::cmp::Ord;
-use std::collections::btree_map::{BTreeMap, IntoIter, Iter};
-use std::fmt::Debug;
-use std::iter::FromIterator;
-use std::ops::Index;
+//! The module only relies on `core` and `alloc`, so `Counter` remains
+//! available under `no_std` when the default `std` feature is disabled.
+//! Reading from `io` sources and files requires `std`.
+//!
+use alloc::borrow::ToOwned;
+use alloc::collections::btree_map::{BTreeMap, IntoIter, Iter};
+use alloc::collections::{BTreeSet, BinaryHeap, VecDeque};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Eq;
+use core::cmp::{Ord, Reverse};
+use core::convert::{Infallible, TryFrom};
+use core::error::Error;
+use core::fmt::{self, Debug, Display, Formatter, Write};
+use core::iter::{Extend, FromIterator};
+use core::mem;
+use core::ops::{Div, DivAssign, Index, IndexMut, Mul, MulAssign, RangeBounds};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::fs::File;
+#[cfg(feature = "std")]
+use std::io::{self, BufRead, BufReader, Read};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+/// Indicate how the elements of a `Counter` are sorted when rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Most counted elements first, ties sorted by key
+    ByCount,
+
+    /// Elements sorted by key
+    ByKey,
+}
+
+/// Indicate what happens when a count of a `Counter` would overflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Infallible updates panic, fallible ones return a `CountOverflowError`
+    #[default]
+    Checked,
+
+    /// Counts stop at `u128::MAX`
+    Saturating,
+
+    /// Counts wrap around to 0
+    Wrapping,
+}
+
+impl OverflowPolicy {
+    fn add(self, count: u128, rhs: u128) -> Result<u128, CountOverflowError> {
+        match self {
+            OverflowPolicy::Checked => count.checked_add(rhs).ok_or(CountOverflowError),
+            OverflowPolicy::Saturating => Ok(count.saturating_add(rhs)),
+            OverflowPolicy::Wrapping => Ok(count.wrapping_add(rhs)),
+        }
+    }
+
+    fn mul(self, count: u128, rhs: u128) -> Result<u128, CountOverflowError> {
+        match self {
+            OverflowPolicy::Checked => count.checked_mul(rhs).ok_or(CountOverflowError),
+            OverflowPolicy::Saturating => Ok(count.saturating_mul(rhs)),
+            OverflowPolicy::Wrapping => Ok(count.wrapping_mul(rhs)),
+        }
+    }
+}
+
+/// Error returned when a count overflows under `OverflowPolicy::Checked`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountOverflowError;
+
+impl Display for CountOverflowError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("count overflow")
+    }
+}
+
+impl Error for CountOverflowError {}
 
 /// Structure that count occurences of `T` elements
-#[derive(Debug)]
+#[derive(Clone)]
 pub struct Counter<T> {
     state: BTreeMap<T, u128>,
+    overflow: OverflowPolicy,
+    normalizer: Option<fn(T) -> T>,
 }
 
-impl<T: Ord + Debug> Counter<T> {
+impl<T> Counter<T> {
     /// Create a new empty `Counter`.
     ///
     /// # Examples
@@ -25,11 +101,105 @@ impl<T: Ord + Debug> Counter<T> {
     /// # assert_eq!(counter.len(), 0);
     /// ```
     pub fn new() -> Self {
+        Counter::with_overflow_policy(OverflowPolicy::default())
+    }
+
+    /// Create a new empty `Counter` handling count overflows with `overflow`.
+    ///
+    /// `Counter::new` uses `OverflowPolicy::Checked`.
+    ///
+    /// # Arguments
+    /// * overflow - What happens when a count would overflow
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, OverflowPolicy};
+    /// let mut counter: Counter<char> = Counter::with_overflow_policy(OverflowPolicy::Saturating);
+    /// counter.extend(vec![('a', u128::MAX), ('a', 1)]);
+    /// assert_eq!(counter[&'a'], u128::MAX);
+    ///
+    /// let mut counter: Counter<char> = Counter::with_overflow_policy(OverflowPolicy::Wrapping);
+    /// counter.extend(vec![('a', u128::MAX), ('a', 2)]);
+    /// assert_eq!(counter[&'a'], 1);
+    /// ```
+    pub fn with_overflow_policy(overflow: OverflowPolicy) -> Self {
+        Counter {
+            state: BTreeMap::new(),
+            overflow,
+            normalizer: None,
+        }
+    }
+
+    /// Create a new empty `Counter` passing every element through
+    /// `normalizer` before counting it, to lowercase, trim or bucket keys
+    /// for instance.
+    ///
+    /// Elements are normalized by the updates and by `extend`, not by
+    /// indexing: `counter[key] += 1` and lookups use the key as given, so
+    /// they must use the normalized form.
+    ///
+    /// # Arguments
+    /// * normalizer - The function giving the canonical form of an element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut counter: Counter<String> = Counter::with_normalizer(|word| word.trim().to_lowercase());
+    /// counter.update_from_iter("Apple apple  APPLE pear".split(' ').map(String::from));
+    /// assert_eq!(counter["apple"], 3);
+    /// assert_eq!(counter["pear"], 1);
+    ///
+    /// // Bucket latencies by 100 ms.
+    /// let mut latencies: Counter<u32> = Counter::with_normalizer(|ms| ms / 100 * 100);
+    /// latencies.update_from_iter(vec![12, 87, 140, 199, 1003].into_iter());
+    /// assert_eq!(latencies[&0], 2);
+    /// assert_eq!(latencies[&100], 2);
+    /// assert_eq!(latencies[&1000], 1);
+    /// ```
+    pub fn with_normalizer(normalizer: fn(T) -> T) -> Self {
+        Counter::with_overflow_policy_and_normalizer(OverflowPolicy::default(), normalizer)
+    }
+
+    /// Create a new empty `Counter` handling count overflows with
+    /// `overflow` and passing every element through `normalizer` before
+    /// counting it, see `Counter::with_overflow_policy` and
+    /// `Counter::with_normalizer`.
+    ///
+    /// # Arguments
+    /// * overflow - What happens when a count would overflow
+    /// * normalizer - The function giving the canonical form of an element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, OverflowPolicy};
+    /// let mut counter: Counter<u8> =
+    ///     Counter::with_overflow_policy_and_normalizer(OverflowPolicy::Saturating, |n| n % 2);
+    /// counter.extend(vec![(1, u128::MAX), (3, 1), (4, 1)]);
+    /// assert_eq!(counter[&1], u128::MAX);
+    /// assert_eq!(counter[&0], 1);
+    /// ```
+    pub fn with_overflow_policy_and_normalizer(
+        overflow: OverflowPolicy,
+        normalizer: fn(T) -> T,
+    ) -> Self {
         Counter {
             state: BTreeMap::new(),
+            overflow,
+            normalizer: Some(normalizer),
         }
     }
 
+    /// Returns the function applied to every element before counting it,
+    /// if any.
+    pub fn normalizer(&self) -> Option<fn(T) -> T> {
+        self.normalizer
+    }
+
+    /// Returns what happens when a count would overflow.
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow
+    }
+
     /// Iterate over the `Counter` without consuming it.
     ///
     /// # Examples
@@ -62,6 +232,24 @@ impl<T: Ord + Debug> Counter<T> {
         self.state.iter()
     }
 
+    /// Iterate over the distinct elements, in key order, skipping those
+    /// whose count is 0.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut counter: Counter<char> = "abracadabra".chars().collect();
+    /// counter[&'z'] += 0;
+    /// let unique: String = counter.unique().collect();
+    /// assert_eq!(unique, "abcdr");
+    /// ```
+    pub fn unique(&self) -> impl Iterator<Item = &T> + '_ {
+        self.state
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(key, _)| key)
+    }
+
     /// Returns the number of elements in the map.
     ///
     /// # Examples
@@ -95,9 +283,96 @@ impl<T: Ord + Debug> Counter<T> {
     pub fn is_empty(&self) -> bool {
         self.state.is_empty()
     }
+}
+
+impl<T: Ord> Counter<T> {
+    /// Create a new `Counter` from an iterator yielding its elements in
+    /// ascending order.
+    ///
+    /// Equal elements are consecutive in sorted input, so they are counted
+    /// as runs and the map is then built in bulk, without looking up the
+    /// map for every element.
+    ///
+    /// # Panics
+    /// Panics if the iterator yields an element lower than a previous one.
+    ///
+    /// # Arguments
+    /// * iter - An iterator yielding elements in ascending order
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<u32> = Counter::from_sorted_iter(vec![1, 1, 2, 5, 5, 5]);
+    /// assert_eq!(counter[&1], 2);
+    /// assert_eq!(counter[&5], 3);
+    /// assert_eq!(counter, vec![1, 1, 2, 5, 5, 5].into_iter().collect());
+    /// ```
+    pub fn from_sorted_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut runs: Vec<(T, u128)> = Vec::new();
+        for elem in iter {
+            match runs.last_mut() {
+                Some((key, count)) if *key == elem => *count += 1,
+                Some((key, _)) => {
+                    assert!(*key < elem, "the iterator is not sorted");
+                    runs.push((elem, 1));
+                }
+                None => runs.push((elem, 1)),
+            }
+        }
+
+        // Collecting already sorted pairs appends them to the map.
+        Counter {
+            state: runs.into_iter().collect(),
+            overflow: OverflowPolicy::default(),
+            normalizer: None,
+        }
+    }
+
+    /// Create a new `Counter` from the keys extracted from the items of an
+    /// iterator.
+    ///
+    /// # Arguments
+    /// * iter - The items to count
+    /// * key - A function extracting the counted key of an item
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// struct Request {
+    ///     path: &'static str,
+    ///     status_code: u16,
+    /// }
+    ///
+    /// let requests = vec![
+    ///     Request { path: "/", status_code: 200 },
+    ///     Request { path: "/login", status_code: 500 },
+    ///     Request { path: "/", status_code: 200 },
+    /// ];
+    /// let by_status = Counter::count_by(requests.iter(), |r| r.status_code);
+    /// assert_eq!(by_status[&200], 2);
+    /// assert_eq!(by_status[&500], 1);
+    ///
+    /// let by_path = Counter::count_by(requests.iter(), |r| r.path);
+    /// assert_eq!(by_path[&"/"], 2);
+    /// ```
+    pub fn count_by<I, F>(iter: I, key: F) -> Self
+    where
+        I: IntoIterator,
+        F: FnMut(I::Item) -> T,
+    {
+        let mut counter: Counter<T> = Counter::new();
+        counter.update_from_iter(iter.into_iter().map(key));
+        counter
+    }
 
     /// Update the `Counter` with an iterator.
     ///
+    /// # Panics
+    /// Panics if a count overflows under `OverflowPolicy::Checked`.
+    ///
     /// # Arguments
     /// * iter - An iterator used to update the `Counter`
     ///
@@ -108,27 +383,57 @@ impl<T: Ord + Debug> Counter<T> {
     /// # use std::iter::FromIterator;
     /// let mut counter: Counter<char> = Counter::new();
     /// counter.update_from_iter("a string".chars());
-    /// assert_eq!(counter['a'], 1);
-    /// assert_eq!(counter[' '], 1);
-    /// assert_eq!(counter['s'], 1);
-    /// assert_eq!(counter['t'], 1);
-    /// assert_eq!(counter['r'], 1);
-    /// assert_eq!(counter['i'], 1);
-    /// assert_eq!(counter['n'], 1);
-    /// assert_eq!(counter['g'], 1);
+    /// assert_eq!(counter[&'a'], 1);
+    /// assert_eq!(counter[&' '], 1);
+    /// assert_eq!(counter[&'s'], 1);
+    /// assert_eq!(counter[&'t'], 1);
+    /// assert_eq!(counter[&'r'], 1);
+    /// assert_eq!(counter[&'i'], 1);
+    /// assert_eq!(counter[&'n'], 1);
+    /// assert_eq!(counter[&'g'], 1);
     /// ```
     pub fn update_from_iter<I>(&mut self, iter: I)
     where
         I: Iterator<Item = T>,
     {
         for elem in iter {
-            let count = self.state.entry(elem).or_insert(0);
-            *count += 1;
+            self.add_count(elem, 1);
+        }
+    }
+
+    /// Update the `Counter` with an iterator, stopping at the first count
+    /// overflowing under `OverflowPolicy::Checked`.
+    ///
+    /// Values yielded before the overflowing one stay counted.
+    ///
+    /// # Arguments
+    /// * iter - An iterator used to update the `Counter`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{CountOverflowError, Counter};
+    /// let mut counter: Counter<char> = Counter::new();
+    /// counter.extend(vec![('b', u128::MAX)]);
+    /// assert_eq!(counter.try_update_from_iter("abc".chars()), Err(CountOverflowError));
+    /// assert_eq!(counter[&'a'], 1);
+    /// assert_eq!(counter[&'b'], u128::MAX);
+    /// assert_eq!(counter[&'c'], 0);
+    /// ```
+    pub fn try_update_from_iter<I>(&mut self, iter: I) -> Result<(), CountOverflowError>
+    where
+        I: Iterator<Item = T>,
+    {
+        for elem in iter {
+            self.try_add_count(elem, 1)?;
         }
+        Ok(())
     }
 
     /// Update the `Counter` with a value.
     ///
+    /// # Panics
+    /// Panics if the count overflows under `OverflowPolicy::Checked`.
+    ///
     /// # Arguments
     /// * elem - A value used to update the `Counter`
     ///
@@ -138,45 +443,1710 @@ impl<T: Ord + Debug> Counter<T> {
     /// # use std::collections::btree_map::BTreeMap;
     /// # use std::iter::FromIterator;
     /// let mut counter: Counter<char> = Counter::new();
-    /// assert_eq!(counter['a'], 0);
+    /// assert_eq!(counter[&'a'], 0);
     /// counter.update_from_value('a');
-    /// assert_eq!(counter['a'], 1);
+    /// assert_eq!(counter[&'a'], 1);
     /// ```
     pub fn update_from_value(&mut self, elem: T) {
-        let count = self.state.entry(elem).or_insert(0);
-        *count += 1;
+        self.add_count(elem, 1);
     }
-}
 
-impl<T: Ord + Debug> FromIterator<T> for Counter<T> {
-    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let mut counter: Counter<T> = Counter::new();
-        counter.update_from_iter(iter.into_iter());
-        counter
+    /// Update the `Counter` with a value, failing if its count overflows
+    /// under `OverflowPolicy::Checked`.
+    ///
+    /// # Arguments
+    /// * elem - A value used to update the `Counter`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{CountOverflowError, Counter};
+    /// let mut counter: Counter<char> = Counter::new();
+    /// counter[&'a'] = u128::MAX;
+    /// assert_eq!(counter.try_update_from_value('a'), Err(CountOverflowError));
+    /// assert_eq!(counter.try_update_from_value('b'), Ok(()));
+    /// ```
+    pub fn try_update_from_value(&mut self, elem: T) -> Result<(), CountOverflowError> {
+        self.try_add_count(elem, 1)
     }
-}
 
-impl<T: Ord + Debug> IntoIterator for Counter<T> {
-    type Item = (T, u128);
-    type IntoIter = IntoIter<T, u128>;
+    /// Multiply every count by a scalar, failing if a count overflows under
+    /// `OverflowPolicy::Checked`, in which case the `Counter` is left
+    /// unchanged.
+    ///
+    /// Elements whose count becomes 0 are removed.
+    ///
+    /// # Arguments
+    /// * rhs - The scalar every count is multiplied by
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{CountOverflowError, Counter};
+    /// let mut counter: Counter<char> = Counter::new();
+    /// counter.extend(vec![('a', 2), ('b', u128::MAX / 2)]);
+    /// assert_eq!(counter.try_mul(3), Err(CountOverflowError));
+    /// assert_eq!(counter[&'a'], 2);
+    /// assert_eq!(counter.try_mul(2), Ok(()));
+    /// assert_eq!(counter[&'a'], 4);
+    /// ```
+    pub fn try_mul(&mut self, rhs: u128) -> Result<(), CountOverflowError> {
+        let overflow = self.overflow;
+        for count in self.state.values() {
+            let _ = overflow.mul(*count, rhs)?;
+        }
+        for count in self.state.values_mut() {
+            *count = overflow.mul(*count, rhs)?;
+        }
+        self.retain(|_, count| count > 0);
+        Ok(())
+    }
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.state.into_iter()
+    /// Update the `Counter` with the counts of another `Counter`, without
+    /// consuming it.
+    ///
+    /// # Arguments
+    /// * other - A `Counter` whose counts are added to the `Counter`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut total: Counter<char> = Counter::new();
+    /// let partial: Counter<char> = "aab".chars().collect();
+    /// total.update_from_counter(&partial);
+    /// total.update_from_counter(&partial);
+    /// assert_eq!(total[&'a'], 4);
+    /// assert_eq!(total[&'b'], 2);
+    /// assert_eq!(partial[&'a'], 2);
+    /// ```
+    pub fn update_from_counter(&mut self, other: &Counter<T>)
+    where
+        T: Clone,
+    {
+        for (key, occurences) in other.state.iter() {
+            self.add_count(key.clone(), *occurences);
+        }
     }
-}
 
-impl<T: Ord + Debug> Index<T> for Counter<T> {
-    type Output = u128;
+    /// Combine the counts of another `Counter` into the `Counter` with
+    /// `combine`, called with both counts of every element counted in either
+    /// of them, 0 standing for a missing element.
+    ///
+    /// Elements whose combined count is 0 are removed.
+    ///
+    /// # Arguments
+    /// * other - The `Counter` to combine with
+    /// * combine - A function returning the new count from the count in the `Counter` and in `other`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut peak: Counter<&str> = Counter::new();
+    /// peak.extend(vec![("eu", 10), ("us", 20)]);
+    /// let mut today: Counter<&str> = Counter::new();
+    /// today.extend(vec![("eu", 15), ("asia", 5)]);
+    ///
+    /// let mut max = peak.clone();
+    /// max.merge_with(&today, |a, b| a.max(b));
+    /// assert_eq!(max[&"eu"], 15);
+    /// assert_eq!(max[&"us"], 20);
+    /// assert_eq!(max[&"asia"], 5);
+    ///
+    /// let mut min = peak.clone();
+    /// min.merge_with(&today, |a, b| a.min(b));
+    /// assert_eq!(min[&"eu"], 10);
+    /// assert_eq!(min.len(), 1);
+    /// ```
+    pub fn merge_with<F>(&mut self, other: &Counter<T>, mut combine: F)
+    where
+        T: Clone,
+        F: FnMut(u128, u128) -> u128,
+    {
+        for (key, count) in self.state.iter_mut() {
+            *count = combine(*count, other.state.get(key).cloned().unwrap_or(0));
+        }
+        for (key, count) in other.state.iter() {
+            if !self.state.contains_key(key) {
+                let _ = self.state.insert(key.clone(), combine(0, *count));
+            }
+        }
+        self.state.retain(|_, count| *count > 0);
+    }
 
-    fn index(&self, index: T) -> &Self::Output {
-        match self.state.get(&index) {
-            Some(value) => value,
-            None => &0,
+    /// Retain only the elements specified by the predicate.
+    ///
+    /// Elements for which the predicate returns `false` are removed in place.
+    ///
+    /// # Arguments
+    /// * f - A predicate called with each key and its count
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut counter: Counter<char> = Counter::new();
+    /// counter.update_from_iter("abracadabra".chars());
+    /// counter.retain(|key, _| *key != 'a');
+    /// assert_eq!(counter[&'a'], 0);
+    /// assert_eq!(counter[&'b'], 2);
+    /// assert_eq!(counter.len(), 4);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T, u128) -> bool,
+    {
+        self.state.retain(|key, count| f(key, *count));
+    }
+
+    /// Retain only the elements counted at least `min_count` times.
+    ///
+    /// # Arguments
+    /// * min_count - The minimum number of occurences to keep an element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut counter: Counter<char> = Counter::new();
+    /// counter.update_from_iter("abracadabra".chars());
+    /// counter.retain_at_least(2);
+    /// assert_eq!(counter[&'a'], 5);
+    /// assert_eq!(counter[&'b'], 2);
+    /// assert_eq!(counter[&'r'], 2);
+    /// assert_eq!(counter[&'c'], 0);
+    /// assert_eq!(counter.len(), 3);
+    /// ```
+    pub fn retain_at_least(&mut self, min_count: u128) {
+        self.retain(|_, count| count >= min_count);
+    }
+
+    /// Remove an element, returning its count, 0 if it was never counted.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut counter: Counter<char> = "aab".chars().collect();
+    /// assert_eq!(counter.remove(&'a'), 2);
+    /// assert_eq!(counter.remove(&'a'), 0);
+    /// assert_eq!(counter.len(), 1);
+    /// ```
+    pub fn remove<Q>(&mut self, elem: &Q) -> u128
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.state.remove(elem).unwrap_or(0)
+    }
+
+    /// Empty the `Counter`, returning all its elements and their counts.
+    ///
+    /// The `Counter` is empty as soon as this method returns, even if the
+    /// returned iterator is dropped without being consumed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut counter: Counter<char> = Counter::new();
+    /// counter.update_from_iter("aab".chars());
+    /// let drained: Vec<(char, u128)> = counter.drain().collect();
+    /// assert_eq!(drained, vec![('a', 2), ('b', 1)]);
+    /// assert!(counter.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> IntoIter<T, u128> {
+        mem::take(&mut self.state).into_iter()
+    }
+
+    /// Convert the `Counter` into the set of its distinct elements, leaving
+    /// out those whose count is 0.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// # use std::collections::BTreeSet;
+    /// let counter: Counter<&str> = vec!["b", "a", "b", "c"].into_iter().collect();
+    /// let keys: BTreeSet<&str> = counter.into_keys();
+    /// assert_eq!(keys.into_iter().collect::<Vec<&str>>(), vec!["a", "b", "c"]);
+    /// ```
+    pub fn into_keys(self) -> BTreeSet<T> {
+        self.state
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// Returns the sum of all counts.
+    ///
+    /// The sum wraps around under `OverflowPolicy::Wrapping`, and stops at
+    /// `u128::MAX` under the other policies.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, OverflowPolicy};
+    /// let mut counter: Counter<char> = Counter::new();
+    /// counter.update_from_iter("hello".chars());
+    /// assert_eq!(counter.total(), 5);
+    ///
+    /// let mut counter: Counter<char> = Counter::with_overflow_policy(OverflowPolicy::Saturating);
+    /// counter.extend(vec![('a', u128::MAX), ('b', 1)]);
+    /// assert_eq!(counter.total(), u128::MAX);
+    /// ```
+    pub fn total(&self) -> u128 {
+        self.sum_counts(self.state.values())
+    }
+
+    /// Returns the sum of counts, wrapping around under
+    /// `OverflowPolicy::Wrapping` and stopping at `u128::MAX` otherwise.
+    fn sum_counts<'a, I: Iterator<Item = &'a u128>>(&self, counts: I) -> u128 {
+        match self.overflow {
+            OverflowPolicy::Wrapping => counts.fold(0, |total, count| total.wrapping_add(*count)),
+            OverflowPolicy::Checked | OverflowPolicy::Saturating => {
+                counts.fold(0, |total, count| total.saturating_add(*count))
+            }
         }
     }
+
+    /// Returns the sum of counts stopping at `u128::MAX`, whatever the
+    /// `OverflowPolicy`, so fractions of it stay between 0 and 1.
+    fn saturating_total(&self) -> u128 {
+        self.state
+            .values()
+            .fold(0, |total, count| total.saturating_add(*count))
+    }
+
+    /// Returns the population variance of the counts of the elements, or
+    /// `None` if the `Counter` is empty. Elements with a count of 0 are
+    /// left out.
+    ///
+    /// It describes how skewed the counts are, regardless of the elements.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<char> = "aaaabbc".chars().collect();
+    /// // The counts are 4, 2 and 1.
+    /// let variance = counter.count_variance().unwrap();
+    /// assert!((variance - 14.0 / 9.0).abs() < 1e-12);
+    ///
+    /// let uniform: Counter<char> = "abc".chars().collect();
+    /// assert_eq!(uniform.count_variance(), Some(0.0));
+    /// ```
+    pub fn count_variance(&self) -> Option<f64> {
+        let counts: Vec<f64> = self
+            .state
+            .values()
+            .filter(|count| **count > 0)
+            .map(|count| *count as f64)
+            .collect();
+        if counts.is_empty() {
+            return None;
+        }
+        let n = counts.len() as f64;
+        let mean = counts.iter().sum::<f64>() / n;
+        Some(
+            counts
+                .iter()
+                .map(|count| (count - mean) * (count - mean))
+                .sum::<f64>()
+                / n,
+        )
+    }
+
+    /// Returns the population standard deviation of the counts of the
+    /// elements, or `None` if the `Counter` is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<char> = "aaaaaabb".chars().collect();
+    /// assert_eq!(counter.count_stddev(), Some(2.0));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn count_stddev(&self) -> Option<f64> {
+        self.count_variance().map(f64::sqrt)
+    }
+
+    /// Returns the most counted element along with its count, or `None` if
+    /// no element has a count greater than 0.
+    ///
+    /// Ties are broken in favor of the lowest element.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<char> = "abracadabra".chars().collect();
+    /// assert_eq!(counter.max_by_count(), Some((&'a', 5)));
+    ///
+    /// let mut unseen: Counter<char> = Counter::new();
+    /// unseen[&'z'] += 0;
+    /// assert_eq!(unseen.max_by_count(), None);
+    /// ```
+    pub fn max_by_count(&self) -> Option<(&T, u128)> {
+        let mut max: Option<(&T, u128)> = None;
+        for (key, count) in self.state.iter().filter(|(_, count)| **count > 0) {
+            match max {
+                Some((_, best)) if best >= *count => {}
+                _ => max = Some((key, *count)),
+            }
+        }
+        max
+    }
+
+    /// Returns the least counted element along with its count, or `None` if
+    /// no element has a count greater than 0.
+    ///
+    /// Ties are broken in favor of the lowest element, and elements with a
+    /// count of 0 are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut counter: Counter<char> = "abracadabra".chars().collect();
+    /// counter[&'z'] += 0;
+    /// assert_eq!(counter.min_by_count(), Some((&'c', 1)));
+    /// ```
+    pub fn min_by_count(&self) -> Option<(&T, u128)> {
+        let mut min: Option<(&T, u128)> = None;
+        for (key, count) in self.state.iter().filter(|(_, count)| **count > 0) {
+            match min {
+                Some((_, best)) if best <= *count => {}
+                _ => min = Some((key, *count)),
+            }
+        }
+        min
+    }
+
+    /// Returns the cumulative distribution of the elements, in key order.
+    ///
+    /// Each element is paired with the fraction of the total count held by
+    /// the elements lower than or equal to it. Elements with a count of 0
+    /// are left out, so the distribution is empty if the total count is 0.
+    /// Counts are summed up to `u128::MAX`, even under
+    /// `OverflowPolicy::Wrapping`, so the fractions stay between 0 and 1.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut counter: Counter<u32> = Counter::new();
+    /// counter.extend(vec![(10, 1), (20, 2), (30, 1)]);
+    /// counter[&40] += 0;
+    /// assert_eq!(counter.cdf(), vec![(&10, 0.25), (&20, 0.75), (&30, 1.0)]);
+    ///
+    /// let mut unseen: Counter<u32> = Counter::new();
+    /// unseen[&10] += 0;
+    /// assert!(unseen.cdf().is_empty());
+    /// ```
+    pub fn cdf(&self) -> Vec<(&T, f64)> {
+        let total = self.saturating_total() as f64;
+        let mut cumulative: u128 = 0;
+        self.state
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(key, count)| {
+                cumulative = cumulative.saturating_add(*count);
+                (key, cumulative as f64 / total)
+            })
+            .collect()
+    }
+
+    /// Iterate over the elements, in key order, along with their count and
+    /// their share of the total count, between 0 and 1. Nothing is yielded
+    /// if the total count is 0.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<&str> = vec!["GET", "GET", "GET", "POST"].into_iter().collect();
+    /// let percents: Vec<String> = counter
+    ///     .iter_with_share()
+    ///     .map(|(method, count, share)| format!("{} {} {:.1}%", method, count, share * 100.0))
+    ///     .collect();
+    /// assert_eq!(percents, vec!["GET 3 75.0%", "POST 1 25.0%"]);
+    ///
+    /// let mut unseen: Counter<&str> = Counter::new();
+    /// unseen[&"PUT"] += 0;
+    /// assert_eq!(unseen.iter_with_share().next(), None);
+    /// ```
+    pub fn iter_with_share(&self) -> impl Iterator<Item = (&T, u128, f64)> + '_ {
+        let total = self.saturating_total();
+        self.state
+            .iter()
+            .filter(move |_| total > 0)
+            .map(move |(key, count)| (key, *count, *count as f64 / total as f64))
+    }
+
+    /// Returns the lowest key whose cumulative fraction of the total count
+    /// reaches `p`, or `None` if the total count is 0.
+    ///
+    /// Keys with a count of 0 are never returned. Counts are summed up to
+    /// `u128::MAX`, even under `OverflowPolicy::Wrapping`.
+    ///
+    /// # Panics
+    /// Panics if `p` is not between 0 and 1.
+    ///
+    /// # Arguments
+    /// * p - The cumulative fraction to reach, between 0 and 1
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let latencies_ms: Vec<u32> = vec![10, 10, 10, 20, 20, 50, 50, 50, 50, 200];
+    /// let counter: Counter<u32> = latencies_ms.into_iter().collect();
+    /// assert_eq!(counter.percentile_key(0.5), Some(&20));
+    /// assert_eq!(counter.percentile_key(0.9), Some(&50));
+    /// assert_eq!(counter.percentile_key(1.0), Some(&200));
+    ///
+    /// let mut unseen: Counter<u32> = Counter::new();
+    /// unseen[&10] += 0;
+    /// assert_eq!(unseen.percentile_key(0.0), None);
+    /// ```
+    pub fn percentile_key(&self, p: f64) -> Option<&T> {
+        assert!((0.0..=1.0).contains(&p));
+        let target = p * self.saturating_total() as f64;
+        let mut cumulative: u128 = 0;
+        for (key, count) in self.state.iter().filter(|(_, count)| **count > 0) {
+            cumulative = cumulative.saturating_add(*count);
+            if cumulative as f64 >= target {
+                return Some(key);
+            }
+        }
+        None
+    }
+
+    /// Sample `k` distinct elements, each draw favouring the elements
+    /// with a high count, or every element if there are less than `k`.
+    ///
+    /// The A-Res algorithm is used: each element gets the priority `u^(1/count)`
+    /// where `u` is drawn uniformly, and the `k` highest priorities win.
+    /// Elements with a count of 0 are never sampled. Sampled elements are
+    /// returned by decreasing priority.
+    ///
+    /// # Arguments
+    /// * k - The number of elements to sample
+    /// * rng - A function returning uniformly distributed values in `[0, 1)`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// // A tiny xorshift generator, any source of uniform values works.
+    /// let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    /// let mut rng = move || {
+    ///     state ^= state << 13;
+    ///     state ^= state >> 7;
+    ///     state ^= state << 17;
+    ///     (state >> 11) as f64 / (1u64 << 53) as f64
+    /// };
+    ///
+    /// let mut counter: Counter<&str> = Counter::new();
+    /// counter.extend(vec![("common", 1_000_000), ("rare", 1), ("unseen", 0)]);
+    /// assert_eq!(counter.sample_without_replacement(1, &mut rng), vec![&"common"]);
+    ///
+    /// let mut all = counter.sample_without_replacement(5, &mut rng);
+    /// all.sort();
+    /// assert_eq!(all, vec![&"common", &"rare"]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn sample_without_replacement<R>(&self, k: usize, mut rng: R) -> Vec<&T>
+    where
+        R: FnMut() -> f64,
+    {
+        // `ln(u) / count` orders the elements as `u^(1/count)` does, without
+        // underflowing for large counts.
+        let mut priorities: Vec<(f64, &T)> = self
+            .state
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(key, count)| (rng().ln() / *count as f64, key))
+            .collect();
+        let by_priority = |a: &(f64, &T), b: &(f64, &T)| b.0.total_cmp(&a.0);
+        if k < priorities.len() {
+            let _ = priorities.select_nth_unstable_by(k, by_priority);
+            priorities.truncate(k);
+        }
+        priorities.sort_by(by_priority);
+        priorities.into_iter().map(|(_, key)| key).collect()
+    }
+
+    /// Returns the counts sorted in decreasing order along with their rank,
+    /// starting at 1. Elements with a count of 0 are left out.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<&str> = "the cat and the dog and the bird"
+    ///     .split(' ')
+    ///     .collect();
+    /// assert_eq!(
+    ///     counter.rank_frequency(),
+    ///     vec![(1, 3), (2, 2), (3, 1), (4, 1), (5, 1)]
+    /// );
+    /// ```
+    pub fn rank_frequency(&self) -> Vec<(usize, u128)> {
+        let mut counts: Vec<u128> = self
+            .state
+            .values()
+            .cloned()
+            .filter(|count| *count > 0)
+            .collect();
+        counts.sort_by_key(|count| Reverse(*count));
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(idx, count)| (idx + 1, count))
+            .collect()
+    }
+
+    /// Estimate the exponent `s` of the Zipf law `count = C / rank^s` fitting
+    /// the rank-frequency curve, or `None` if there are less than 2 elements.
+    ///
+    /// The estimate is the opposite of the slope of the least squares line
+    /// through the `(ln(rank), ln(count))` points.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut counter: Counter<u32> = Counter::new();
+    /// counter.extend(vec![(1, 1200), (2, 600), (3, 400), (4, 300)]);
+    /// let exponent = counter.zipf_exponent_estimate().unwrap();
+    /// assert!((exponent - 1.0).abs() < 1e-9);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn zipf_exponent_estimate(&self) -> Option<f64> {
+        let points: Vec<(f64, f64)> = self
+            .rank_frequency()
+            .into_iter()
+            .map(|(rank, count)| ((rank as f64).ln(), (count as f64).ln()))
+            .collect();
+        if points.len() < 2 {
+            return None;
+        }
+
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let covariance: f64 = points
+            .iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum();
+        let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        Some(-covariance / variance)
+    }
+
+    /// Returns `true` if every count of the `Counter` is lower than or equal
+    /// to the count of the same element in `other`.
+    ///
+    /// # Arguments
+    /// * other - The `Counter` to compare against
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut order: Counter<&str> = Counter::new();
+    /// order.extend(vec![("apple", 2), ("pear", 1)]);
+    /// let mut inventory: Counter<&str> = Counter::new();
+    /// inventory.extend(vec![("apple", 5), ("pear", 1), ("plum", 3)]);
+    /// assert!(order.is_subset(&inventory));
+    /// assert!(!inventory.is_subset(&order));
+    /// ```
+    pub fn is_subset(&self, other: &Counter<T>) -> bool {
+        self.state
+            .iter()
+            .all(|(key, count)| *count <= other.state.get(key).cloned().unwrap_or(0))
+    }
+
+    /// Returns `true` if every count of `other` is lower than or equal to
+    /// the count of the same element in the `Counter`.
+    ///
+    /// # Arguments
+    /// * other - The `Counter` to compare against
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let inventory: Counter<char> = "aabbc".chars().collect();
+    /// assert!(inventory.is_superset(&"abb".chars().collect()));
+    /// assert!(!inventory.is_superset(&"aaa".chars().collect()));
+    /// ```
+    pub fn is_superset(&self, other: &Counter<T>) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns `true` if no element is counted in both the `Counter` and `other`.
+    ///
+    /// # Arguments
+    /// * other - The `Counter` to compare against
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<char> = "abc".chars().collect();
+    /// assert!(counter.is_disjoint(&"xyz".chars().collect()));
+    /// assert!(!counter.is_disjoint(&"cde".chars().collect()));
+    /// ```
+    pub fn is_disjoint(&self, other: &Counter<T>) -> bool {
+        self.state
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .all(|(key, _)| other.state.get(key).cloned().unwrap_or(0) == 0)
+    }
+
+    /// Returns a new `Counter` holding, for each element, the absolute
+    /// difference between its count in the `Counter` and in `other`.
+    ///
+    /// Elements with the same count in both are left out.
+    ///
+    /// # Arguments
+    /// * other - The `Counter` to compare against
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let yesterday: Counter<char> = "aaabbc".chars().collect();
+    /// let today: Counter<char> = "abbbbd".chars().collect();
+    /// let diff = yesterday.symmetric_difference(&today);
+    /// assert_eq!(diff[&'a'], 2);
+    /// assert_eq!(diff[&'b'], 2);
+    /// assert_eq!(diff[&'c'], 1);
+    /// assert_eq!(diff[&'d'], 1);
+    /// assert_eq!(diff, today.symmetric_difference(&yesterday));
+    /// ```
+    pub fn symmetric_difference(&self, other: &Counter<T>) -> Counter<T>
+    where
+        T: Clone,
+    {
+        let mut difference: Counter<T> = Counter::new();
+        for (key, count) in self.state.iter() {
+            let other_count = other.state.get(key).cloned().unwrap_or(0);
+            if *count != other_count {
+                let _ = difference.state.insert(
+                    key.clone(),
+                    count.max(&other_count) - count.min(&other_count),
+                );
+            }
+        }
+        for (key, count) in other.state.iter() {
+            if *count > 0 && !self.state.contains_key(key) {
+                let _ = difference.state.insert(key.clone(), *count);
+            }
+        }
+        difference
+    }
+
+    /// Compare the `Counter` with `other`, taken as its newer state.
+    ///
+    /// Elements with a count of 0 are considered absent.
+    ///
+    /// # Arguments
+    /// * other - The newer `Counter`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let yesterday: Counter<&str> = vec!["GET", "GET", "PUT", "DELETE"].into_iter().collect();
+    /// let today: Counter<&str> = vec!["GET", "PUT", "PUT", "POST"].into_iter().collect();
+    /// let diff = yesterday.diff(&today);
+    /// assert_eq!(diff.added, vec![(&"POST", 1)]);
+    /// assert_eq!(diff.removed, vec![(&"DELETE", 1)]);
+    /// assert_eq!(diff.changed, vec![(&"GET", -1), (&"PUT", 1)]);
+    /// assert!(today.diff(&today).is_empty());
+    /// ```
+    pub fn diff<'a>(&'a self, other: &'a Counter<T>) -> CounterDiff<'a, T> {
+        let mut diff = CounterDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+        for (key, count) in self.state.iter().filter(|(_, count)| **count > 0) {
+            match other.state.get(key).cloned().unwrap_or(0) {
+                0 => diff.removed.push((key, *count)),
+                other_count if other_count != *count => {
+                    let delta = signed(other_count) - signed(*count);
+                    diff.changed.push((key, delta));
+                }
+                _ => {}
+            }
+        }
+        for (key, count) in other.state.iter().filter(|(_, count)| **count > 0) {
+            if self.state.get(key).cloned().unwrap_or(0) == 0 {
+                diff.added.push((key, *count));
+            }
+        }
+        diff
+    }
+
+    /// Iterate over the elements within `range`, in key order, along with
+    /// their counts.
+    ///
+    /// # Arguments
+    /// * range - The range of keys to iterate over
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<u32> = vec![50, 100, 150, 150, 200, 250].into_iter().collect();
+    /// let in_range: Vec<(&u32, u128)> = counter.range(100..200).collect();
+    /// assert_eq!(in_range, vec![(&100, 1), (&150, 2)]);
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> impl Iterator<Item = (&T, u128)> + '_
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        self.state.range(range).map(|(key, count)| (key, *count))
+    }
+
+    /// Returns the sum of the counts of the elements within `range`,
+    /// overflowing as `total` does.
+    ///
+    /// # Arguments
+    /// * range - The range of keys to sum the counts of
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<u32> = vec![50, 100, 150, 150, 200, 250].into_iter().collect();
+    /// assert_eq!(counter.count_in_range(100..=200), 4);
+    /// assert_eq!(counter.count_in_range(300..), 0);
+    /// ```
+    pub fn count_in_range<Q, R>(&self, range: R) -> u128
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        self.sum_counts(self.state.range(range).map(|(_, count)| count))
+    }
+
+    /// Build an optimal prefix code from the counts, mapping each element to
+    /// its code as bits, most significant first.
+    ///
+    /// The code is canonical: codes are assigned in order of length then
+    /// key, so the table only depends on the code lengths. Elements with a
+    /// count of 0 are left out, a lone element gets a 1 bit code.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<char> = "abracadabra".chars().collect();
+    /// let codes = counter.to_huffman_codes();
+    /// assert_eq!(codes[&'a'], vec![false]);
+    /// assert_eq!(codes[&'b'], vec![true, false, false]);
+    /// assert_eq!(codes[&'c'], vec![true, false, true]);
+    /// assert_eq!(codes[&'d'], vec![true, true, false]);
+    /// assert_eq!(codes[&'r'], vec![true, true, true]);
+    ///
+    /// let encoded: usize = "abracadabra".chars().map(|c| codes[&c].len()).sum();
+    /// assert_eq!(encoded, 23);
+    /// ```
+    pub fn to_huffman_codes(&self) -> BTreeMap<T, Vec<bool>>
+    where
+        T: Clone,
+    {
+        let symbols: Vec<(&T, u128)> = self
+            .state
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(key, count)| (key, *count))
+            .collect();
+        if symbols.len() == 1 {
+            return symbols
+                .into_iter()
+                .map(|(key, _)| (key.clone(), vec![false]))
+                .collect();
+        }
+
+        // Nodes 0..symbols.len() are the leaves, merged nodes come next.
+        let mut parents: Vec<usize> = vec![0; symbols.len()];
+        let mut heap: BinaryHeap<Reverse<(u128, usize)>> = symbols
+            .iter()
+            .enumerate()
+            .map(|(node, (_, count))| Reverse((*count, node)))
+            .collect();
+        while let (Some(Reverse(first)), Some(Reverse(second))) = (heap.pop(), heap.pop()) {
+            let node = parents.len();
+            parents[first.1] = node;
+            parents[second.1] = node;
+            parents.push(node);
+            heap.push(Reverse((first.0.saturating_add(second.0), node)));
+        }
+        let mut depths: Vec<usize> = vec![0; parents.len()];
+        for node in (0..parents.len().saturating_sub(1)).rev() {
+            depths[node] = depths[parents[node]] + 1;
+        }
+
+        let mut lengths: Vec<(usize, &T)> = symbols
+            .iter()
+            .enumerate()
+            .map(|(node, (key, _))| (depths[node], *key))
+            .collect();
+        lengths.sort_by_key(|(length, _)| *length);
+
+        let mut codes: BTreeMap<T, Vec<bool>> = BTreeMap::new();
+        let mut code: Vec<bool> = Vec::new();
+        for (idx, (length, key)) in lengths.into_iter().enumerate() {
+            if idx > 0 {
+                // Increment the previous code, as a binary number.
+                while let Some(true) = code.last() {
+                    let _ = code.pop();
+                }
+                if let Some(bit) = code.last_mut() {
+                    *bit = true;
+                }
+            }
+            code.resize(length, false);
+            let _ = codes.insert(key.clone(), code.clone());
+        }
+        codes
+    }
+
+    /// Create a new `Counter` from the tokens of a reader.
+    ///
+    /// The reader is consumed line by line through a buffer, so the whole
+    /// input never needs to be loaded in memory.
+    ///
+    /// # Arguments
+    /// * reader - The reader to consume, it must yield valid UTF-8
+    /// * tokenizer - A function splitting a line, without its line ending, into tokens
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let input = "1,2\n2,3\n".as_bytes();
+    /// let counter: Counter<u8> = Counter::count_from_read(input, |line| {
+    ///     line.split(',')
+    ///         .map(|token| token.parse().unwrap())
+    ///         .collect::<Vec<u8>>()
+    /// })
+    /// .unwrap();
+    /// assert_eq!(counter[&2], 2);
+    /// assert_eq!(counter[&3], 1);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn count_from_read<R, F, I>(reader: R, mut tokenizer: F) -> io::Result<Self>
+    where
+        R: Read,
+        F: FnMut(&str) -> I,
+        I: IntoIterator<Item = T>,
+    {
+        let mut counter: Counter<T> = Counter::new();
+        let mut reader = BufReader::new(reader);
+        let mut line = String::new();
+        while reader.read_line(&mut line)? != 0 {
+            let content = line.trim_end_matches(['\n', '\r']);
+            counter.update_from_iter(tokenizer(content).into_iter());
+            line.clear();
+        }
+        Ok(counter)
+    }
+
+    pub(crate) fn try_add_count(
+        &mut self,
+        elem: T,
+        occurences: u128,
+    ) -> Result<(), CountOverflowError> {
+        let elem = match self.normalizer {
+            Some(normalize) => normalize(elem),
+            None => elem,
+        };
+        let overflow = self.overflow;
+        let count = self.state.entry(elem).or_insert(0);
+        *count = overflow.add(*count, occurences)?;
+        Ok(())
+    }
+
+    fn add_count(&mut self, elem: T, occurences: u128) {
+        self.try_add_count(elem, occurences)
+            .expect("count overflow");
+    }
+}
+
+/// Filters applied to a text before counting its characters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextOptions {
+    /// Skip whitespace characters
+    pub ignore_whitespace: bool,
+
+    /// Count characters in their lowercase form
+    pub lowercase: bool,
+}
+
+/// Changes between two `Counter`, as returned by `Counter::diff`.
+///
+/// Each list is sorted by key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CounterDiff<'a, T> {
+    /// Elements only counted in the newer `Counter`, with their count
+    pub added: Vec<(&'a T, u128)>,
+
+    /// Elements only counted in the older `Counter`, with their count
+    pub removed: Vec<(&'a T, u128)>,
+
+    /// Elements counted in both with different counts, with the change of
+    /// count, saturated to the `i128` range
+    pub changed: Vec<(&'a T, i128)>,
+}
+
+impl<T> CounterDiff<'_, T> {
+    /// Returns `true` if both `Counter` hold the same counts, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Convert a count to a signed value, saturating the counts above `i128::MAX`.
+pub(crate) fn signed(count: u128) -> i128 {
+    i128::try_from(count).unwrap_or(i128::MAX)
+}
+
+impl<T: Ord + Display> Counter<T> {
+    /// Render the `Counter` as a text bar chart, one line per element.
+    ///
+    /// Each line holds the element, its count and a bar of `#` scaled so
+    /// that the most counted element gets `width` of them.
+    ///
+    /// # Arguments
+    /// * width - The length of the longest bar
+    /// * order - How the lines are sorted
+    /// * limit - The maximum number of lines, `None` to render every element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, SortOrder};
+    /// let mut counter: Counter<char> = Counter::new();
+    /// counter.update_from_iter("abracadabra".chars());
+    /// let histogram = counter.to_histogram(10, SortOrder::ByCount, Some(3));
+    /// assert_eq!(
+    ///     histogram,
+    ///     "a 5 ##########\n\
+    ///      b 2 ####\n\
+    ///      r 2 ####\n"
+    /// );
+    ///
+    /// let mut huge: Counter<char> = Counter::new();
+    /// huge.extend(vec![('x', u128::MAX / 2), ('y', u128::MAX / 4)]);
+    /// let histogram = huge.to_histogram(40, SortOrder::ByKey, None);
+    /// assert_eq!(histogram.lines().nth(1).unwrap().matches('#').count(), 20);
+    /// ```
+    pub fn to_histogram(&self, width: usize, order: SortOrder, limit: Option<usize>) -> String {
+        let mut lines: Vec<(String, u128)> = self
+            .state
+            .iter()
+            .map(|(key, count)| (key.to_string(), *count))
+            .collect();
+        if order == SortOrder::ByCount {
+            lines.sort_by_key(|(_, count)| Reverse(*count));
+        }
+        lines.truncate(limit.unwrap_or(lines.len()));
+
+        let key_width = lines.iter().map(|(key, _)| key.chars().count()).max();
+        let count_width = lines.iter().map(|(_, count)| count.to_string().len()).max();
+        let max_count = lines.iter().map(|(_, count)| *count).max().unwrap_or(0);
+        let mut histogram = String::new();
+        for (key, count) in lines.iter() {
+            // Scaled in floating point, as `count * width` may overflow.
+            let bar = *count as f64 * width as f64 / max_count.max(1) as f64 + 0.5;
+            let _ = writeln!(
+                histogram,
+                "{:<key_width$} {:>count_width$} {}",
+                key,
+                count,
+                "#".repeat(bar as usize),
+                key_width = key_width.unwrap_or(0),
+                count_width = count_width.unwrap_or(0),
+            );
+        }
+        histogram
+    }
+}
+
+impl<T: Ord + Copy + Into<f64>> Counter<T> {
+    /// Returns the mean of the counted values weighted by their counts, or
+    /// `None` if the total count is 0.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<u8> = vec![1, 2, 2, 3].into_iter().collect();
+    /// assert_eq!(counter.mean(), Some(2.0));
+    ///
+    /// let mut uncounted: Counter<u8> = Counter::new();
+    /// uncounted[&7] = 0;
+    /// assert_eq!(uncounted.mean(), None);
+    /// ```
+    pub fn mean(&self) -> Option<f64> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+        let sum: f64 = self
+            .state
+            .iter()
+            .map(|(key, count)| (*key).into() * *count as f64)
+            .sum();
+        Some(sum / total as f64)
+    }
+
+    /// Returns the median of the counted values weighted by their counts,
+    /// or `None` if the total count is 0.
+    ///
+    /// When the total count is even, the median is the mean of the two
+    /// middle values.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<u8> = vec![1, 2, 2, 3, 9].into_iter().collect();
+    /// assert_eq!(counter.median(), Some(2.0));
+    /// let counter: Counter<u8> = vec![1, 2, 3, 9].into_iter().collect();
+    /// assert_eq!(counter.median(), Some(2.5));
+    /// ```
+    pub fn median(&self) -> Option<f64> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+        let lower_rank = (total - 1) / 2;
+        let upper_rank = total / 2;
+        let mut lower: Option<f64> = None;
+        let mut seen: u128 = 0;
+        for (key, count) in self.state.iter() {
+            seen = seen.saturating_add(*count);
+            if lower.is_none() && seen > lower_rank {
+                lower = Some((*key).into());
+            }
+            if seen > upper_rank {
+                return lower.map(|lower| (lower + (*key).into()) / 2.0);
+            }
+        }
+        None
+    }
+
+    /// Returns the most counted value, or `None` if the total count is 0.
+    ///
+    /// Ties are broken in favor of the lowest value, and values with a count
+    /// of 0 are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<u8> = vec![3, 1, 3, 1, 2].into_iter().collect();
+    /// assert_eq!(counter.mode(), Some(1));
+    ///
+    /// let mut unseen: Counter<u8> = Counter::new();
+    /// unseen[&0] += 0;
+    /// assert_eq!(unseen.mode(), None);
+    /// ```
+    pub fn mode(&self) -> Option<T> {
+        self.max_by_count().map(|(key, _)| *key)
+    }
+
+    /// Returns the population variance of the counted values weighted by
+    /// their counts, or `None` if the total count is 0.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<u8> = vec![2, 4, 4, 4, 5, 5, 7, 9].into_iter().collect();
+    /// assert_eq!(counter.variance(), Some(4.0));
+    /// ```
+    pub fn variance(&self) -> Option<f64> {
+        let mean = self.mean()?;
+        let sum: f64 = self
+            .state
+            .iter()
+            .map(|(key, count)| {
+                let delta = (*key).into() - mean;
+                delta * delta * *count as f64
+            })
+            .sum();
+        Some(sum / self.total() as f64)
+    }
+}
+
+impl Counter<char> {
+    /// Create a new `Counter` from the characters of a text.
+    ///
+    /// # Arguments
+    /// * text - The text whose characters are counted
+    /// * options - Filters applied to the text before counting
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, TextOptions};
+    /// let options = TextOptions {
+    ///     ignore_whitespace: true,
+    ///     lowercase: true,
+    /// };
+    /// let counter = Counter::from_text("Hello World", options);
+    /// assert_eq!(counter[&'l'], 3);
+    /// assert_eq!(counter[&'h'], 1);
+    /// assert_eq!(counter[&'H'], 0);
+    /// assert_eq!(counter[&' '], 0);
+    /// ```
+    pub fn from_text(text: &str, options: TextOptions) -> Self {
+        let mut counter: Counter<char> = Counter::new();
+        let chars = text
+            .chars()
+            .filter(|c| !(options.ignore_whitespace && c.is_whitespace()));
+        if options.lowercase {
+            counter.update_from_iter(chars.flat_map(char::to_lowercase));
+        } else {
+            counter.update_from_iter(chars);
+        }
+        counter
+    }
+}
+
+impl<T: Ord + Clone> Counter<Vec<T>> {
+    /// Update the `Counter` with every sliding window of `n` elements of an iterator.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0, or if a count overflows under
+    /// `OverflowPolicy::Checked`.
+    ///
+    /// # Arguments
+    /// * iter - An iterator whose n-grams are counted
+    /// * n - The size of the sliding window, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut counter: Counter<Vec<char>> = Counter::new();
+    /// counter.update_from_ngrams("abcab".chars(), 2);
+    /// assert_eq!(counter[&vec!['a', 'b']], 2);
+    /// assert_eq!(counter[&vec!['b', 'c']], 1);
+    /// assert_eq!(counter[&vec!['c', 'a']], 1);
+    /// assert_eq!(counter.len(), 3);
+    /// ```
+    pub fn update_from_ngrams<I>(&mut self, iter: I, n: usize)
+    where
+        I: Iterator<Item = T>,
+    {
+        assert!(n > 0);
+        let mut window: VecDeque<T> = VecDeque::with_capacity(n);
+        for elem in iter {
+            if window.len() == n {
+                let _ = window.pop_front();
+            }
+            window.push_back(elem);
+            if window.len() == n {
+                self.update_from_value(window.iter().cloned().collect());
+            }
+        }
+    }
+
+    /// Create a new `Counter` from every sliding window of `n` elements of an iterator.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0.
+    ///
+    /// # Arguments
+    /// * iter - An iterator whose n-grams are counted
+    /// * n - The size of the sliding window, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let words = vec!["to", "be", "or", "not", "to", "be"];
+    /// let counter = Counter::from_ngrams(words.into_iter(), 3);
+    /// assert_eq!(counter[&vec!["to", "be", "or"]], 1);
+    /// assert_eq!(counter.len(), 4);
+    /// ```
+    pub fn from_ngrams<I>(iter: I, n: usize) -> Self
+    where
+        I: Iterator<Item = T>,
+    {
+        let mut counter: Counter<Vec<T>> = Counter::new();
+        counter.update_from_ngrams(iter, n);
+        counter
+    }
+}
+
+impl<T: Ord + Clone> Counter<(T, T)> {
+    /// Update the `Counter` with every pair of consecutive elements of an iterator.
+    ///
+    /// # Arguments
+    /// * iter - An iterator whose bigrams are counted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut counter: Counter<(char, char)> = Counter::new();
+    /// counter.update_from_bigrams("abab".chars());
+    /// assert_eq!(counter[&('a', 'b')], 2);
+    /// assert_eq!(counter[&('b', 'a')], 1);
+    /// ```
+    pub fn update_from_bigrams<I>(&mut self, iter: I)
+    where
+        I: Iterator<Item = T>,
+    {
+        let mut previous: Option<T> = None;
+        for elem in iter {
+            if let Some(prev) = previous.take() {
+                self.update_from_value((prev, elem.clone()));
+            }
+            previous = Some(elem);
+        }
+    }
+
+    /// Create a new `Counter` from every pair of consecutive elements of an iterator.
+    ///
+    /// # Arguments
+    /// * iter - An iterator whose bigrams are counted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter = Counter::from_bigrams("aaa".chars());
+    /// assert_eq!(counter[&('a', 'a')], 2);
+    /// ```
+    pub fn from_bigrams<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = T>,
+    {
+        let mut counter: Counter<(T, T)> = Counter::new();
+        counter.update_from_bigrams(iter);
+        counter
+    }
+
+    /// Update the `Counter` with every unordered pair of distinct elements
+    /// occuring together in a group.
+    ///
+    /// A pair is stored with its lowest element first, and counted once per
+    /// group however many times its elements occur in it.
+    ///
+    /// # Arguments
+    /// * groups - An iterator over the groups, such as windows or baskets
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut counter: Counter<(&str, &str)> = Counter::new();
+    /// counter.update_from_pairs(vec![vec!["milk", "bread", "milk"], vec!["bread", "eggs"]]);
+    /// assert_eq!(counter[&("bread", "milk")], 1);
+    /// assert_eq!(counter[&("bread", "eggs")], 1);
+    /// assert_eq!(counter[&("milk", "milk")], 0);
+    /// assert_eq!(counter.len(), 2);
+    /// ```
+    pub fn update_from_pairs<I, G>(&mut self, groups: I)
+    where
+        I: IntoIterator<Item = G>,
+        G: IntoIterator<Item = T>,
+    {
+        for group in groups {
+            let mut elements: Vec<T> = group.into_iter().collect();
+            elements.sort();
+            elements.dedup();
+            for (idx, first) in elements.iter().enumerate() {
+                for second in &elements[idx + 1..] {
+                    self.update_from_value((first.clone(), second.clone()));
+                }
+            }
+        }
+    }
+
+    /// Create a new `Counter` from every unordered pair of distinct elements
+    /// occuring together in a group.
+    ///
+    /// # Arguments
+    /// * groups - An iterator over the groups, such as windows or baskets
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let sentences = vec!["the cat sat", "the cat ran", "a dog ran"];
+    /// let counter = Counter::count_pairs(sentences.iter().map(|s| s.split(' ')));
+    /// assert_eq!(counter[&("cat", "the")], 2);
+    /// assert_eq!(counter[&("cat", "ran")], 1);
+    /// assert_eq!(counter[&("dog", "the")], 0);
+    /// ```
+    pub fn count_pairs<I, G>(groups: I) -> Self
+    where
+        I: IntoIterator<Item = G>,
+        G: IntoIterator<Item = T>,
+    {
+        let mut counter: Counter<(T, T)> = Counter::new();
+        counter.update_from_pairs(groups);
+        counter
+    }
+}
+
+#[cfg(feature = "std")]
+impl Counter<String> {
+    /// Create a new `Counter` from the lines of a reader.
+    ///
+    /// # Arguments
+    /// * reader - The reader to consume, it must yield valid UTF-8
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let input = "GET /\nPOST /login\r\nGET /\n".as_bytes();
+    /// let counter = Counter::count_lines(input).unwrap();
+    /// assert_eq!(counter["GET /"], 2);
+    /// assert_eq!(counter["POST /login"], 1);
+    /// ```
+    pub fn count_lines<R: Read>(reader: R) -> io::Result<Self> {
+        Counter::count_from_read(reader, |line| Some(line.to_owned()))
+    }
+
+    /// Create a new `Counter` from the whitespace separated words of a reader.
+    ///
+    /// # Arguments
+    /// * reader - The reader to consume, it must yield valid UTF-8
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let input = "the cat\nand  the dog\n".as_bytes();
+    /// let counter = Counter::count_words(input).unwrap();
+    /// assert_eq!(counter["the"], 2);
+    /// assert_eq!(counter["dog"], 1);
+    /// assert_eq!(counter.len(), 4);
+    /// ```
+    pub fn count_words<R: Read>(reader: R) -> io::Result<Self> {
+        Counter::count_from_read(reader, |line| {
+            line.split_whitespace()
+                .map(String::from)
+                .collect::<Vec<String>>()
+        })
+    }
+
+    /// Create a new `Counter` from the lines of a file.
+    ///
+    /// # Arguments
+    /// * path - The path of a file containing valid UTF-8
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// # let path = std::env::temp_dir().join("collectors_from_file_lines.txt");
+    /// # std::fs::write(&path, "error\nwarning\nerror\n").unwrap();
+    /// let counter = Counter::from_file_lines(&path).unwrap();
+    /// assert_eq!(counter["error"], 2);
+    /// assert_eq!(counter["warning"], 1);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn from_file_lines<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Counter::count_lines(File::open(path)?)
+    }
+
+    /// Create a new `Counter` from the whitespace separated words of a file.
+    ///
+    /// # Arguments
+    /// * path - The path of a file containing valid UTF-8
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// # let path = std::env::temp_dir().join("collectors_from_file_words.txt");
+    /// # std::fs::write(&path, "the cat\nand the dog\n").unwrap();
+    /// let counter = Counter::from_file_words(&path).unwrap();
+    /// assert_eq!(counter["the"], 2);
+    /// assert_eq!(counter["cat"], 1);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// assert!(Counter::from_file_words("/this/file/does/not/exist").is_err());
+    /// ```
+    pub fn from_file_words<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Counter::count_words(File::open(path)?)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Counter<u8> {
+    /// Create a new `Counter` from the bytes of a file.
+    ///
+    /// # Arguments
+    /// * path - The path of the file
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// # let path = std::env::temp_dir().join("collectors_from_file_bytes.bin");
+    /// # std::fs::write(&path, [0u8, 1, 1, 255]).unwrap();
+    /// let counter = Counter::from_file_bytes(&path).unwrap();
+    /// assert_eq!(counter[&1], 2);
+    /// assert_eq!(counter[&255], 1);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn from_file_bytes<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut counter: Counter<u8> = Counter::new();
+        let mut reader = BufReader::new(File::open(path)?);
+        loop {
+            let read = {
+                let buffer = reader.fill_buf()?;
+                counter.update_from_iter(buffer.iter().cloned());
+                buffer.len()
+            };
+            if read == 0 {
+                break;
+            }
+            reader.consume(read);
+        }
+        Ok(counter)
+    }
+}
+
+/// Count every character of a string, without any filtering.
+///
+/// # Examples
+/// ```
+/// # use collectors::Counter;
+/// let counter: Counter<char> = "a string".parse().unwrap();
+/// assert_eq!(counter[&'a'], 1);
+/// assert_eq!(counter[&' '], 1);
+/// ```
+impl FromStr for Counter<char> {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Counter::from_text(s, TextOptions::default()))
+    }
+}
+
+impl<T: Ord> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter: Counter<T> = Counter::new();
+        counter.update_from_iter(iter.into_iter());
+        counter
+    }
+}
+
+/// Count every value yielded by an iterator.
+///
+/// # Examples
+/// ```
+/// # use collectors::Counter;
+/// let mut counter: Counter<&str> = Counter::new();
+/// counter.extend("a b a".split(' '));
+/// assert_eq!(counter[&"a"], 2);
+/// assert_eq!(counter[&"b"], 1);
+/// ```
+impl<T: Ord> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.update_from_iter(iter.into_iter());
+    }
+}
+
+/// Add the counts of `(element, count)` pairs yielded by an iterator.
+///
+/// # Examples
+/// ```
+/// # use collectors::Counter;
+/// let mut counter: Counter<char> = Counter::new();
+/// counter.update_from_value('a');
+/// counter.extend(vec![('a', 2), ('b', 5)]);
+/// assert_eq!(counter[&'a'], 3);
+/// assert_eq!(counter[&'b'], 5);
+/// ```
+impl<T: Ord> Extend<(T, u128)> for Counter<T> {
+    fn extend<I: IntoIterator<Item = (T, u128)>>(&mut self, iter: I) {
+        for (elem, occurences) in iter {
+            self.add_count(elem, occurences);
+        }
+    }
+}
+
+impl<T> IntoIterator for Counter<T> {
+    type Item = (T, u128);
+    type IntoIter = IntoIter<T, u128>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.state.into_iter()
+    }
+}
+
+/// Access the count of an element, missing elements are counted as 0.
+///
+/// The key may be any borrowed form of the element type, so a
+/// `Counter<String>` can be indexed with a `&str`. It is not passed through
+/// the normalizer of the `Counter`, so it must be given normalized.
+///
+/// # Examples
+/// ```
+/// # use collectors::Counter;
+/// let mut counter: Counter<String> = Counter::new();
+/// counter.update_from_value(String::from("word"));
+/// assert_eq!(counter["word"], 1);
+/// assert_eq!(counter["other"], 0);
+/// ```
+impl<T, Q> Index<&Q> for Counter<T>
+where
+    T: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = u128;
+
+    fn index(&self, index: &Q) -> &Self::Output {
+        match self.state.get(index) {
+            Some(value) => value,
+            None => &0,
+        }
+    }
+}
+
+/// Mutable access to the count of an element.
+///
+/// Missing elements are inserted with a count of 0 before being returned,
+/// which requires the borrowed key to be convertible to an owned element.
+/// As with `Index`, the key is not passed through the normalizer.
+///
+/// The count is handed out as a plain `u128`, so arithmetic on it ignores
+/// the `OverflowPolicy` of the `Counter`: `counter[key] += n` panics on
+/// overflow in debug builds and wraps in release builds, whatever the
+/// policy. Use `extend` or `try_update_from_value` to add occurrences
+/// under the policy.
+///
+/// # Examples
+/// ```
+/// # use collectors::{Counter, OverflowPolicy};
+/// let mut counter: Counter<char> = Counter::new();
+/// counter[&'a'] += 3;
+/// assert_eq!(counter[&'a'], 3);
+/// counter[&'a'] = 0;
+/// assert_eq!(counter[&'a'], 0);
+/// assert_eq!(counter.len(), 1);
+///
+/// let mut counter: Counter<String> = Counter::new();
+/// counter["word"] += 2;
+/// assert_eq!(counter["word"], 2);
+///
+/// // Only the counting methods apply the overflow policy.
+/// let mut counter: Counter<char> = Counter::with_overflow_policy(OverflowPolicy::Saturating);
+/// counter[&'a'] = u128::MAX;
+/// counter.extend(Some(('a', 1)));
+/// assert_eq!(counter[&'a'], u128::MAX);
+/// ```
+impl<T, Q> IndexMut<&Q> for Counter<T>
+where
+    T: Ord + Borrow<Q>,
+    Q: Ord + ToOwned<Owned = T> + ?Sized,
+{
+    fn index_mut(&mut self, index: &Q) -> &mut Self::Output {
+        if !self.state.contains_key(index) {
+            let _ = self.state.insert(index.to_owned(), 0);
+        }
+        self.state.get_mut(index).unwrap()
+    }
+}
+
+/// Multiply every count by a scalar.
+///
+/// Elements whose count becomes 0 are removed.
+///
+/// # Panics
+/// Panics if a count overflows under `OverflowPolicy::Checked`, see
+/// `Counter::try_mul` for a fallible version.
+///
+/// # Examples
+/// ```
+/// # use collectors::Counter;
+/// let counter: Counter<char> = "aab".chars().collect();
+/// let counter = counter * 3;
+/// assert_eq!(counter[&'a'], 6);
+/// assert_eq!(counter[&'b'], 3);
+/// ```
+impl<T: Ord> Mul<u128> for Counter<T> {
+    type Output = Counter<T>;
+
+    fn mul(mut self, rhs: u128) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
+
+impl<T: Ord> MulAssign<u128> for Counter<T> {
+    fn mul_assign(&mut self, rhs: u128) {
+        self.try_mul(rhs).expect("count overflow");
+    }
+}
+
+/// Divide every count by a scalar, rounding down.
+///
+/// Elements whose count becomes 0 are removed.
+///
+/// # Examples
+/// ```
+/// # use collectors::Counter;
+/// let mut counter: Counter<char> = Counter::new();
+/// counter.extend(vec![('a', 60), ('b', 90), ('c', 20)]);
+/// let per_minute = counter / 30;
+/// assert_eq!(per_minute[&'a'], 2);
+/// assert_eq!(per_minute[&'b'], 3);
+/// assert_eq!(per_minute.len(), 2);
+/// ```
+impl<T: Ord> Div<u128> for Counter<T> {
+    type Output = Counter<T>;
+
+    fn div(mut self, rhs: u128) -> Self::Output {
+        self /= rhs;
+        self
+    }
+}
+
+impl<T: Ord> DivAssign<u128> for Counter<T> {
+    fn div_assign(&mut self, rhs: u128) {
+        for count in self.state.values_mut() {
+            *count /= rhs;
+        }
+        self.retain(|_, count| count > 0);
+    }
+}
+
+/// Render the `Counter` like python does, most counted elements first.
+///
+/// Elements are rendered with their `Debug` implementation and elements
+/// with the same count are sorted by key.
+///
+/// # Examples
+/// ```
+/// # use collectors::Counter;
+/// let counter: Counter<char> = "abracadabra".chars().collect();
+/// assert_eq!(
+///     counter.to_string(),
+///     "Counter({'a': 5, 'b': 2, 'r': 2, 'c': 1, 'd': 1})"
+/// );
+/// ```
+impl<T: Ord + Debug> Display for Counter<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut elements: Vec<(&T, &u128)> = self.state.iter().collect();
+        elements.sort_by_key(|(_, count)| Reverse(**count));
+
+        f.write_str("Counter({")?;
+        for (idx, (key, count)) in elements.into_iter().enumerate() {
+            if idx > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{:?}: {}", key, count)?;
+        }
+        f.write_str("})")
+    }
+}
+
+/// Format the entries of a `Counter` in key order, along with its overflow
+/// policy and whether it has a normalizer.
+///
+/// # Examples
+/// ```
+/// # use collectors::Counter;
+/// let mut counter: Counter<char> = Counter::with_normalizer(|c| c.to_ascii_lowercase());
+/// counter.update_from_iter("aAb".chars());
+/// assert_eq!(
+///     format!("{:?}", counter),
+///     "Counter { state: {'a': 2, 'b': 1}, overflow: Checked, normalizer: true }"
+/// );
+/// ```
+impl<T: Debug> Debug for Counter<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Counter")
+            .field("state", &self.state)
+            .field("overflow", &self.overflow)
+            .field("normalizer", &self.normalizer.is_some())
+            .finish()
+    }
 }
 
-impl<T: Ord + Debug> PartialEq for Counter<T> {
+impl<T: Ord> PartialEq for Counter<T> {
     fn eq(&self, other: &Counter<T>) -> bool {
         if self.state.len() == other.state.len() {
             for (key, value) in self.state.iter() {
@@ -196,9 +2166,9 @@ impl<T: Ord + Debug> PartialEq for Counter<T> {
     }
 }
 
-impl<T: Ord + Debug> Eq for Counter<T> {}
+impl<T: Ord> Eq for Counter<T> {}
 
-impl<T: Ord + Debug> Default for Counter<T> {
+impl<T> Default for Counter<T> {
     fn default() -> Self {
         Counter::new()
     }
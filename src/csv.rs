@@ -0,0 +1,127 @@
+//! This module implements CSV export and import for `Counter`.
+//!
+//! Each record holds an element and its count, `key,count`, without any
+//! header. Keys containing a comma, a double quote or a line break are
+//! enclosed in double quotes, inner double quotes being doubled.
+//!
+use crate::binary::invalid_data;
+use crate::Counter;
+use std::fmt::Display;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::str::FromStr;
+
+impl<T: Ord + Display> Counter<T> {
+    /// Write the `Counter` as CSV, one `key,count` record per element.
+    ///
+    /// # Arguments
+    /// * writer - Where the records are written
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut counter: Counter<String> = Counter::new();
+    /// counter["plain"] += 2;
+    /// counter["with, comma"] += 1;
+    /// counter["with \"quotes\""] += 1;
+    /// let mut csv: Vec<u8> = Vec::new();
+    /// counter.to_csv(&mut csv).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(csv).unwrap(),
+    ///     "plain,2\n\"with \"\"quotes\"\"\",1\n\"with, comma\",1\n"
+    /// );
+    /// ```
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for (key, count) in self.iter() {
+            writeln!(writer, "{},{}", escape(&key.to_string()), count)?;
+        }
+        writer.flush()
+    }
+}
+
+impl<T: Ord + FromStr> Counter<T> {
+    /// Create a new `Counter` from CSV `key,count` records.
+    ///
+    /// Counts of keys appearing in several records are summed.
+    ///
+    /// # Arguments
+    /// * reader - Where the records are read from, it must yield valid UTF-8
+    ///
+    /// # Errors
+    /// An error of kind `InvalidData` is returned when a record is
+    /// malformed, when its key or count can't be parsed, or when summed
+    /// counts overflow.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let csv = "plain,2\n\"multi\nline, key\",1\nplain,3\n";
+    /// let counter: Counter<String> = Counter::from_csv(csv.as_bytes()).unwrap();
+    /// assert_eq!(counter["plain"], 5);
+    /// assert_eq!(counter["multi\nline, key"], 1);
+    ///
+    /// assert!(Counter::<String>::from_csv("no count\n".as_bytes()).is_err());
+    /// assert!(Counter::<u8>::from_csv("300,1\n".as_bytes()).is_err());
+    /// let overflowing = format!("a,{}\na,1\n", u128::MAX);
+    /// assert!(Counter::<String>::from_csv(overflowing.as_bytes()).is_err());
+    /// ```
+    pub fn from_csv<R: Read>(reader: R) -> io::Result<Self> {
+        let mut counter: Counter<T> = Counter::new();
+        let mut reader = BufReader::new(reader);
+        let mut record = String::new();
+        let mut line = String::new();
+        while reader.read_line(&mut line)? != 0 {
+            record.push_str(&line);
+            line.clear();
+            // A record only ends on a line break outside of quotes.
+            if record.matches('"').count() % 2 == 1 {
+                continue;
+            }
+            let content = record.trim_end_matches(['\n', '\r']);
+            if !content.is_empty() {
+                let (key, count) = parse_record(content)?;
+                counter
+                    .try_add_count(key, count)
+                    .map_err(|_| invalid_data("count overflow"))?;
+            }
+            record.clear();
+        }
+
+        if !record.is_empty() {
+            return Err(invalid_data("unterminated quoted field"));
+        }
+        Ok(counter)
+    }
+}
+
+pub(crate) fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+fn parse_record<T: FromStr>(record: &str) -> io::Result<(T, u128)> {
+    let separator = match record.rfind(',') {
+        Some(separator) => separator,
+        None => return Err(invalid_data("missing count")),
+    };
+    let (key, count) = (&record[..separator], &record[separator + 1..]);
+
+    let key = if let Some(quoted) = key.strip_prefix('"') {
+        match quoted.strip_suffix('"') {
+            Some(quoted) => quoted.replace("\"\"", "\""),
+            None => return Err(invalid_data("malformed quoted field")),
+        }
+    } else {
+        key.to_owned()
+    };
+    let key = key
+        .parse()
+        .map_err(|_| invalid_data(&format!("invalid key {:?}", key)))?;
+    let count = count
+        .trim()
+        .parse()
+        .map_err(|_| invalid_data(&format!("invalid count {:?}", count)))?;
+    Ok((key, count))
+}
@@ -0,0 +1,212 @@
+//! This module implements a `Counter` whose counts decay over time.
+//!
+//! Every count is halved each time `half_life` time steps elapse, so the
+//! counted frequencies favor recent elements. A time step is either an
+//! explicit call to `tick` or an update, depending on the `DecayClock`.
+//!
+//! Decaying is done lazily through a global scale factor, so a time step
+//! costs O(1) whatever the number of counted elements.
+//!
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+
+/// Below this scale, stored weights are renormalized to avoid underflows.
+const MIN_SCALE: f64 = 1e-100;
+
+/// Indicate what makes the time move forward for a `DecayingCounter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecayClock {
+    /// Only explicit calls to `tick` make counts decay
+    Tick,
+
+    /// Every update makes counts decay, before the updated element is counted
+    Update,
+}
+
+/// Structure that count occurences of `T` elements with decaying weights
+#[derive(Debug, Clone)]
+pub struct DecayingCounter<T> {
+    state: BTreeMap<T, f64>,
+    half_life: f64,
+    decay: f64,
+    scale: f64,
+    clock: DecayClock,
+}
+
+impl<T: Ord> DecayingCounter<T> {
+    /// Create a new empty `DecayingCounter`.
+    ///
+    /// # Arguments
+    /// * half_life - The number of time steps after which a count is halved, must be positive
+    /// * clock - What makes the time move forward
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{DecayClock, DecayingCounter};
+    /// let counter: DecayingCounter<char> = DecayingCounter::new(10.0, DecayClock::Tick);
+    /// # assert_eq!(counter.len(), 0);
+    /// ```
+    pub fn new(half_life: f64, clock: DecayClock) -> Self {
+        assert!(half_life > 0.0);
+        DecayingCounter {
+            state: BTreeMap::new(),
+            half_life,
+            decay: 0.5f64.powf(1.0 / half_life),
+            scale: 1.0,
+            clock,
+        }
+    }
+
+    /// Returns the half-life of the counts, in time steps.
+    pub fn half_life(&self) -> f64 {
+        self.half_life
+    }
+
+    /// Returns what makes the time move forward.
+    pub fn clock(&self) -> DecayClock {
+        self.clock
+    }
+
+    /// Returns the number of elements in the map.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{DecayClock, DecayingCounter};
+    /// let mut counter: DecayingCounter<char> = DecayingCounter::new(10.0, DecayClock::Tick);
+    /// counter.update('a');
+    /// assert_eq!(counter.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.state.len()
+    }
+
+    /// Returns `true` if the `DecayingCounter` is empty, `false` otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{DecayClock, DecayingCounter};
+    /// let mut counter: DecayingCounter<char> = DecayingCounter::new(10.0, DecayClock::Tick);
+    /// assert_eq!(counter.is_empty(), true);
+    /// counter.update('a');
+    /// assert_eq!(counter.is_empty(), false);
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.state.is_empty()
+    }
+
+    /// Make the time move forward by one step, decaying every count.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{DecayClock, DecayingCounter};
+    /// let mut counter: DecayingCounter<char> = DecayingCounter::new(2.0, DecayClock::Tick);
+    /// counter.update('a');
+    /// counter.tick();
+    /// counter.tick();
+    /// assert!((counter.get(&'a') - 0.5).abs() < 1e-9);
+    /// ```
+    pub fn tick(&mut self) {
+        self.scale *= self.decay;
+        if self.scale < MIN_SCALE {
+            let scale = self.scale;
+            for weight in self.state.values_mut() {
+                *weight *= scale;
+            }
+            self.scale = 1.0;
+        }
+    }
+
+    /// Update the `DecayingCounter` with a value.
+    ///
+    /// # Arguments
+    /// * elem - A value used to update the `DecayingCounter`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{DecayClock, DecayingCounter};
+    /// let mut counter: DecayingCounter<char> = DecayingCounter::new(1.0, DecayClock::Update);
+    /// counter.update('a');
+    /// counter.update('b');
+    /// assert!((counter.get(&'a') - 0.5).abs() < 1e-9);
+    /// assert!((counter.get(&'b') - 1.0).abs() < 1e-9);
+    /// ```
+    pub fn update(&mut self, elem: T) {
+        if self.clock == DecayClock::Update {
+            self.tick();
+        }
+        let weight = self.state.entry(elem).or_insert(0.0);
+        *weight += 1.0 / self.scale;
+    }
+
+    /// Update the `DecayingCounter` with an iterator.
+    ///
+    /// # Arguments
+    /// * iter - An iterator used to update the `DecayingCounter`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{DecayClock, DecayingCounter};
+    /// let mut counter: DecayingCounter<char> = DecayingCounter::new(10.0, DecayClock::Tick);
+    /// counter.update_from_iter("aab".chars());
+    /// assert!((counter.get(&'a') - 2.0).abs() < 1e-9);
+    /// ```
+    pub fn update_from_iter<I>(&mut self, iter: I)
+    where
+        I: Iterator<Item = T>,
+    {
+        for elem in iter {
+            self.update(elem);
+        }
+    }
+
+    /// Returns the decayed count of an element, missing elements are counted as 0.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element to look up
+    pub fn get<Q>(&self, elem: &Q) -> f64
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.state
+            .get(elem)
+            .map_or(0.0, |weight| weight * self.scale)
+    }
+
+    /// Iterate over the elements and their decayed counts.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{DecayClock, DecayingCounter};
+    /// let mut counter: DecayingCounter<char> = DecayingCounter::new(1.0, DecayClock::Tick);
+    /// counter.update('a');
+    /// counter.tick();
+    /// let counts: Vec<(&char, f64)> = counter.iter().collect();
+    /// assert_eq!(counts, vec![(&'a', 0.5)]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&T, f64)> + '_ {
+        let scale = self.scale;
+        self.state
+            .iter()
+            .map(move |(key, weight)| (key, weight * scale))
+    }
+
+    /// Remove the elements whose decayed count is below `min_count`.
+    ///
+    /// # Arguments
+    /// * min_count - The minimum decayed count to keep an element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{DecayClock, DecayingCounter};
+    /// let mut counter: DecayingCounter<char> = DecayingCounter::new(1.0, DecayClock::Update);
+    /// counter.update_from_iter("abcd".chars());
+    /// counter.prune_below(0.3);
+    /// assert_eq!(counter.len(), 2);
+    /// assert_eq!(counter.get(&'a'), 0.0);
+    /// ```
+    pub fn prune_below(&mut self, min_count: f64) {
+        let min_weight = min_count / self.scale;
+        self.state.retain(|_, weight| *weight >= min_weight);
+    }
+}
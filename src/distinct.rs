@@ -0,0 +1,90 @@
+//! This module implements the removal of duplicates from an iterator,
+//! keeping the order elements are first seen in.
+//!
+//! `Vec::dedup` only removes consecutive duplicates and sorting loses the
+//! order of the elements. `unique_ordered` instead remembers every element
+//! it already yielded in a set, and lazily yields each element the first
+//! time it comes, in O(log n). Collecting it in a `Vec` gives the elements
+//! in first-seen order, and in an `IndexSet` numbers them too.
+//!
+use std::collections::BTreeSet;
+
+/// Iterate over the elements of an iterator without their duplicates, in
+/// the order they are first seen.
+///
+/// # Arguments
+/// * iter - The elements filtered
+///
+/// # Examples
+/// ```
+/// # use collectors::{unique_ordered, Counter};
+/// // Build a vocabulary next to the counts of its words.
+/// let text = "the cat saw the dog and the cat ran";
+/// let vocabulary: Vec<&str> = unique_ordered(text.split(' ')).collect();
+/// let counter: Counter<&str> = text.split(' ').collect();
+/// assert_eq!(vocabulary, vec!["the", "cat", "saw", "dog", "and", "ran"]);
+/// assert_eq!(vocabulary.len(), counter.len());
+/// ```
+pub fn unique_ordered<I>(iter: I) -> DistinctOrdered<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: Ord + Clone,
+{
+    DistinctOrdered {
+        iter: iter.into_iter(),
+        seen: BTreeSet::new(),
+    }
+}
+
+/// Iterator over the elements of an iterator without their duplicates, see
+/// `unique_ordered`
+#[derive(Debug, Clone)]
+pub struct DistinctOrdered<I: Iterator> {
+    iter: I,
+    seen: BTreeSet<I::Item>,
+}
+
+impl<I: Iterator> DistinctOrdered<I>
+where
+    I::Item: Ord + Clone,
+{
+    /// Returns the number of distinct elements yielded so far.
+    pub fn num_seen(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// Returns `true` if an element was already yielded, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * elem - The element looked up
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::unique_ordered;
+    /// let mut ids = unique_ordered(vec![3, 1, 3, 2]);
+    /// assert_eq!(ids.next(), Some(3));
+    /// assert!(ids.seen(&3));
+    /// assert!(!ids.seen(&1));
+    /// assert_eq!(ids.num_seen(), 1);
+    /// assert_eq!(ids.collect::<Vec<u32>>(), vec![1, 2]);
+    /// ```
+    pub fn seen(&self, elem: &I::Item) -> bool {
+        self.seen.contains(elem)
+    }
+}
+
+impl<I: Iterator> Iterator for DistinctOrdered<I>
+where
+    I::Item: Ord + Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let seen = &mut self.seen;
+        self.iter.by_ref().find(|elem| seen.insert(elem.clone()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
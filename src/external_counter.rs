@@ -0,0 +1,353 @@
+//! This module implements exact counting over more distinct elements than
+//! fit in memory.
+//!
+//! An `ExternalCounter` counts in a `Counter` until it holds a given number
+//! of distinct elements, then spills it to a temporary file as a run
+//! sorted by element, and starts over. Finalizing merges every run along
+//! with the elements still in memory, summing the counts of an element
+//! found in several runs, either into a `Counter` or as a stream in
+//! ascending order that never holds more than one entry per run. Elements
+//! are written in the entry format of `Counter::save`, through their
+//! string form, so parsing it back must give the same element. Run files
+//! are removed when the merge, or the `ExternalCounter`, is dropped.
+//!
+use crate::binary::{invalid_data, read_entry, write_entry};
+use crate::Counter;
+use std::cmp::Reverse;
+use std::collections::btree_map::IntoIter;
+use std::collections::BinaryHeap;
+use std::fmt::Display;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The number of run files created by the process, naming the next one.
+static NEXT_RUN: AtomicUsize = AtomicUsize::new(0);
+
+/// A run spilled to a file, removed when dropped.
+#[derive(Debug)]
+struct Run {
+    path: PathBuf,
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Structure that count `T` elements exactly, spilling to disk past a
+/// number of distinct elements
+#[derive(Debug)]
+pub struct ExternalCounter<T> {
+    counter: Counter<T>,
+    max_in_memory: usize,
+    dir: PathBuf,
+    runs: Vec<Run>,
+}
+
+impl<T: Ord + Display + FromStr> ExternalCounter<T> {
+    /// Create a new empty `ExternalCounter`, spilling to the temporary
+    /// directory of the system.
+    ///
+    /// # Arguments
+    /// * max_in_memory - The number of distinct elements counted in memory
+    ///   before spilling them, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ExternalCounter;
+    /// let counter: ExternalCounter<String> = ExternalCounter::new(1_000_000);
+    /// # assert!(counter.is_empty());
+    /// ```
+    pub fn new(max_in_memory: usize) -> Self {
+        ExternalCounter::with_dir(max_in_memory, std::env::temp_dir())
+    }
+
+    /// Create a new empty `ExternalCounter`, spilling to a given directory.
+    ///
+    /// # Arguments
+    /// * max_in_memory - The number of distinct elements counted in memory
+    ///   before spilling them, must be greater than 0
+    /// * dir - The directory the runs are written to, which must exist
+    pub fn with_dir<P: Into<PathBuf>>(max_in_memory: usize, dir: P) -> Self {
+        assert!(max_in_memory > 0);
+        ExternalCounter {
+            counter: Counter::new(),
+            max_in_memory,
+            dir: dir.into(),
+            runs: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if no element was counted, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.counter.is_empty() && self.runs.is_empty()
+    }
+
+    /// Returns the number of distinct elements counted in memory.
+    pub fn len_in_memory(&self) -> usize {
+        self.counter.len()
+    }
+
+    /// Returns the number of runs spilled to disk.
+    pub fn num_runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Returns the directory the runs are written to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Count an element, spilling the elements in memory first if there
+    /// are too many.
+    ///
+    /// # Errors
+    /// An error is returned if the run can't be written.
+    ///
+    /// # Panics
+    /// Panics if a count overflows.
+    ///
+    /// # Arguments
+    /// * elem - The element counted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ExternalCounter;
+    /// let mut counter: ExternalCounter<u32> = ExternalCounter::new(2);
+    /// for n in vec![1, 2, 1, 3, 1, 3] {
+    ///     counter.update_from_value(n).unwrap();
+    /// }
+    /// assert_eq!(counter.num_runs(), 1);
+    /// assert_eq!(counter.len_in_memory(), 2);
+    /// assert_eq!(counter.finalize().unwrap()[&1], 3);
+    /// ```
+    pub fn update_from_value(&mut self, elem: T) -> io::Result<()> {
+        self.add_count(elem, 1)
+    }
+
+    /// Count the elements of an iterator.
+    ///
+    /// # Errors
+    /// An error is returned if a run can't be written, the elements
+    /// yielded before staying counted.
+    ///
+    /// # Panics
+    /// Panics if a count overflows.
+    ///
+    /// # Arguments
+    /// * iter - The elements counted
+    pub fn update_from_iter<I>(&mut self, iter: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for elem in iter {
+            self.update_from_value(elem)?;
+        }
+        Ok(())
+    }
+
+    /// Add occurrences of an element, spilling the elements in memory first
+    /// if there are too many. Adding 0 occurrences does nothing.
+    ///
+    /// # Errors
+    /// An error is returned if the run can't be written.
+    ///
+    /// # Panics
+    /// Panics if the count overflows.
+    ///
+    /// # Arguments
+    /// * elem - The element counted
+    /// * count - The number of occurrences added
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ExternalCounter;
+    /// let mut counter: ExternalCounter<char> = ExternalCounter::new(1);
+    /// for c in "abc".chars() {
+    ///     counter.add_count(c, 0).unwrap();
+    /// }
+    /// assert!(counter.is_empty());
+    ///
+    /// // Counts summed across runs may overflow.
+    /// counter.add_count('a', u128::MAX).unwrap();
+    /// counter.add_count('b', 1).unwrap();
+    /// counter.add_count('a', 1).unwrap();
+    /// assert_eq!(counter.num_runs(), 2);
+    /// let error = counter.finalize().unwrap_err();
+    /// assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    /// ```
+    pub fn add_count(&mut self, elem: T, count: u128) -> io::Result<()> {
+        if count == 0 {
+            return Ok(());
+        }
+        if self.counter.len() >= self.max_in_memory && self.counter[&elem] == 0 {
+            self.spill()?;
+        }
+        self.counter.extend(Some((elem, count)));
+        Ok(())
+    }
+
+    /// Write the elements in memory to a new run, emptying the memory.
+    ///
+    /// # Errors
+    /// An error is returned if the run can't be written, the elements then
+    /// staying in memory.
+    pub fn spill(&mut self) -> io::Result<()> {
+        if self.counter.is_empty() {
+            return Ok(());
+        }
+        let path = self.dir.join(format!(
+            "collectors-{}-{}.run",
+            process::id(),
+            NEXT_RUN.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        let run = Run { path };
+        let mut writer = BufWriter::new(file);
+        for (elem, count) in self.counter.iter() {
+            write_entry(&mut writer, elem, *count)?;
+        }
+        writer.flush()?;
+        self.counter = Counter::new();
+        self.runs.push(run);
+        Ok(())
+    }
+
+    /// Merge every run and the elements in memory into a `Counter`.
+    ///
+    /// # Errors
+    /// An error is returned if a run can't be read back, and an error of
+    /// kind `InvalidData` if the summed counts of an element overflow.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, ExternalCounter};
+    /// let words = "the cat saw the dog and the cat ran".split(' ');
+    /// let mut counter: ExternalCounter<String> = ExternalCounter::new(3);
+    /// counter.update_from_iter(words.clone().map(String::from)).unwrap();
+    /// assert!(counter.num_runs() > 1);
+    ///
+    /// let exact = counter.finalize().unwrap();
+    /// assert_eq!(exact, words.map(String::from).collect::<Counter<String>>());
+    /// assert_eq!(exact["the"], 3);
+    /// ```
+    pub fn finalize(self) -> io::Result<Counter<T>> {
+        let mut counter: Counter<T> = Counter::new();
+        for entry in self.finalize_iter()? {
+            counter.extend(Some(entry?));
+        }
+        Ok(counter)
+    }
+
+    /// Merge every run and the elements in memory into a stream of the
+    /// elements and their counts, in ascending order.
+    ///
+    /// # Errors
+    /// An error is returned if a run can't be opened. The first entry that
+    /// can't be read back, or whose summed counts overflow, is an error,
+    /// ending the stream.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ExternalCounter;
+    /// // Keep only the frequent elements, never holding every count at once.
+    /// let mut counter: ExternalCounter<u32> = ExternalCounter::new(4);
+    /// counter.update_from_iter((0..100).map(|n| n % 10 * (n % 3))).unwrap();
+    /// let frequent: Vec<(u32, u128)> = counter
+    ///     .finalize_iter()
+    ///     .unwrap()
+    ///     .map(Result::unwrap)
+    ///     .filter(|(_, count)| *count > 10)
+    ///     .collect();
+    /// assert_eq!(frequent, vec![(0, 40)]);
+    /// ```
+    pub fn finalize_iter(mut self) -> io::Result<ExternalCounts<T>> {
+        let mut readers: Vec<BufReader<File>> = Vec::with_capacity(self.runs.len());
+        for run in &self.runs {
+            readers.push(BufReader::new(File::open(&run.path)?));
+        }
+        let memory = std::mem::take(&mut self.counter).into_iter();
+        let mut counts = ExternalCounts {
+            readers,
+            memory,
+            heads: BinaryHeap::new(),
+            runs: std::mem::take(&mut self.runs),
+            failed: false,
+        };
+        for source in 0..=counts.readers.len() {
+            counts.refill(source)?;
+        }
+        Ok(counts)
+    }
+}
+
+/// Iterator over the merged counts of an `ExternalCounter`, see
+/// `ExternalCounter::finalize_iter`
+#[derive(Debug)]
+pub struct ExternalCounts<T> {
+    readers: Vec<BufReader<File>>,
+    /// The elements left in memory, the source after every reader.
+    memory: IntoIter<T, u128>,
+    /// The next entry of every source not exhausted, with its source.
+    heads: BinaryHeap<Reverse<(T, usize, u128)>>,
+    runs: Vec<Run>,
+    failed: bool,
+}
+
+impl<T: Ord + FromStr> ExternalCounts<T> {
+    /// Push the next entry of a source on the heads, if any.
+    fn refill(&mut self, source: usize) -> io::Result<()> {
+        let entry = match self.readers.get_mut(source) {
+            Some(reader) => {
+                if reader.fill_buf()?.is_empty() {
+                    None
+                } else {
+                    Some(read_entry(reader)?)
+                }
+            }
+            None => self.memory.next(),
+        };
+        if let Some((elem, count)) = entry {
+            self.heads.push(Reverse((elem, source, count)));
+        }
+        Ok(())
+    }
+}
+
+impl<T: Ord + FromStr> Iterator for ExternalCounts<T> {
+    type Item = io::Result<(T, u128)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let Reverse((elem, source, mut total)) = self.heads.pop()?;
+        let mut result = self.refill(source);
+        while result.is_ok() && self.heads.peek().is_some_and(|head| head.0 .0 == elem) {
+            let Reverse((_, source, count)) = self.heads.pop().expect("a head was peeked");
+            result = match total.checked_add(count) {
+                Some(sum) => {
+                    total = sum;
+                    self.refill(source)
+                }
+                None => Err(invalid_data("count overflow")),
+            };
+        }
+        match result {
+            Ok(()) => Some(Ok((elem, total))),
+            Err(error) => {
+                self.failed = true;
+                self.runs.clear();
+                Some(Err(error))
+            }
+        }
+    }
+}
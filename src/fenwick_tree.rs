@@ -0,0 +1,241 @@
+//! This module implements a Fenwick tree, also known as a binary indexed
+//! tree, maintaining the prefix sums of a sequence of values.
+//!
+//! Node `i` of the tree, counting from 1, holds the sum of the values of
+//! the `i & -i` positions ending at `i`, so a prefix is the sum of one node
+//! per set bit of its length and a value is in one node per level. Both
+//! updating a value and reading a prefix sum are then O(log n), where a
+//! plain vector trades one for the other. A range sum is the difference of
+//! two prefix sums.
+//!
+use std::iter::FromIterator;
+use std::ops::{Add, Bound, RangeBounds, Sub};
+
+/// Structure that maintain the prefix sums of `T` values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FenwickTree<T> {
+    nodes: Vec<T>,
+}
+
+impl<T> FenwickTree<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    /// Create a new `FenwickTree` of `len` values equal to the default.
+    ///
+    /// # Arguments
+    /// * len - The number of values
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::FenwickTree;
+    /// let buckets: FenwickTree<u128> = FenwickTree::new(16);
+    /// # assert_eq!(buckets.len(), 16);
+    /// # assert_eq!(buckets.total(), 0);
+    /// ```
+    pub fn new(len: usize) -> Self {
+        FenwickTree {
+            nodes: vec![T::default(); len],
+        }
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the `FenwickTree` holds no value, `false`
+    /// otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Append a value.
+    ///
+    /// # Arguments
+    /// * value - The value appended
+    pub fn push(&mut self, value: T) {
+        let pos = self.nodes.len() + 1;
+        let first = pos - lowest_bit(pos);
+        let node = value + self.prefix_sum(pos - 1) - self.prefix_sum(first);
+        self.nodes.push(node);
+    }
+
+    /// Add `delta` to the value at position `idx`.
+    ///
+    /// # Arguments
+    /// * idx - The position, must be lower than the number of values
+    /// * delta - The amount added
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::FenwickTree;
+    /// // Requests per latency bucket of 10ms.
+    /// let mut buckets: FenwickTree<u128> = FenwickTree::new(10);
+    /// for latency in vec![3, 12, 18, 25, 47, 91] {
+    ///     buckets.add(latency / 10, 1);
+    /// }
+    /// assert_eq!(buckets.prefix_sum(2), 3);
+    /// assert_eq!(buckets.range_sum(1..5), 4);
+    /// assert_eq!(buckets.total(), 6);
+    /// ```
+    pub fn add(&mut self, idx: usize, delta: T) {
+        assert!(idx < self.nodes.len());
+        let mut pos = idx + 1;
+        while pos <= self.nodes.len() {
+            self.nodes[pos - 1] = self.nodes[pos - 1] + delta;
+            pos += lowest_bit(pos);
+        }
+    }
+
+    /// Subtract `delta` from the value at position `idx`.
+    ///
+    /// # Arguments
+    /// * idx - The position, must be lower than the number of values
+    /// * delta - The amount subtracted
+    pub fn sub(&mut self, idx: usize, delta: T) {
+        assert!(idx < self.nodes.len());
+        let mut pos = idx + 1;
+        while pos <= self.nodes.len() {
+            self.nodes[pos - 1] = self.nodes[pos - 1] - delta;
+            pos += lowest_bit(pos);
+        }
+    }
+
+    /// Replace the value at position `idx`.
+    ///
+    /// # Arguments
+    /// * idx - The position, must be lower than the number of values
+    /// * value - The new value
+    pub fn set(&mut self, idx: usize, value: T) {
+        let old = self.get(idx);
+        self.sub(idx, old);
+        self.add(idx, value);
+    }
+
+    /// Returns the value at position `idx`.
+    ///
+    /// # Arguments
+    /// * idx - The position, must be lower than the number of values
+    pub fn get(&self, idx: usize) -> T {
+        assert!(idx < self.nodes.len());
+        self.range_sum(idx..=idx)
+    }
+
+    /// Returns the sum of the first `len` values.
+    ///
+    /// # Arguments
+    /// * len - The number of values summed, at most the number of values
+    pub fn prefix_sum(&self, len: usize) -> T {
+        assert!(len <= self.nodes.len());
+        let mut sum = T::default();
+        let mut pos = len;
+        while pos > 0 {
+            sum = sum + self.nodes[pos - 1];
+            pos -= lowest_bit(pos);
+        }
+        sum
+    }
+
+    /// Returns the sum of the values in a range of positions.
+    ///
+    /// # Arguments
+    /// * range - The range of positions, within the values
+    pub fn range_sum<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let start = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => end + 1,
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => self.nodes.len(),
+        };
+        assert!(start <= end);
+        self.prefix_sum(end) - self.prefix_sum(start)
+    }
+
+    /// Returns the sum of every value.
+    pub fn total(&self) -> T {
+        self.prefix_sum(self.nodes.len())
+    }
+
+    /// Returns the smallest position whose prefix sum, value included,
+    /// reaches `sum`, `None` if the total is lower.
+    ///
+    /// The values must not be negative.
+    ///
+    /// # Arguments
+    /// * sum - The prefix sum searched
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::FenwickTree;
+    /// // The bucket holding the median of 9 requests.
+    /// let buckets: FenwickTree<u128> = vec![2, 0, 1, 4, 2].into_iter().collect();
+    /// assert_eq!(buckets.lower_bound(5), Some(3));
+    /// assert_eq!(buckets.lower_bound(3), Some(2));
+    /// assert_eq!(buckets.lower_bound(10), None);
+    /// ```
+    pub fn lower_bound(&self, sum: T) -> Option<usize>
+    where
+        T: PartialOrd,
+    {
+        let mut pos = 0;
+        let mut remaining = sum;
+        let mut step = self.nodes.len().checked_next_power_of_two()?;
+        while step > 0 {
+            let next = pos + step;
+            if next <= self.nodes.len() && self.nodes[next - 1] < remaining {
+                pos = next;
+                remaining = remaining - self.nodes[next - 1];
+            }
+            step /= 2;
+        }
+        if pos < self.nodes.len() {
+            Some(pos)
+        } else {
+            None
+        }
+    }
+
+    /// Reset every value to the default.
+    pub fn clear(&mut self) {
+        for node in self.nodes.iter_mut() {
+            *node = T::default();
+        }
+    }
+}
+
+/// Returns the lowest set bit of `pos`.
+fn lowest_bit(pos: usize) -> usize {
+    pos & pos.wrapping_neg()
+}
+
+impl<T> FromIterator<T> for FenwickTree<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut nodes: Vec<T> = iter.into_iter().collect();
+        for idx in 0..nodes.len() {
+            let parent = idx + lowest_bit(idx + 1);
+            if parent < nodes.len() {
+                nodes[parent] = nodes[parent] + nodes[idx];
+            }
+        }
+        FenwickTree { nodes }
+    }
+}
+
+impl<T> Extend<T> for FenwickTree<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
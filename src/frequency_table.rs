@@ -0,0 +1,261 @@
+//! This module implements a cross tabulation of two variables.
+//!
+//! A `FrequencyTable` counts `(row, column)` pairs, keeping a `Counter` of
+//! columns per row along with the column totals, so a cell, a row total
+//! or a column total is read in O(log n). Tables render as CSV or as a
+//! Markdown table, rows and columns in key order, absent cells being 0.
+//!
+use crate::csv::escape;
+use crate::Counter;
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write as _};
+use std::io::{self, Write};
+use std::iter::FromIterator;
+
+/// Structure that count occurences of `(R, C)` pairs
+#[derive(Debug, Clone)]
+pub struct FrequencyTable<R, C> {
+    rows: BTreeMap<R, Counter<C>>,
+    columns: Counter<C>,
+}
+
+impl<R: Ord, C: Ord + Clone> FrequencyTable<R, C> {
+    /// Create a new empty `FrequencyTable`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::FrequencyTable;
+    /// let table: FrequencyTable<&str, bool> = FrequencyTable::new();
+    /// # assert!(table.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        FrequencyTable {
+            rows: BTreeMap::new(),
+            columns: Counter::new(),
+        }
+    }
+
+    /// Returns `true` if no pair was counted, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Count a pair.
+    ///
+    /// # Arguments
+    /// * row - The row of the pair
+    /// * column - The column of the pair
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::FrequencyTable;
+    /// // Outcomes of a test per browser.
+    /// let mut table: FrequencyTable<&str, &str> = FrequencyTable::new();
+    /// table.update("firefox", "pass");
+    /// table.update("chrome", "pass");
+    /// table.update("firefox", "fail");
+    /// table.update("firefox", "pass");
+    /// assert_eq!(table.get("firefox", "pass"), 2);
+    /// assert_eq!(table.get("chrome", "fail"), 0);
+    /// assert_eq!(table.row_total("firefox"), 3);
+    /// assert_eq!(table.column_total("pass"), 3);
+    /// assert_eq!(table.total(), 4);
+    /// ```
+    pub fn update(&mut self, row: R, column: C) {
+        self.columns.update_from_value(column.clone());
+        self.rows.entry(row).or_default().update_from_value(column);
+    }
+
+    /// Count every pair of an iterator.
+    ///
+    /// # Arguments
+    /// * iter - An iterator over the pairs
+    pub fn update_from_iter<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (R, C)>,
+    {
+        for (row, column) in iter {
+            self.update(row, column);
+        }
+    }
+
+    /// Returns the count of a cell.
+    ///
+    /// # Arguments
+    /// * row - A borrowed form of the row
+    /// * column - A borrowed form of the column
+    pub fn get<QR, QC>(&self, row: &QR, column: &QC) -> u128
+    where
+        R: Borrow<QR>,
+        C: Borrow<QC>,
+        QR: Ord + ?Sized,
+        QC: Ord + ?Sized,
+    {
+        self.rows.get(row).map_or(0, |columns| columns[column])
+    }
+
+    /// Returns the `Counter` of the columns of a row, `None` if no pair
+    /// of the row was counted.
+    ///
+    /// # Arguments
+    /// * row - A borrowed form of the row
+    pub fn row<Q>(&self, row: &Q) -> Option<&Counter<C>>
+    where
+        R: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.rows.get(row)
+    }
+
+    /// Returns the number of pairs counted in a row.
+    ///
+    /// # Arguments
+    /// * row - A borrowed form of the row
+    pub fn row_total<Q>(&self, row: &Q) -> u128
+    where
+        R: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.rows.get(row).map_or(0, Counter::total)
+    }
+
+    /// Returns the number of pairs counted in a column.
+    ///
+    /// # Arguments
+    /// * column - A borrowed form of the column
+    pub fn column_total<Q>(&self, column: &Q) -> u128
+    where
+        C: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.columns[column]
+    }
+
+    /// Returns the number of pairs counted in every row.
+    pub fn row_totals(&self) -> Counter<R>
+    where
+        R: Clone,
+    {
+        let mut totals: Counter<R> = Counter::new();
+        totals.extend(
+            self.rows
+                .iter()
+                .map(|(row, columns)| (row.clone(), columns.total())),
+        );
+        totals
+    }
+
+    /// Returns the number of pairs counted in every column.
+    pub fn column_totals(&self) -> &Counter<C> {
+        &self.columns
+    }
+
+    /// Returns the number of pairs counted.
+    pub fn total(&self) -> u128 {
+        self.columns.total()
+    }
+
+    /// Iterate over the rows, in order.
+    pub fn rows(&self) -> impl Iterator<Item = &R> + '_ {
+        self.rows.keys()
+    }
+
+    /// Iterate over the columns, in order.
+    pub fn columns(&self) -> impl Iterator<Item = &C> + '_ {
+        self.columns.unique()
+    }
+
+    /// Remove every count.
+    pub fn clear(&mut self) {
+        self.rows.clear();
+        self.columns = Counter::new();
+    }
+}
+
+impl<R: Ord + Display, C: Ord + Clone + Display> FrequencyTable<R, C> {
+    /// Write the table as CSV, a header record of the columns, its first
+    /// field empty, then one record per row.
+    ///
+    /// # Arguments
+    /// * writer - Where the records are written
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::FrequencyTable;
+    /// let table: FrequencyTable<&str, u8> =
+    ///     vec![("a, b", 1), ("c", 2), ("c", 2)].into_iter().collect();
+    /// let mut csv: Vec<u8> = Vec::new();
+    /// table.to_csv(&mut csv).unwrap();
+    /// assert_eq!(String::from_utf8(csv).unwrap(), ",1,2\n\"a, b\",1,0\nc,0,2\n");
+    /// ```
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for column in self.columns() {
+            write!(writer, ",{}", escape(&column.to_string()))?;
+        }
+        writeln!(writer)?;
+        for (row, columns) in self.rows.iter() {
+            write!(writer, "{}", escape(&row.to_string()))?;
+            for column in self.columns() {
+                write!(writer, ",{}", columns[column])?;
+            }
+            writeln!(writer)?;
+        }
+        writer.flush()
+    }
+
+    /// Render the table as a Markdown table.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::FrequencyTable;
+    /// let table: FrequencyTable<&str, &str> =
+    ///     vec![("linux", "ok"), ("macos", "ok"), ("linux", "err")].into_iter().collect();
+    /// assert_eq!(
+    ///     table.to_markdown(),
+    ///     "|  | err | ok |\n\
+    ///      | --- | --- | --- |\n\
+    ///      | linux | 1 | 1 |\n\
+    ///      | macos | 0 | 1 |\n"
+    /// );
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("| ");
+        for column in self.columns() {
+            let _ = write!(markdown, " | {}", column);
+        }
+        markdown.push_str(" |\n|");
+        for _ in 0..=self.columns.len() {
+            markdown.push_str(" --- |");
+        }
+        markdown.push('\n');
+        for (row, columns) in self.rows.iter() {
+            let _ = write!(markdown, "| {}", row);
+            for column in self.columns() {
+                let _ = write!(markdown, " | {}", columns[column]);
+            }
+            markdown.push_str(" |\n");
+        }
+        markdown
+    }
+}
+
+impl<R: Ord, C: Ord + Clone> FromIterator<(R, C)> for FrequencyTable<R, C> {
+    fn from_iter<I: IntoIterator<Item = (R, C)>>(iter: I) -> Self {
+        let mut table: FrequencyTable<R, C> = FrequencyTable::new();
+        table.update_from_iter(iter);
+        table
+    }
+}
+
+impl<R: Ord, C: Ord + Clone> Extend<(R, C)> for FrequencyTable<R, C> {
+    fn extend<I: IntoIterator<Item = (R, C)>>(&mut self, iter: I) {
+        self.update_from_iter(iter);
+    }
+}
+
+impl<R: Ord, C: Ord + Clone> Default for FrequencyTable<R, C> {
+    fn default() -> Self {
+        FrequencyTable::new()
+    }
+}
@@ -0,0 +1,226 @@
+//! This module implements a read-only map over a minimal perfect hash.
+//!
+//! A `FrozenMap` is built once from all of its entries. Keys are hashed
+//! into buckets of a few keys each, and every bucket, largest first, is
+//! given the smallest displacement that sends each of its keys to a slot no
+//! other key took, following the hash and displace scheme. There are as
+//! many slots as entries, so a lookup hashes the key, reads the
+//! displacement of its bucket and compares a single entry, without
+//! allocating nor probing.
+//!
+use crate::min_hash::mix;
+use crate::Counter;
+use std::borrow::Borrow;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::Index;
+
+/// The average number of keys per bucket.
+const BUCKET_SIZE: usize = 4;
+
+/// Structure that map `K` keys to `V` values, built once
+#[derive(Debug, Clone)]
+pub struct FrozenMap<K, V> {
+    entries: Vec<(K, V)>,
+    displacements: Vec<u32>,
+}
+
+impl<K: Hash + Eq, V> FrozenMap<K, V> {
+    /// Create a new `FrozenMap` holding the entries of an iterator, the
+    /// last value of a key being kept.
+    ///
+    /// # Arguments
+    /// * iter - An iterator over the entries
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::FrozenMap;
+    /// let ids = FrozenMap::new(vec![("GET", 0), ("POST", 1), ("PUT", 2), ("GET", 3)]);
+    /// assert_eq!(ids.len(), 3);
+    /// assert_eq!(ids.get("GET"), Some(&3));
+    /// assert_eq!(ids.get("PATCH"), None);
+    /// ```
+    pub fn new<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let entries: Vec<(K, V)> = iter.into_iter().collect();
+        let num_buckets = entries.len() / BUCKET_SIZE + 1;
+        let mut buckets: Vec<Vec<(u64, K, V)>> = (0..num_buckets).map(|_| Vec::new()).collect();
+        for (key, value) in entries {
+            let hash = hash_of(&key);
+            let bucket = &mut buckets[(hash % num_buckets as u64) as usize];
+            match bucket.iter_mut().find(|(_, other, _)| *other == key) {
+                Some(entry) => entry.2 = value,
+                None => bucket.push((hash, key, value)),
+            }
+        }
+        let len: usize = buckets.iter().map(Vec::len).sum();
+
+        let mut order: Vec<usize> = (0..num_buckets).collect();
+        order.sort_by_key(|bucket| std::cmp::Reverse(buckets[*bucket].len()));
+        let mut slots: Vec<Option<(K, V)>> = (0..len).map(|_| None).collect();
+        let mut displacements: Vec<u32> = vec![0; num_buckets];
+        let mut taken: Vec<usize> = Vec::new();
+        for bucket in order {
+            if buckets[bucket].is_empty() {
+                break;
+            }
+            let mut displacement: u32 = 0;
+            loop {
+                taken.clear();
+                let fits = buckets[bucket].iter().all(|(hash, _, _)| {
+                    let slot = slot_of(*hash, displacement, len);
+                    let free = slots[slot].is_none() && !taken.contains(&slot);
+                    taken.push(slot);
+                    free
+                });
+                if fits {
+                    break;
+                }
+                displacement = displacement
+                    .checked_add(1)
+                    .expect("keys with colliding hashes");
+            }
+            displacements[bucket] = displacement;
+            for (hash, key, value) in buckets[bucket].drain(..) {
+                slots[slot_of(hash, displacement, len)] = Some((key, value));
+            }
+        }
+        FrozenMap {
+            entries: slots
+                .into_iter()
+                .map(|slot| slot.expect("every slot is taken"))
+                .collect(),
+            displacements,
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the `FrozenMap` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the value of a key, `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    /// Returns the entry of a key, `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let hash = hash_of(key);
+        let displacement = self.displacements[(hash % self.displacements.len() as u64) as usize];
+        let (other, value) = &self.entries[slot_of(hash, displacement, self.entries.len())];
+        if other.borrow() == key {
+            Some((other, value))
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the key is present, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_value(key).is_some()
+    }
+
+    /// Iterate over the entries, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+
+    /// Iterate over the keys, in no particular order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    /// Iterate over the values, in no particular order.
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.entries.iter().map(|(_, value)| value)
+    }
+}
+
+impl<K: Hash + Eq + Clone> FrozenMap<K, u128> {
+    /// Create a new `FrozenMap` of the counts of a `Counter`.
+    ///
+    /// # Arguments
+    /// * counter - The `Counter` of the keys
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, FrozenMap};
+    /// let counter: Counter<&str> = "to be or not to be".split(' ').collect();
+    /// let vocabulary = FrozenMap::from_counter(&counter);
+    /// assert_eq!(vocabulary["be"], 2);
+    /// assert_eq!(vocabulary.len(), 4);
+    /// ```
+    pub fn from_counter(counter: &Counter<K>) -> Self
+    where
+        K: Ord,
+    {
+        FrozenMap::new(counter.iter().map(|(key, count)| (key.clone(), *count)))
+    }
+}
+
+/// Returns the hash of a key.
+fn hash_of<Q: Hash + ?Sized>(key: &Q) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the slot of a key of hash `hash` under a displacement.
+fn slot_of(hash: u64, displacement: u32, len: usize) -> usize {
+    (mix(hash ^ mix(u64::from(displacement))) % len as u64) as usize
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for FrozenMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        FrozenMap::new(iter)
+    }
+}
+
+impl<K, V, Q> Index<&Q> for FrozenMap<K, V>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+{
+    type Output = V;
+
+    /// Returns the value of a key.
+    ///
+    /// # Panics
+    /// Panics if the key is absent.
+    fn index(&self, key: &Q) -> &Self::Output {
+        self.get(key).expect("key not found")
+    }
+}
@@ -0,0 +1,349 @@
+//! This module implements a two dimensional grid of cells.
+//!
+//! Cells are stored contiguously row after row, so a cell is read in O(1)
+//! from its `(row, column)` position, a row is a plain slice and a column
+//! a strided walk. Positions are checked against both dimensions, so
+//! a column past the end of a row never reads into the next one, and
+//! neighbors at the edges of the grid are simply left out.
+//!
+use std::ops::{Index, IndexMut};
+
+/// Structure that hold `T` cells in rows and columns
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    columns: usize,
+}
+
+impl<T> Grid<T> {
+    /// Create a new `Grid` of default cells.
+    ///
+    /// # Arguments
+    /// * rows - The number of rows
+    /// * columns - The number of columns
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Grid;
+    /// let grid: Grid<u128> = Grid::new(3, 4);
+    /// # assert_eq!(grid.rows(), 3);
+    /// # assert_eq!(grid.columns(), 4);
+    /// ```
+    pub fn new(rows: usize, columns: usize) -> Self
+    where
+        T: Default,
+    {
+        Grid::from_fn(rows, columns, |_, _| T::default())
+    }
+
+    /// Create a new `Grid` of cells equal to `value`.
+    ///
+    /// # Arguments
+    /// * rows - The number of rows
+    /// * columns - The number of columns
+    /// * value - The value of every cell
+    pub fn filled(rows: usize, columns: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![value; rows * columns],
+            rows,
+            columns,
+        }
+    }
+
+    /// Create a new `Grid` whose cells are computed from their position.
+    ///
+    /// # Arguments
+    /// * rows - The number of rows
+    /// * columns - The number of columns
+    /// * f - The function computing a cell from its row and column
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Grid;
+    /// let table: Grid<usize> = Grid::from_fn(2, 3, |row, column| row * 10 + column);
+    /// assert_eq!(table.row(1), &[10, 11, 12]);
+    /// ```
+    pub fn from_fn<F>(rows: usize, columns: usize, mut f: F) -> Self
+    where
+        F: FnMut(usize, usize) -> T,
+    {
+        let mut cells: Vec<T> = Vec::with_capacity(rows * columns);
+        for row in 0..rows {
+            for column in 0..columns {
+                cells.push(f(row, column));
+            }
+        }
+        Grid {
+            cells,
+            rows,
+            columns,
+        }
+    }
+
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns `true` if the `Grid` has no cell, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns a reference to a cell, `None` if out of bounds.
+    ///
+    /// # Arguments
+    /// * row - The row of the cell
+    /// * column - The column of the cell
+    pub fn get(&self, row: usize, column: usize) -> Option<&T> {
+        if row < self.rows && column < self.columns {
+            Some(&self.cells[row * self.columns + column])
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to a cell, `None` if out of bounds.
+    ///
+    /// # Arguments
+    /// * row - The row of the cell
+    /// * column - The column of the cell
+    pub fn get_mut(&mut self, row: usize, column: usize) -> Option<&mut T> {
+        if row < self.rows && column < self.columns {
+            Some(&mut self.cells[row * self.columns + column])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cells of a row.
+    ///
+    /// # Arguments
+    /// * row - The row, must be lower than the number of rows
+    pub fn row(&self, row: usize) -> &[T] {
+        assert!(row < self.rows);
+        &self.cells[row * self.columns..(row + 1) * self.columns]
+    }
+
+    /// Returns the cells of a row, mutably.
+    ///
+    /// # Arguments
+    /// * row - The row, must be lower than the number of rows
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        assert!(row < self.rows);
+        &mut self.cells[row * self.columns..(row + 1) * self.columns]
+    }
+
+    /// Iterate over the cells of a column, from the first row.
+    ///
+    /// # Arguments
+    /// * column - The column, must be lower than the number of columns
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Grid;
+    /// let grid: Grid<usize> = Grid::from_fn(3, 2, |row, column| row + column);
+    /// let second: Vec<&usize> = grid.column(1).collect();
+    /// assert_eq!(second, vec![&1, &2, &3]);
+    /// ```
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> + '_ {
+        assert!(column < self.columns);
+        self.cells.iter().skip(column).step_by(self.columns)
+    }
+
+    /// Iterate over the rows, from the first one.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.rows).map(move |row| self.row(row))
+    }
+
+    /// Iterate over the columns, from the first one, each column being an
+    /// iterator over its cells.
+    pub fn iter_columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.columns).map(move |column| self.column(column))
+    }
+
+    /// Iterate over the positions and cells, row after row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        let columns = self.columns;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(idx, cell)| ((idx / columns, idx % columns), cell))
+    }
+
+    /// Iterate over the positions and mutable cells, row after row.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = ((usize, usize), &mut T)> + '_ {
+        let columns = self.columns;
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .map(move |(idx, cell)| ((idx / columns, idx % columns), cell))
+    }
+
+    /// Iterate over the positions and cells of the up to 8 cells around a
+    /// position, the ones out of bounds being left out.
+    ///
+    /// # Arguments
+    /// * row - The row of the position
+    /// * column - The column of the position
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Grid;
+    /// let grid: Grid<u8> = Grid::new(3, 3);
+    /// assert_eq!(grid.neighbors(1, 1).count(), 8);
+    /// assert_eq!(grid.neighbors(0, 0).count(), 3);
+    /// assert_eq!(grid.neighbors(0, 1).count(), 5);
+    /// ```
+    pub fn neighbors(
+        &self,
+        row: usize,
+        column: usize,
+    ) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        const OFFSETS: [(isize, isize); 8] = [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ];
+        self.cells_at(row, column, &OFFSETS)
+    }
+
+    /// Iterate over the positions and cells of the up to 4 cells sharing
+    /// an edge with a position, the ones out of bounds being left out.
+    ///
+    /// # Arguments
+    /// * row - The row of the position
+    /// * column - The column of the position
+    pub fn orthogonal_neighbors(
+        &self,
+        row: usize,
+        column: usize,
+    ) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        const OFFSETS: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+        self.cells_at(row, column, &OFFSETS)
+    }
+
+    /// Resize the `Grid`, cells keeping their position and new cells being
+    /// equal to `value`.
+    ///
+    /// # Arguments
+    /// * rows - The new number of rows
+    /// * columns - The new number of columns
+    /// * value - The value of the new cells
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Grid;
+    /// let mut grid: Grid<char> = Grid::filled(2, 2, 'x');
+    /// grid.resize(3, 1, '.');
+    /// assert_eq!(grid.iter_rows().collect::<Vec<&[char]>>(), vec![&['x'], &['x'], &['.']]);
+    /// grid.resize(1, 3, '.');
+    /// assert_eq!(grid.row(0), &['x', '.', '.']);
+    /// ```
+    pub fn resize(&mut self, rows: usize, columns: usize, value: T)
+    where
+        T: Clone,
+    {
+        if columns != self.columns {
+            let old_columns = self.columns;
+            let mut cells: Vec<T> = Vec::with_capacity(self.rows * columns);
+            let mut old = std::mem::take(&mut self.cells).into_iter();
+            for _ in 0..self.rows {
+                let mut row: Vec<T> = old.by_ref().take(old_columns).collect();
+                row.resize(columns, value.clone());
+                cells.extend(row);
+            }
+            self.cells = cells;
+            self.columns = columns;
+        }
+        self.cells.resize(rows * columns, value);
+        self.rows = rows;
+    }
+
+    /// Set every cell to `value`.
+    ///
+    /// # Arguments
+    /// * value - The value of every cell
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        for cell in self.cells.iter_mut() {
+            *cell = value.clone();
+        }
+    }
+
+    /// Returns the cells, row after row.
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Convert into the cells, row after row.
+    pub fn into_vec(self) -> Vec<T> {
+        self.cells
+    }
+
+    fn cells_at<'a>(
+        &'a self,
+        row: usize,
+        column: usize,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = ((usize, usize), &'a T)> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |(row_offset, column_offset)| {
+                let row = row.checked_add_signed(*row_offset)?;
+                let column = column.checked_add_signed(*column_offset)?;
+                Some(((row, column), self.get(row, column)?))
+            })
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    /// Returns the cell at a `(row, column)` position.
+    ///
+    /// # Panics
+    /// Panics if the position is out of bounds.
+    fn index(&self, (row, column): (usize, usize)) -> &Self::Output {
+        self.get(row, column).expect("position out of bounds")
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    /// Returns the cell at a `(row, column)` position, mutably.
+    ///
+    /// # Panics
+    /// Panics if the position is out of bounds.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Grid;
+    /// // Events per cell of a map.
+    /// let events = vec![(0, 1), (2, 2), (0, 1)];
+    /// let mut heat: Grid<u128> = Grid::new(3, 3);
+    /// for position in events {
+    ///     heat[position] += 1;
+    /// }
+    /// assert_eq!(heat[(0, 1)], 2);
+    /// assert_eq!(heat.neighbors(1, 1).map(|(_, count)| count).sum::<u128>(), 3);
+    /// ```
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut Self::Output {
+        self.get_mut(row, column).expect("position out of bounds")
+    }
+}
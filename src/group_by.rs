@@ -0,0 +1,153 @@
+//! This module implements the grouping of elements by a key.
+//!
+//! Where a `Counter` only keeps how many elements share a key, grouping
+//! keeps the elements themselves, in iteration order, in a `Vec` per key.
+//! `group_by` groups a whole iterator at once, while a `GroupBy` collects
+//! elements as they come.
+//!
+use crate::Counter;
+use std::borrow::Borrow;
+use std::collections::btree_map::{BTreeMap, Iter};
+
+/// Group the elements of an iterator by the key `key` gives them.
+///
+/// # Arguments
+/// * iter - The elements grouped
+/// * key - A function giving the key of an element
+///
+/// # Examples
+/// ```
+/// # use collectors::group_by;
+/// let words = vec!["apple", "avocado", "banana", "blueberry", "cherry"];
+/// let by_initial = group_by(words, |word| word.chars().next());
+/// assert_eq!(by_initial[&Some('a')], vec!["apple", "avocado"]);
+/// assert_eq!(by_initial[&Some('c')], vec!["cherry"]);
+/// assert_eq!(by_initial.len(), 3);
+/// ```
+pub fn group_by<I, K, F>(iter: I, mut key: F) -> BTreeMap<K, Vec<I::Item>>
+where
+    I: IntoIterator,
+    K: Ord,
+    F: FnMut(&I::Item) -> K,
+{
+    let mut groups: BTreeMap<K, Vec<I::Item>> = BTreeMap::new();
+    for elem in iter {
+        groups.entry(key(&elem)).or_default().push(elem);
+    }
+    groups
+}
+
+/// Structure that group `T` elements by `K` key
+#[derive(Debug, Clone)]
+pub struct GroupBy<K, T> {
+    key: fn(&T) -> K,
+    groups: BTreeMap<K, Vec<T>>,
+}
+
+impl<K: Ord, T> GroupBy<K, T> {
+    /// Create a new empty `GroupBy`.
+    ///
+    /// # Arguments
+    /// * key - The function giving the key of an element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::GroupBy;
+    /// let groups: GroupBy<bool, u32> = GroupBy::new(|n| n % 2 == 0);
+    /// # assert!(groups.is_empty());
+    /// ```
+    pub fn new(key: fn(&T) -> K) -> Self {
+        GroupBy {
+            key,
+            groups: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of groups.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Returns `true` if no element was added, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Add an element to the group of its key.
+    ///
+    /// # Arguments
+    /// * elem - The element added
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::GroupBy;
+    /// // Requests by status class.
+    /// let mut requests: GroupBy<u16, (&str, u16)> = GroupBy::new(|(_, status)| status / 100);
+    /// requests.push(("/", 200));
+    /// requests.push(("/missing", 404));
+    /// requests.push(("/login", 204));
+    /// assert_eq!(requests.get(&2), &[("/", 200), ("/login", 204)]);
+    /// assert_eq!(requests.get(&5), &[]);
+    /// ```
+    pub fn push(&mut self, elem: T) {
+        self.groups.entry((self.key)(&elem)).or_default().push(elem);
+    }
+
+    /// Returns the elements of a group, in insertion order, empty if no
+    /// element has the key.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key of the group
+    pub fn get<Q>(&self, key: &Q) -> &[T]
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.groups.get(key).map_or(&[], |elems| elems.as_slice())
+    }
+
+    /// Iterate over the groups, in key order.
+    pub fn iter(&self) -> Iter<'_, K, Vec<T>> {
+        self.groups.iter()
+    }
+
+    /// Returns the number of elements of every group.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::GroupBy;
+    /// let mut lengths: GroupBy<usize, &str> = GroupBy::new(|word| word.len());
+    /// lengths.extend(vec!["a", "bb", "cc", "ddd"]);
+    /// assert_eq!(lengths.to_counter()[&2], 2);
+    /// ```
+    pub fn to_counter(&self) -> Counter<K>
+    where
+        K: Clone,
+    {
+        let mut counter: Counter<K> = Counter::new();
+        counter.extend(
+            self.groups
+                .iter()
+                .map(|(key, elems)| (key.clone(), elems.len() as u128)),
+        );
+        counter
+    }
+
+    /// Convert into the groups.
+    pub fn into_groups(self) -> BTreeMap<K, Vec<T>> {
+        self.groups
+    }
+
+    /// Remove every group.
+    pub fn clear(&mut self) {
+        self.groups.clear();
+    }
+}
+
+impl<K: Ord, T> Extend<T> for GroupBy<K, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! This module implements a `Counter` per group, such as the error
+//! messages of each service.
+//!
+//! Groups are created on their first update, so a `GroupedCounter` only
+//! holds the groups that were seen.
+//!
+use crate::Counter;
+use std::borrow::Borrow;
+use std::cmp::Reverse;
+use std::collections::btree_map::{BTreeMap, Iter};
+use std::iter::FromIterator;
+
+/// Structure that count occurences of `T` elements per `G` group
+#[derive(Debug, Clone)]
+pub struct GroupedCounter<G, T> {
+    groups: BTreeMap<G, Counter<T>>,
+}
+
+impl<G: Ord, T: Ord> GroupedCounter<G, T> {
+    /// Create a new empty `GroupedCounter`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::GroupedCounter;
+    /// let counter: GroupedCounter<&str, &str> = GroupedCounter::new();
+    /// # assert!(counter.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        GroupedCounter {
+            groups: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of groups.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Returns `true` if no group was updated, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Count an element in a group.
+    ///
+    /// # Arguments
+    /// * group - The group of the element
+    /// * elem - A value used to update the `Counter` of the group
+    pub fn update(&mut self, group: G, elem: T) {
+        self.groups
+            .entry(group)
+            .or_default()
+            .update_from_value(elem);
+    }
+
+    /// Count every `(group, element)` pair of an iterator.
+    ///
+    /// # Arguments
+    /// * iter - An iterator over the elements and their groups
+    pub fn update_from_iter<I>(&mut self, iter: I)
+    where
+        I: Iterator<Item = (G, T)>,
+    {
+        for (group, elem) in iter {
+            self.update(group, elem);
+        }
+    }
+
+    /// Returns the `Counter` of a group, `None` if it was never updated.
+    ///
+    /// # Arguments
+    /// * group - A borrowed form of the group
+    pub fn group<Q>(&self, group: &Q) -> Option<&Counter<T>>
+    where
+        G: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.groups.get(group)
+    }
+
+    /// Iterate over the groups and their `Counter`, in group order.
+    pub fn groups(&self) -> Iter<'_, G, Counter<T>> {
+        self.groups.iter()
+    }
+
+    /// Returns the `k` most counted elements of each group along with their
+    /// counts, groups in order and elements by decreasing count, ties
+    /// sorted by key.
+    ///
+    /// # Arguments
+    /// * k - The maximum number of elements returned per group
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::GroupedCounter;
+    /// let logs = vec![
+    ///     ("api", "timeout"),
+    ///     ("db", "deadlock"),
+    ///     ("api", "bad request"),
+    ///     ("api", "timeout"),
+    ///     ("db", "disk full"),
+    ///     ("db", "deadlock"),
+    /// ];
+    /// let errors: GroupedCounter<&str, &str> = logs.into_iter().collect();
+    /// assert_eq!(
+    ///     errors.top_k_per_group(1),
+    ///     vec![(&"api", vec![(&"timeout", 2)]), (&"db", vec![(&"deadlock", 2)])]
+    /// );
+    /// ```
+    pub fn top_k_per_group(&self, k: usize) -> Vec<(&G, Vec<(&T, u128)>)> {
+        self.groups
+            .iter()
+            .map(|(group, counter)| {
+                let mut top: Vec<(&T, u128)> = counter
+                    .iter()
+                    .filter(|(_, count)| **count > 0)
+                    .map(|(key, count)| (key, *count))
+                    .collect();
+                top.sort_by_key(|(_, count)| Reverse(*count));
+                top.truncate(k);
+                (group, top)
+            })
+            .collect()
+    }
+
+    /// Merge the `Counter` of every group into a new `Counter`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::GroupedCounter;
+    /// let errors: GroupedCounter<&str, &str> =
+    ///     vec![("api", "timeout"), ("db", "timeout")].into_iter().collect();
+    /// assert_eq!(errors.rolled_up()[&"timeout"], 2);
+    /// ```
+    pub fn rolled_up(&self) -> Counter<T>
+    where
+        T: Clone,
+    {
+        let mut counter: Counter<T> = Counter::new();
+        for group in self.groups.values() {
+            counter.update_from_counter(group);
+        }
+        counter
+    }
+}
+
+impl<G: Ord, T: Ord> FromIterator<(G, T)> for GroupedCounter<G, T> {
+    fn from_iter<I: IntoIterator<Item = (G, T)>>(iter: I) -> Self {
+        let mut counter: GroupedCounter<G, T> = GroupedCounter::new();
+        counter.update_from_iter(iter.into_iter());
+        counter
+    }
+}
+
+impl<G, T> Default for GroupedCounter<G, T> {
+    fn default() -> Self {
+        GroupedCounter {
+            groups: BTreeMap::new(),
+        }
+    }
+}
@@ -0,0 +1,330 @@
+//! This module implements a histogram of integer values with bounded
+//! relative error, after HdrHistogram.
+//!
+//! Values are counted in buckets whose width grows with the magnitude of
+//! the values, so that the values of a bucket share their first
+//! `significant_digits` decimal digits: with 3 digits, a latency of 1 ms
+//! is told apart from 1.001 ms, and 1 s from 1.001 s, in a fixed amount
+//! of memory. Histograms of the same layout merge without loss, so every
+//! thread of a service can record its own.
+//!
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Error returned when a value is larger than the highest value a
+/// `HdrHistogram` tracks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueOutOfRangeError;
+
+impl Display for ValueOutOfRangeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("value out of range")
+    }
+}
+
+impl Error for ValueOutOfRangeError {}
+
+/// Structure that count `u64` values with a bounded relative error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HdrHistogram {
+    counts: Vec<u128>,
+    lowest: u64,
+    highest: u64,
+    significant_digits: u8,
+    unit_magnitude: u32,
+    sub_bucket_half_count_magnitude: u32,
+    sub_bucket_mask: u64,
+    total: u128,
+    min: u64,
+    max: u64,
+}
+
+impl HdrHistogram {
+    /// Create a new empty `HdrHistogram`.
+    ///
+    /// # Arguments
+    /// * lowest - The smallest value told apart from 0, must be at least 1
+    /// * highest - The largest value recorded, must be at least twice `lowest`
+    /// * significant_digits - The number of decimal digits kept, from 1 to 5
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::HdrHistogram;
+    /// // Latencies in microseconds, up to an hour.
+    /// let histogram = HdrHistogram::new(1, 3_600_000_000, 3);
+    /// # assert!(histogram.is_empty());
+    /// ```
+    pub fn new(lowest: u64, highest: u64, significant_digits: u8) -> Self {
+        assert!(lowest >= 1);
+        assert!(highest / 2 >= lowest);
+        assert!((1..=5).contains(&significant_digits));
+        let largest_single_unit = 2 * 10u64.pow(significant_digits as u32);
+        // The smallest power of 2 making single units of every value up to
+        // `largest_single_unit`.
+        let sub_bucket_count_magnitude = 64 - (largest_single_unit - 1).leading_zeros();
+        let sub_bucket_half_count_magnitude = sub_bucket_count_magnitude - 1;
+        let unit_magnitude = 63 - lowest.leading_zeros();
+        assert!(unit_magnitude + sub_bucket_half_count_magnitude <= 61);
+        let sub_bucket_count = 1u64 << sub_bucket_count_magnitude;
+
+        let mut bucket_count = 1;
+        let mut smallest_untrackable = (sub_bucket_count as u128) << unit_magnitude;
+        while smallest_untrackable <= highest as u128 {
+            smallest_untrackable <<= 1;
+            bucket_count += 1;
+        }
+        HdrHistogram {
+            counts: vec![0; (bucket_count + 1) << sub_bucket_half_count_magnitude],
+            lowest,
+            highest,
+            significant_digits,
+            unit_magnitude,
+            sub_bucket_half_count_magnitude,
+            sub_bucket_mask: (sub_bucket_count - 1) << unit_magnitude,
+            total: 0,
+            min: u64::MAX,
+            max: 0,
+        }
+    }
+
+    /// Returns the smallest value told apart from 0.
+    pub fn lowest(&self) -> u64 {
+        self.lowest
+    }
+
+    /// Returns the largest value that can be recorded.
+    pub fn highest(&self) -> u64 {
+        self.highest
+    }
+
+    /// Returns the number of decimal digits kept.
+    pub fn significant_digits(&self) -> u8 {
+        self.significant_digits
+    }
+
+    /// Returns the number of values recorded.
+    pub fn len(&self) -> u128 {
+        self.total
+    }
+
+    /// Returns `true` if no value was recorded, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Record a value.
+    ///
+    /// # Arguments
+    /// * value - The value recorded, must not exceed `highest`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{HdrHistogram, ValueOutOfRangeError};
+    /// let mut histogram = HdrHistogram::new(1, 1000, 2);
+    /// assert_eq!(histogram.record(999), Ok(()));
+    /// assert_eq!(histogram.record(1001), Err(ValueOutOfRangeError));
+    /// assert_eq!(histogram.len(), 1);
+    /// ```
+    pub fn record(&mut self, value: u64) -> Result<(), ValueOutOfRangeError> {
+        self.record_n(value, 1)
+    }
+
+    /// Record a value `count` times.
+    ///
+    /// # Arguments
+    /// * value - The value recorded, must not exceed `highest`
+    /// * count - The number of times the value is recorded
+    pub fn record_n(&mut self, value: u64, count: u128) -> Result<(), ValueOutOfRangeError> {
+        if value > self.highest {
+            return Err(ValueOutOfRangeError);
+        }
+        if count == 0 {
+            return Ok(());
+        }
+        let idx = self.index_of(value);
+        self.counts[idx] += count;
+        self.total += count;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        Ok(())
+    }
+
+    /// Returns the number of values recorded in the bucket of `value`, that
+    /// is the values equivalent to it at the precision kept.
+    ///
+    /// # Arguments
+    /// * value - The value looked up
+    pub fn count_at(&self, value: u64) -> u128 {
+        if value > self.highest {
+            return 0;
+        }
+        self.counts[self.index_of(value)]
+    }
+
+    /// Returns the smallest value recorded, `None` if empty.
+    pub fn min(&self) -> Option<u64> {
+        if self.total == 0 {
+            return None;
+        }
+        Some(self.min)
+    }
+
+    /// Returns the largest value recorded, `None` if empty.
+    pub fn max(&self) -> Option<u64> {
+        if self.total == 0 {
+            return None;
+        }
+        Some(self.max)
+    }
+
+    /// Returns the mean of the values, each taken at the middle of its
+    /// bucket, `None` if empty.
+    pub fn mean(&self) -> Option<f64> {
+        if self.total == 0 {
+            return None;
+        }
+        let sum: f64 = self
+            .iter()
+            .map(|(value, count)| {
+                let middle = value + (self.equivalent_range(value) >> 1);
+                middle as f64 * count as f64
+            })
+            .sum();
+        Some(sum / self.total as f64)
+    }
+
+    /// Returns the value below which `percentile` percents of the values
+    /// fall, within the precision kept, `None` if empty.
+    ///
+    /// # Arguments
+    /// * percentile - The percentile, from 0 to 100
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::HdrHistogram;
+    /// let mut latencies = HdrHistogram::new(1, 60_000_000, 3);
+    /// for us in 1..=10_000 {
+    ///     latencies.record(us).unwrap();
+    /// }
+    /// latencies.record(5_000_000).unwrap();
+    /// assert_eq!(latencies.value_at_percentile(50.0), Some(5_003));
+    /// assert_eq!(latencies.value_at_percentile(99.0), Some(9_903));
+    /// assert_eq!(latencies.value_at_percentile(100.0), Some(5_000_000));
+    /// ```
+    pub fn value_at_percentile(&self, percentile: f64) -> Option<u64> {
+        assert!((0.0..=100.0).contains(&percentile));
+        if self.total == 0 {
+            return None;
+        }
+        let rank = ((percentile / 100.0 * self.total as f64).ceil() as u128).max(1);
+        let mut seen: u128 = 0;
+        for (idx, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let value = self.value_from_index(idx);
+                let highest_equivalent = value + (self.equivalent_range(value) - 1);
+                return Some(highest_equivalent.min(self.max));
+            }
+        }
+        Some(self.max)
+    }
+
+    /// Iterate over the buckets holding values, as the smallest value of
+    /// the bucket along with its count, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, u128)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(move |(idx, count)| (self.value_from_index(idx), *count))
+    }
+
+    /// Add the values recorded by another `HdrHistogram`.
+    ///
+    /// The merge is lossless when both histograms have the same layout,
+    /// otherwise the values of `other` are recorded at the smallest value
+    /// of their bucket. Nothing is merged if a value of `other` exceeds
+    /// `highest`.
+    ///
+    /// # Arguments
+    /// * other - The histogram to merge
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::HdrHistogram;
+    /// let mut first = HdrHistogram::new(1, 100_000, 3);
+    /// let mut second = first.clone();
+    /// first.record(120).unwrap();
+    /// second.record_n(48_000, 3).unwrap();
+    /// first.merge(&second).unwrap();
+    /// assert_eq!(first.len(), 4);
+    /// assert_eq!(first.max(), Some(48_000));
+    /// assert_eq!(first.value_at_percentile(25.0), Some(120));
+    /// ```
+    pub fn merge(&mut self, other: &HdrHistogram) -> Result<(), ValueOutOfRangeError> {
+        let max = match other.max() {
+            Some(max) => max,
+            None => return Ok(()),
+        };
+        if max > self.highest {
+            return Err(ValueOutOfRangeError);
+        }
+        if self.counts.len() == other.counts.len()
+            && self.unit_magnitude == other.unit_magnitude
+            && self.sub_bucket_half_count_magnitude == other.sub_bucket_half_count_magnitude
+        {
+            for (count, other_count) in self.counts.iter_mut().zip(other.counts.iter()) {
+                *count += other_count;
+            }
+            self.total += other.total;
+        } else {
+            for (value, count) in other.iter() {
+                self.record_n(value, count)?;
+            }
+        }
+        // The exact extremes of `other` are known even when re-recorded.
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        Ok(())
+    }
+
+    /// Remove every value.
+    pub fn clear(&mut self) {
+        for count in self.counts.iter_mut() {
+            *count = 0;
+        }
+        self.total = 0;
+        self.min = u64::MAX;
+        self.max = 0;
+    }
+
+    fn bucket_index(&self, value: u64) -> u32 {
+        let leading_zeros_base = 63 - self.unit_magnitude - self.sub_bucket_half_count_magnitude;
+        leading_zeros_base - (value | self.sub_bucket_mask).leading_zeros()
+    }
+
+    fn index_of(&self, value: u64) -> usize {
+        let bucket_idx = self.bucket_index(value);
+        let sub_bucket_idx = (value >> (bucket_idx + self.unit_magnitude)) as usize;
+        ((bucket_idx as usize + 1) << self.sub_bucket_half_count_magnitude) + sub_bucket_idx
+            - (1 << self.sub_bucket_half_count_magnitude)
+    }
+
+    /// Returns the smallest value of the bucket at `idx`.
+    fn value_from_index(&self, idx: usize) -> u64 {
+        let sub_bucket_half_count = 1 << self.sub_bucket_half_count_magnitude;
+        let bucket_idx = idx >> self.sub_bucket_half_count_magnitude;
+        let sub_bucket_idx = idx & (sub_bucket_half_count - 1);
+        // The first bucket also holds the lower half of sub-buckets.
+        let (bucket_idx, sub_bucket_idx) = match bucket_idx {
+            0 => (0, sub_bucket_idx),
+            _ => (bucket_idx - 1, sub_bucket_idx + sub_bucket_half_count),
+        };
+        (sub_bucket_idx as u64) << (bucket_idx as u32 + self.unit_magnitude)
+    }
+
+    /// Returns the width of the bucket of `value`.
+    fn equivalent_range(&self, value: u64) -> u64 {
+        1 << (self.unit_magnitude + self.bucket_index(value))
+    }
+}
@@ -0,0 +1,143 @@
+//! This module implements an estimation of the memory used by a `Counter`.
+//!
+//! Element types report the heap memory they own through the `HeapSize`
+//! trait, it is implemented for the primitive types, `String`, `Vec`,
+//! `Box`, `Option` and tuples, and can be implemented for user types.
+//!
+use crate::Counter;
+use std::mem::size_of;
+
+/// Number of elements a node of the standard `BTreeMap` can hold.
+const NODE_CAPACITY: usize = 11;
+
+/// Trait reporting the heap memory owned by a value
+///
+/// # Examples
+/// ```
+/// # use collectors::{Counter, HeapSize};
+/// #[derive(PartialEq, Eq, PartialOrd, Ord)]
+/// struct Tenant {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// impl HeapSize for Tenant {
+///     fn heap_size(&self) -> usize {
+///         self.name.heap_size()
+///     }
+/// }
+///
+/// let mut counter: Counter<Tenant> = Counter::new();
+/// counter.update_from_value(Tenant { id: 1, name: String::from("acme") });
+/// assert!(counter.estimated_heap_size() >= 4);
+/// ```
+pub trait HeapSize {
+    /// Returns the number of bytes allocated on the heap by the value,
+    /// not counting `size_of::<Self>()` itself.
+    fn heap_size(&self) -> usize;
+}
+
+macro_rules! impl_heap_size_for_inline_types {
+    ($($t:ty),*) => {
+        $(
+            impl HeapSize for $t {
+                fn heap_size(&self) -> usize {
+                    0
+                }
+            }
+        )*
+    };
+}
+
+impl_heap_size_for_inline_types!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64
+);
+
+impl<T: ?Sized> HeapSize for &T {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl HeapSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(HeapSize::heap_size).sum::<usize>()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Box<T> {
+    fn heap_size(&self) -> usize {
+        size_of::<T>() + (**self).heap_size()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Option<T> {
+    fn heap_size(&self) -> usize {
+        self.as_ref().map_or(0, HeapSize::heap_size)
+    }
+}
+
+impl<A: HeapSize, B: HeapSize> HeapSize for (A, B) {
+    fn heap_size(&self) -> usize {
+        self.0.heap_size() + self.1.heap_size()
+    }
+}
+
+impl<A: HeapSize, B: HeapSize, C: HeapSize> HeapSize for (A, B, C) {
+    fn heap_size(&self) -> usize {
+        self.0.heap_size() + self.1.heap_size() + self.2.heap_size()
+    }
+}
+
+impl<T: HeapSize> Counter<T> {
+    /// Returns an estimation of the number of bytes the `Counter` allocated
+    /// on the heap, for its map nodes and the heap memory of its elements.
+    ///
+    /// Map nodes are assumed to be about 70% full, as they are after random
+    /// insertions, so the estimation is only approximate.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let empty: Counter<u32> = Counter::new();
+    /// assert_eq!(empty.estimated_heap_size(), 0);
+    ///
+    /// let numbers: Counter<u32> = (0..1000).collect();
+    /// let words: Counter<String> = (0..1000).map(|i| format!("word-{:04}", i)).collect();
+    /// assert!(numbers.estimated_heap_size() > 1000 * 20);
+    /// assert!(words.estimated_heap_size() > numbers.estimated_heap_size() + 1000 * 9);
+    /// ```
+    pub fn estimated_heap_size(&self) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        let entry_size = size_of::<T>() + size_of::<u128>();
+        // Besides its entries, a node holds a parent pointer and two
+        // indices, internal nodes are neglected.
+        let node_size = NODE_CAPACITY * entry_size + size_of::<usize>() + 2 * size_of::<u16>();
+        let nodes = (self.len() * 10).div_ceil(NODE_CAPACITY * 7);
+        nodes * node_size + self.iter().map(|(key, _)| key.heap_size()).sum::<usize>()
+    }
+}
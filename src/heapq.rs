@@ -0,0 +1,139 @@
+//! This module implements the selection of the largest or smallest
+//! elements of an iterator, after Python's `heapq.nlargest` and
+//! `heapq.nsmallest`.
+//!
+//! The `k` best elements seen so far are kept in a bounded heap whose root
+//! is the worst of them, so a single pass costs O(n log k) and memory never
+//! grows past `k` elements, where sorting everything would be O(n log n)
+//! and keep all `n`. Unlike a `TopK`, which collects a stream, these
+//! consume a whole iterator at once and return the selected elements
+//! sorted. Among elements of equal key, the earliest are selected and come
+//! first, as sorting stably would.
+//!
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// An element along with its key and a tiebreaker on its position.
+struct Entry<K, S, T> {
+    key: K,
+    seq: S,
+    elem: T,
+}
+
+impl<K: Ord, S: Ord, T> PartialEq for Entry<K, S, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K: Ord, S: Ord, T> Eq for Entry<K, S, T> {}
+
+impl<K: Ord, S: Ord, T> PartialOrd for Entry<K, S, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, S: Ord, T> Ord for Entry<K, S, T> {
+    /// Orders by key, then by tiebreaker, ignoring the element.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key
+            .cmp(&other.key)
+            .then_with(|| self.seq.cmp(&other.seq))
+    }
+}
+
+/// Returns the `k` elements of an iterator with the largest keys, largest
+/// first.
+///
+/// # Arguments
+/// * k - The number of elements returned, at most
+/// * iter - The elements selected from
+/// * key - A function giving the key of an element
+///
+/// # Examples
+/// ```
+/// # use collectors::nlargest;
+/// // Top 2 players by score.
+/// let scores = vec![("ann", 72), ("bob", 95), ("cid", 88), ("dee", 95)];
+/// let top = nlargest(2, scores, |(_, score)| *score);
+/// assert_eq!(top, vec![("bob", 95), ("dee", 95)]);
+/// ```
+pub fn nlargest<I, K, F>(k: usize, iter: I, mut key: F) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    K: Ord,
+    F: FnMut(&I::Item) -> K,
+{
+    if k == 0 {
+        return Vec::new();
+    }
+    // A min heap, where of equal keys the latest is the smallest.
+    let mut heap = BinaryHeap::new();
+    for (seq, elem) in iter.into_iter().enumerate() {
+        let entry = Entry {
+            key: key(&elem),
+            seq: Reverse(seq),
+            elem,
+        };
+        if heap.len() < k {
+            heap.push(Reverse(entry));
+        } else if let Some(mut worst) = heap.peek_mut() {
+            if entry > worst.0 {
+                *worst = Reverse(entry);
+            }
+        }
+    }
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(entry)| entry.elem)
+        .collect()
+}
+
+/// Returns the `k` elements of an iterator with the smallest keys,
+/// smallest first.
+///
+/// # Arguments
+/// * k - The number of elements returned, at most
+/// * iter - The elements selected from
+/// * key - A function giving the key of an element
+///
+/// # Examples
+/// ```
+/// # use collectors::nsmallest;
+/// // The 3 fastest responses, in milliseconds.
+/// let responses = vec![("/a", 120), ("/b", 15), ("/c", 48), ("/d", 15), ("/e", 300)];
+/// let fastest = nsmallest(3, responses, |(_, ms)| *ms);
+/// assert_eq!(fastest, vec![("/b", 15), ("/d", 15), ("/c", 48)]);
+/// assert!(nsmallest(3, Vec::<u32>::new(), |n| *n).is_empty());
+/// ```
+pub fn nsmallest<I, K, F>(k: usize, iter: I, mut key: F) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    K: Ord,
+    F: FnMut(&I::Item) -> K,
+{
+    if k == 0 {
+        return Vec::new();
+    }
+    // A max heap, where of equal keys the latest is the largest.
+    let mut heap = BinaryHeap::new();
+    for (seq, elem) in iter.into_iter().enumerate() {
+        let entry = Entry {
+            key: key(&elem),
+            seq,
+            elem,
+        };
+        if heap.len() < k {
+            heap.push(entry);
+        } else if let Some(mut worst) = heap.peek_mut() {
+            if entry < *worst {
+                *worst = entry;
+            }
+        }
+    }
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|entry| entry.elem)
+        .collect()
+}
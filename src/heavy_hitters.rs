@@ -0,0 +1,167 @@
+//! This module implements the Space-Saving algorithm, tracking the
+//! approximate most frequent elements of an unbounded stream in a fixed
+//! amount of memory.
+//!
+//! At most `capacity` elements are monitored. When a new element arrives
+//! while the structure is full, it replaces the monitored element with the
+//! smallest count and inherits that count as its error. Hence:
+//! * the count of a monitored element never underestimates its true count,
+//!   and overestimates it by at most its error,
+//! * the error of any element is at most `total / capacity`,
+//! * any element occuring more than `total / capacity` times is monitored.
+//!
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Structure that track the approximate top elements of a stream
+#[derive(Debug, Clone)]
+pub struct HeavyHitters<T> {
+    monitored: BTreeMap<T, (u128, u128)>,
+    by_count: BTreeSet<(u128, T)>,
+    capacity: usize,
+    total: u128,
+}
+
+impl<T: Ord + Clone> HeavyHitters<T> {
+    /// Create a new empty `HeavyHitters` monitoring at most `capacity` elements.
+    ///
+    /// # Arguments
+    /// * capacity - The maximum number of monitored elements, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::HeavyHitters;
+    /// let hitters: HeavyHitters<char> = HeavyHitters::new(10);
+    /// # assert_eq!(hitters.capacity(), 10);
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        HeavyHitters {
+            monitored: BTreeMap::new(),
+            by_count: BTreeSet::new(),
+            capacity,
+            total: 0,
+        }
+    }
+
+    /// Returns the maximum number of monitored elements.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of currently monitored elements.
+    pub fn len(&self) -> usize {
+        self.monitored.len()
+    }
+
+    /// Returns `true` if no element was offered yet, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.monitored.is_empty()
+    }
+
+    /// Returns the number of elements offered so far.
+    pub fn total(&self) -> u128 {
+        self.total
+    }
+
+    /// Offer an element of the stream.
+    ///
+    /// # Arguments
+    /// * elem - The next element of the stream
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::HeavyHitters;
+    /// let mut hitters: HeavyHitters<char> = HeavyHitters::new(2);
+    /// for c in "aabac".chars() {
+    ///     hitters.offer(c);
+    /// }
+    /// assert_eq!(hitters.count(&'a'), Some(3));
+    /// // 'c' replaced 'b', inheriting its count as error.
+    /// assert_eq!(hitters.count(&'b'), None);
+    /// assert_eq!(hitters.count(&'c'), Some(2));
+    /// assert_eq!(hitters.error(&'c'), Some(1));
+    /// ```
+    pub fn offer(&mut self, elem: T) {
+        self.total += 1;
+        if let Some((count, _)) = self.monitored.get_mut(&elem) {
+            let _ = self.by_count.remove(&(*count, elem.clone()));
+            *count += 1;
+            let _ = self.by_count.insert((*count, elem));
+            return;
+        }
+
+        let (count, error) = if self.monitored.len() < self.capacity {
+            (1, 0)
+        } else {
+            let (min_count, victim) = self.by_count.pop_first().unwrap();
+            let _ = self.monitored.remove(&victim);
+            (min_count + 1, min_count)
+        };
+        let _ = self.by_count.insert((count, elem.clone()));
+        let _ = self.monitored.insert(elem, (count, error));
+    }
+
+    /// Offer every element of an iterator.
+    ///
+    /// # Arguments
+    /// * iter - The next elements of the stream
+    pub fn offer_from_iter<I>(&mut self, iter: I)
+    where
+        I: Iterator<Item = T>,
+    {
+        for elem in iter {
+            self.offer(elem);
+        }
+    }
+
+    /// Returns the estimated count of a monitored element, `None` if it is
+    /// not monitored.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element to look up
+    pub fn count<Q>(&self, elem: &Q) -> Option<u128>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.monitored.get(elem).map(|(count, _)| *count)
+    }
+
+    /// Returns the maximum overestimation of the count of a monitored
+    /// element, `None` if it is not monitored.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element to look up
+    pub fn error<Q>(&self, elem: &Q) -> Option<u128>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.monitored.get(elem).map(|(_, error)| *error)
+    }
+
+    /// Returns the `k` monitored elements with the highest estimated counts,
+    /// along with those counts, in decreasing order of count.
+    ///
+    /// # Arguments
+    /// * k - The maximum number of elements returned
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::HeavyHitters;
+    /// let mut hitters: HeavyHitters<char> = HeavyHitters::new(3);
+    /// hitters.offer_from_iter("abracadabra".chars());
+    /// let top = hitters.top(2);
+    /// assert_eq!(top[0], (&'a', 5));
+    /// assert_eq!(top.len(), 2);
+    /// ```
+    pub fn top(&self, k: usize) -> Vec<(&T, u128)> {
+        self.by_count
+            .iter()
+            .rev()
+            .take(k)
+            .map(|(count, elem)| (elem, *count))
+            .collect()
+    }
+}
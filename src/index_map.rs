@@ -0,0 +1,440 @@
+//! This module implements collections iterating in insertion order.
+//!
+//! An `IndexMap` stores its entries in a vector, in the order their keys
+//! were first inserted, along with the position of every key, so entries
+//! can be looked up both by key and by position. An `IndexSet` holds the
+//! keys alone, which numbers the elements of a vocabulary in a stable way.
+//!
+//! Removal either swaps the last entry into the hole, in O(log n) but
+//! perturbing the order, or shifts every following entry, in O(n).
+//!
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+use std::ops::Index;
+
+/// Structure that map `K` keys to `V` values in insertion order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexMap<K, V> {
+    entries: Vec<(K, V)>,
+    indices: BTreeMap<K, usize>,
+}
+
+impl<K: Ord + Clone, V> IndexMap<K, V> {
+    /// Create a new empty `IndexMap`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IndexMap;
+    /// let map: IndexMap<&str, u32> = IndexMap::new();
+    /// # assert!(map.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        IndexMap {
+            entries: Vec::new(),
+            indices: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the `IndexMap` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Insert a value, returning the previous value of the key if any.
+    ///
+    /// A key already present keeps its position.
+    ///
+    /// # Arguments
+    /// * key - The key of the value
+    /// * value - The value inserted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IndexMap;
+    /// let mut map: IndexMap<&str, u32> = IndexMap::new();
+    /// assert_eq!(map.insert("zebra", 1), None);
+    /// assert_eq!(map.insert("apple", 2), None);
+    /// assert_eq!(map.insert("zebra", 3), Some(1));
+    /// assert_eq!(map.keys().collect::<Vec<_>>(), vec![&"zebra", &"apple"]);
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert_full(key, value).1
+    }
+
+    /// Insert a value, returning the position of the key along with its
+    /// previous value if any.
+    ///
+    /// # Arguments
+    /// * key - The key of the value
+    /// * value - The value inserted
+    pub fn insert_full(&mut self, key: K, value: V) -> (usize, Option<V>) {
+        if let Some(idx) = self.indices.get(&key) {
+            let previous = std::mem::replace(&mut self.entries[*idx].1, value);
+            return (*idx, Some(previous));
+        }
+        let idx = self.entries.len();
+        let _ = self.indices.insert(key.clone(), idx);
+        self.entries.push((key, value));
+        (idx, None)
+    }
+
+    /// Returns the value of a key, `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = *self.indices.get(key)?;
+        Some(&self.entries[idx].1)
+    }
+
+    /// Returns a mutable reference to the value of a key, `None` if it is
+    /// absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = *self.indices.get(key)?;
+        Some(&mut self.entries[idx].1)
+    }
+
+    /// Returns the entry at position `idx`, `None` if out of bounds.
+    ///
+    /// # Arguments
+    /// * idx - The position of the entry
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IndexMap;
+    /// let map: IndexMap<char, u32> = vec![('x', 10), ('a', 20)].into_iter().collect();
+    /// assert_eq!(map.get_index(1), Some((&'a', &20)));
+    /// assert_eq!(map.get_index_of(&'x'), Some(0));
+    /// assert_eq!(map.get_index(2), None);
+    /// ```
+    pub fn get_index(&self, idx: usize) -> Option<(&K, &V)> {
+        self.entries.get(idx).map(|(key, value)| (key, value))
+    }
+
+    /// Returns the position of a key, `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get_index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.indices.get(key).cloned()
+    }
+
+    /// Returns `true` if the key is present, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.indices.contains_key(key)
+    }
+
+    /// Remove a key, moving the last entry to its position, and return its
+    /// value if it was present.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IndexMap;
+    /// let mut map: IndexMap<char, u32> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// assert_eq!(map.swap_remove(&'a'), Some(1));
+    /// assert_eq!(map.keys().collect::<Vec<_>>(), vec![&'c', &'b']);
+    /// ```
+    pub fn swap_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.indices.remove(key)?;
+        let (_, value) = self.entries.swap_remove(idx);
+        if let Some((moved, _)) = self.entries.get(idx) {
+            let _ = self.indices.insert(moved.clone(), idx);
+        }
+        Some(value)
+    }
+
+    /// Remove a key, shifting the following entries, and return its value
+    /// if it was present.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IndexMap;
+    /// let mut map: IndexMap<char, u32> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// assert_eq!(map.shift_remove(&'a'), Some(1));
+    /// assert_eq!(map.keys().collect::<Vec<_>>(), vec![&'b', &'c']);
+    /// assert_eq!(map.get_index_of(&'c'), Some(1));
+    /// ```
+    pub fn shift_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.indices.remove(key)?;
+        let (_, value) = self.entries.remove(idx);
+        for (key, _) in &self.entries[idx..] {
+            if let Some(position) = self.indices.get_mut::<K>(key) {
+                *position -= 1;
+            }
+        }
+        Some(value)
+    }
+
+    /// Remove the last entry, returning it.
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let (key, value) = self.entries.pop()?;
+        let _ = self.indices.remove(&key);
+        Some((key, value))
+    }
+
+    /// Iterate over the entries, in insertion order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> + '_ {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+
+    /// Iterate over the keys, in insertion order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + '_ {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    /// Iterate over the values, in insertion order.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + '_ {
+        self.entries.iter().map(|(_, value)| value)
+    }
+
+    /// Remove every entry.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.indices.clear();
+    }
+}
+
+impl<K: Ord + Clone, V> FromIterator<(K, V)> for IndexMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map: IndexMap<K, V> = IndexMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord + Clone, V> Extend<(K, V)> for IndexMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            let _ = self.insert(key, value);
+        }
+    }
+}
+
+impl<K, V> IntoIterator for IndexMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<K, V, Q> Index<&Q> for IndexMap<K, V>
+where
+    K: Ord + Clone + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    /// Returns the value of a key.
+    ///
+    /// # Panics
+    /// Panics if the key is absent.
+    fn index(&self, key: &Q) -> &Self::Output {
+        self.get(key).expect("key not found")
+    }
+}
+
+impl<K: Ord + Clone, V> Default for IndexMap<K, V> {
+    fn default() -> Self {
+        IndexMap::new()
+    }
+}
+
+/// Structure that hold unique `T` elements in insertion order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexSet<T> {
+    map: IndexMap<T, ()>,
+}
+
+impl<T: Ord + Clone> IndexSet<T> {
+    /// Create a new empty `IndexSet`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IndexSet;
+    /// let set: IndexSet<&str> = IndexSet::new();
+    /// # assert!(set.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        IndexSet {
+            map: IndexMap::new(),
+        }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the `IndexSet` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Add an element, returning `true` if it was absent.
+    ///
+    /// # Arguments
+    /// * elem - The element added
+    pub fn insert(&mut self, elem: T) -> bool {
+        self.map.insert(elem, ()).is_none()
+    }
+
+    /// Add an element, returning its position along with `true` if it was
+    /// absent.
+    ///
+    /// # Arguments
+    /// * elem - The element added
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IndexSet;
+    /// // Number the tokens of a text as they appear.
+    /// let mut vocabulary: IndexSet<&str> = IndexSet::new();
+    /// let ids: Vec<usize> = "to be or not to be"
+    ///     .split(' ')
+    ///     .map(|token| vocabulary.insert_full(token).0)
+    ///     .collect();
+    /// assert_eq!(ids, vec![0, 1, 2, 3, 0, 1]);
+    /// assert_eq!(vocabulary.get_index(3), Some(&"not"));
+    /// ```
+    pub fn insert_full(&mut self, elem: T) -> (usize, bool) {
+        let (idx, previous) = self.map.insert_full(elem, ());
+        (idx, previous.is_none())
+    }
+
+    /// Returns the element at position `idx`, `None` if out of bounds.
+    ///
+    /// # Arguments
+    /// * idx - The position of the element
+    pub fn get_index(&self, idx: usize) -> Option<&T> {
+        self.map.get_index(idx).map(|(elem, _)| elem)
+    }
+
+    /// Returns the position of an element, `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element
+    pub fn get_index_of<Q>(&self, elem: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.get_index_of(elem)
+    }
+
+    /// Returns `true` if the element is present, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element
+    pub fn contains<Q>(&self, elem: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(elem)
+    }
+
+    /// Remove an element, moving the last element to its position, and
+    /// return `true` if it was present.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element
+    pub fn swap_remove<Q>(&mut self, elem: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.swap_remove(elem).is_some()
+    }
+
+    /// Remove an element, shifting the following elements, and return
+    /// `true` if it was present.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element
+    pub fn shift_remove<Q>(&mut self, elem: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.shift_remove(elem).is_some()
+    }
+
+    /// Remove the last element, returning it.
+    pub fn pop(&mut self) -> Option<T> {
+        self.map.pop().map(|(elem, _)| elem)
+    }
+
+    /// Iterate over the elements, in insertion order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.map.keys()
+    }
+
+    /// Remove every element.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for IndexSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        IndexSet {
+            map: iter.into_iter().map(|elem| (elem, ())).collect(),
+        }
+    }
+}
+
+impl<T: Ord + Clone> Extend<T> for IndexSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.map.extend(iter.into_iter().map(|elem| (elem, ())));
+    }
+}
+
+impl<T: Ord + Clone> Default for IndexSet<T> {
+    fn default() -> Self {
+        IndexSet::new()
+    }
+}
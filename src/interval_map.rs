@@ -0,0 +1,324 @@
+//! This module implements collections of possibly overlapping intervals.
+//!
+//! An `IntervalMap` associates a value to every half open range inserted,
+//! and an `IntervalSet` holds the ranges alone. Ranges are kept sorted by
+//! start along with the running maximum of their ends, so point and
+//! overlap queries only visit the ranges that may match, at the cost of a
+//! linear time insertion. `coalesce` merges the overlapping or adjacent
+//! ranges, of equal values for an `IntervalMap`.
+//!
+use std::iter::FromIterator;
+use std::ops::Range;
+
+/// Structure that map possibly overlapping ranges of `K` to `V` values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalMap<K, V> {
+    intervals: Vec<(Range<K>, V)>,
+    max_ends: Vec<K>,
+}
+
+impl<K: Ord + Clone, V> IntervalMap<K, V> {
+    /// Create a new empty `IntervalMap`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IntervalMap;
+    /// let map: IntervalMap<usize, &str> = IntervalMap::new();
+    /// # assert!(map.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        IntervalMap {
+            intervals: Vec::new(),
+            max_ends: Vec::new(),
+        }
+    }
+
+    /// Returns the number of ranges.
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// Returns `true` if the `IntervalMap` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Insert a range along with its value, after the ranges of same start.
+    /// An empty range contains no point, so it is not inserted.
+    ///
+    /// # Arguments
+    /// * range - The range inserted
+    /// * value - The value of the range
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IntervalMap;
+    /// // Bit offsets of the fields of a parsed header.
+    /// let mut fields: IntervalMap<usize, &str> = IntervalMap::new();
+    /// fields.insert(0..32, "header");
+    /// fields.insert(0..4, "version");
+    /// fields.insert(4..8, "length");
+    /// fields.insert(16..32, "total length");
+    /// assert_eq!(fields.get(&5), Some(&"length"));
+    /// assert_eq!(fields.get(&10), Some(&"header"));
+    /// assert_eq!(fields.get(&32), None);
+    ///
+    /// fields.insert(8..8, "padding");
+    /// assert_eq!(fields.len(), 4);
+    /// ```
+    pub fn insert(&mut self, range: Range<K>, value: V) {
+        if range.start >= range.end {
+            return;
+        }
+        let idx = self
+            .intervals
+            .partition_point(|(other, _)| other.start <= range.start);
+        self.intervals.insert(idx, (range, value));
+        self.max_ends.truncate(idx);
+        self.update_max_ends();
+    }
+
+    /// Returns the value of the range of latest start containing `point`,
+    /// the innermost one for nested ranges, `None` if no range does.
+    ///
+    /// # Arguments
+    /// * point - The point looked up
+    pub fn get(&self, point: &K) -> Option<&V> {
+        self.containing(point).pop().map(|(_, value)| value)
+    }
+
+    /// Returns the ranges containing `point` and their values, by start.
+    ///
+    /// # Arguments
+    /// * point - The point looked up
+    pub fn containing(&self, point: &K) -> Vec<(&Range<K>, &V)> {
+        let idx = self
+            .intervals
+            .partition_point(|(range, _)| range.start <= *point);
+        self.scan_back(idx, |end| end > point)
+    }
+
+    /// Returns the ranges overlapping `range` and their values, by start.
+    ///
+    /// # Arguments
+    /// * range - The range whose overlapping ranges are returned
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IntervalMap;
+    /// let map: IntervalMap<u32, char> = vec![(0..10, 'a'), (5..8, 'b'), (12..20, 'c')].into_iter().collect();
+    /// let values: Vec<char> = map.overlapping(&(7..13)).into_iter().map(|(_, value)| *value).collect();
+    /// assert_eq!(values, vec!['a', 'b', 'c']);
+    /// assert!(map.overlapping(&(10..12)).is_empty());
+    /// ```
+    pub fn overlapping(&self, range: &Range<K>) -> Vec<(&Range<K>, &V)> {
+        let idx = self
+            .intervals
+            .partition_point(|(other, _)| other.start < range.end);
+        self.scan_back(idx, |end| *end > range.start)
+    }
+
+    /// Iterate over the ranges and their values, by start.
+    pub fn iter(&self) -> impl Iterator<Item = (&Range<K>, &V)> + '_ {
+        self.intervals.iter().map(|(range, value)| (range, value))
+    }
+
+    /// Merge the ranges of equal values that overlap or are adjacent.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IntervalMap;
+    /// let mut map: IntervalMap<u32, char> =
+    ///     vec![(0..4, 'a'), (2..6, 'a'), (6..9, 'a'), (3..5, 'b'), (10..12, 'a')].into_iter().collect();
+    /// map.coalesce();
+    /// let ranges: Vec<_> = map.iter().map(|(range, value)| (range.clone(), *value)).collect();
+    /// assert_eq!(ranges, vec![(0..9, 'a'), (3..5, 'b'), (10..12, 'a')]);
+    /// ```
+    pub fn coalesce(&mut self)
+    where
+        V: PartialEq,
+    {
+        let mut kept: Vec<(Range<K>, V)> = Vec::with_capacity(self.intervals.len());
+        // Indexes of the kept ranges that may still touch the next ranges.
+        let mut active: Vec<usize> = Vec::new();
+        for (range, value) in self.intervals.drain(..) {
+            active.retain(|idx| kept[*idx].0.end >= range.start);
+            match active.iter().find(|idx| kept[**idx].1 == value) {
+                Some(idx) => {
+                    let end = &mut kept[*idx].0.end;
+                    if range.end > *end {
+                        *end = range.end;
+                    }
+                }
+                None => {
+                    active.push(kept.len());
+                    kept.push((range, value));
+                }
+            }
+        }
+        self.intervals = kept;
+        self.max_ends.clear();
+        self.update_max_ends();
+    }
+
+    /// Remove every range.
+    pub fn clear(&mut self) {
+        self.intervals.clear();
+        self.max_ends.clear();
+    }
+
+    /// Compute the running maximum of the ends missing from `max_ends`.
+    fn update_max_ends(&mut self) {
+        for idx in self.max_ends.len()..self.intervals.len() {
+            let end = &self.intervals[idx].0.end;
+            let max_end = match self.max_ends.last() {
+                Some(max_end) if max_end > end => max_end.clone(),
+                _ => end.clone(),
+            };
+            self.max_ends.push(max_end);
+        }
+    }
+
+    /// Returns the ranges starting before `idx` whose end satisfies
+    /// `reaches`, scanning backwards until no earlier range can.
+    fn scan_back<F>(&self, idx: usize, reaches: F) -> Vec<(&Range<K>, &V)>
+    where
+        F: Fn(&K) -> bool,
+    {
+        let mut found: Vec<(&Range<K>, &V)> = (0..idx)
+            .rev()
+            .take_while(|idx| reaches(&self.max_ends[*idx]))
+            .map(|idx| &self.intervals[idx])
+            .filter(|(range, _)| reaches(&range.end))
+            .map(|(range, value)| (range, value))
+            .collect();
+        found.reverse();
+        found
+    }
+}
+
+impl<K: Ord + Clone, V> FromIterator<(Range<K>, V)> for IntervalMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (Range<K>, V)>>(iter: I) -> Self {
+        let mut map: IntervalMap<K, V> = IntervalMap::new();
+        for (range, value) in iter {
+            map.insert(range, value);
+        }
+        map
+    }
+}
+
+impl<K: Ord + Clone, V> Default for IntervalMap<K, V> {
+    fn default() -> Self {
+        IntervalMap::new()
+    }
+}
+
+/// Structure that hold possibly overlapping ranges of `K`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<K> {
+    map: IntervalMap<K, ()>,
+}
+
+impl<K: Ord + Clone> IntervalSet<K> {
+    /// Create a new empty `IntervalSet`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IntervalSet;
+    /// let set: IntervalSet<u64> = IntervalSet::new();
+    /// # assert!(set.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        IntervalSet {
+            map: IntervalMap::new(),
+        }
+    }
+
+    /// Returns the number of ranges.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the `IntervalSet` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Insert a range, unless it is empty.
+    ///
+    /// # Arguments
+    /// * range - The range inserted
+    pub fn insert(&mut self, range: Range<K>) {
+        self.map.insert(range, ());
+    }
+
+    /// Returns `true` if a range contains `point`, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * point - The point looked up
+    pub fn contains(&self, point: &K) -> bool {
+        self.map.get(point).is_some()
+    }
+
+    /// Returns the ranges containing `point`, by start.
+    ///
+    /// # Arguments
+    /// * point - The point looked up
+    pub fn containing(&self, point: &K) -> Vec<&Range<K>> {
+        self.map
+            .containing(point)
+            .into_iter()
+            .map(|(range, _)| range)
+            .collect()
+    }
+
+    /// Returns the ranges overlapping `range`, by start.
+    ///
+    /// # Arguments
+    /// * range - The range whose overlapping ranges are returned
+    pub fn overlapping(&self, range: &Range<K>) -> Vec<&Range<K>> {
+        self.map
+            .overlapping(range)
+            .into_iter()
+            .map(|(range, _)| range)
+            .collect()
+    }
+
+    /// Iterate over the ranges, by start.
+    pub fn iter(&self) -> impl Iterator<Item = &Range<K>> + '_ {
+        self.map.iter().map(|(range, _)| range)
+    }
+
+    /// Merge the ranges that overlap or are adjacent.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IntervalSet;
+    /// let mut set: IntervalSet<u32> = vec![4..6, 0..2, 1..4, 8..9].into_iter().collect();
+    /// assert_eq!(set.overlapping(&(3..5)), vec![&(1..4), &(4..6)]);
+    /// set.coalesce();
+    /// assert_eq!(set.iter().cloned().collect::<Vec<_>>(), vec![0..6, 8..9]);
+    /// ```
+    pub fn coalesce(&mut self) {
+        self.map.coalesce();
+    }
+
+    /// Remove every range.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+}
+
+impl<K: Ord + Clone> FromIterator<Range<K>> for IntervalSet<K> {
+    fn from_iter<I: IntoIterator<Item = Range<K>>>(iter: I) -> Self {
+        IntervalSet {
+            map: iter.into_iter().map(|range| (range, ())).collect(),
+        }
+    }
+}
+
+impl<K: Ord + Clone> Default for IntervalSet<K> {
+    fn default() -> Self {
+        IntervalSet::new()
+    }
+}
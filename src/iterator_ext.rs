@@ -0,0 +1,151 @@
+//! This module implements an extension trait ending iterator chains with
+//! the collectors of the crate.
+//!
+//! Besides grouping and partitioning, elements can be cut into batches of
+//! a fixed size, lazily with `batched` or all at once with `chunks_of`,
+//! the last batch holding whatever elements are left.
+//!
+use crate::{group_by, partition_n, Counter};
+use std::collections::BTreeMap;
+
+/// Trait adding collectors to every iterator
+pub trait IteratorExt: Iterator {
+    /// Group the elements by the key `key` gives them, see `group_by`.
+    ///
+    /// # Arguments
+    /// * key - A function giving the key of an element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IteratorExt;
+    /// let by_parity = (1..=6).group_into(|n| n % 2);
+    /// assert_eq!(by_parity[&0], vec![2, 4, 6]);
+    /// assert_eq!(by_parity[&1], vec![1, 3, 5]);
+    /// ```
+    fn group_into<K, F>(self, key: F) -> BTreeMap<K, Vec<Self::Item>>
+    where
+        Self: Sized,
+        K: Ord,
+        F: FnMut(&Self::Item) -> K,
+    {
+        group_by(self, key)
+    }
+
+    /// Split the elements into `N` buckets, by the index `bucket` gives
+    /// them, see `partition_n`.
+    ///
+    /// # Arguments
+    /// * bucket - A function giving the index of the bucket of an element,
+    ///   must be lower than `N`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IteratorExt;
+    /// let [small, medium, large] = vec![3, 120, 45, 2000, 7].into_iter().partition_into(|n| match n {
+    ///     0..=9 => 0,
+    ///     10..=999 => 1,
+    ///     _ => 2,
+    /// });
+    /// assert_eq!(small, vec![3, 7]);
+    /// assert_eq!(medium, vec![120, 45]);
+    /// assert_eq!(large, vec![2000]);
+    /// ```
+    fn partition_into<F, const N: usize>(self, bucket: F) -> [Vec<Self::Item>; N]
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item) -> usize,
+    {
+        partition_n(self, bucket)
+    }
+
+    /// Count the elements in a `Counter`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IteratorExt;
+    /// let tokens = "the cat and the hat".split(' ');
+    /// let counter = tokens.collect_counter();
+    /// assert_eq!(counter[&"the"], 2);
+    /// assert_eq!(counter[&"cat"], 1);
+    /// ```
+    fn collect_counter(self) -> Counter<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Ord,
+    {
+        self.collect()
+    }
+
+    /// Iterate over batches of `size` elements, the last batch being
+    /// shorter if elements are left.
+    ///
+    /// # Arguments
+    /// * size - The number of elements of a batch, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IteratorExt;
+    /// // Insert rows 2 at a time.
+    /// let mut batches = vec!["r1", "r2", "r3", "r4", "r5"].into_iter().batched(2);
+    /// assert_eq!(batches.next(), Some(vec!["r1", "r2"]));
+    /// assert_eq!(batches.next(), Some(vec!["r3", "r4"]));
+    /// assert_eq!(batches.next(), Some(vec!["r5"]));
+    /// assert_eq!(batches.next(), None);
+    /// ```
+    fn batched(self, size: usize) -> Batched<Self>
+    where
+        Self: Sized,
+    {
+        assert!(size > 0);
+        Batched { iter: self, size }
+    }
+
+    /// Collect the elements in chunks of `size` elements, the last chunk
+    /// being shorter if elements are left.
+    ///
+    /// # Arguments
+    /// * size - The number of elements of a chunk, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::IteratorExt;
+    /// let chunks = (1..=7).chunks_of(3);
+    /// assert_eq!(chunks, vec![vec![1, 2, 3], vec![4, 5, 6], vec![7]]);
+    /// assert!((1..1).chunks_of(3).is_empty());
+    /// ```
+    fn chunks_of(self, size: usize) -> Vec<Vec<Self::Item>>
+    where
+        Self: Sized,
+    {
+        self.batched(size).collect()
+    }
+}
+
+impl<I: Iterator> IteratorExt for I {}
+
+/// Iterator over the batches of elements of an iterator, see
+/// `IteratorExt::batched`
+#[derive(Debug, Clone)]
+pub struct Batched<I> {
+    iter: I,
+    size: usize,
+}
+
+impl<I: Iterator> Iterator for Batched<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch: Vec<I::Item> = self.iter.by_ref().take(self.size).collect();
+        if batch.is_empty() {
+            None
+        } else {
+            Some(batch)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let batches = |len: usize| len.div_ceil(self.size);
+        (batches(lower), upper.map(batches))
+    }
+}
@@ -0,0 +1,262 @@
+//! This module implements JSON export and import for `Counter`, without
+//! depending on any serialization framework.
+//!
+//! A `Counter` is represented as a JSON object mapping the string form of
+//! each element to its count, `{"a":5,"b":2}`.
+//!
+use crate::Counter;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter, Write};
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+
+/// Error returned when a JSON document can't be parsed into a `Counter`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseJsonError {
+    message: String,
+    position: usize,
+}
+
+impl ParseJsonError {
+    /// Returns the byte offset in the document where the error was detected.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl Display for ParseJsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl Error for ParseJsonError {}
+
+impl<T: Ord + Display> Counter<T> {
+    /// Serialize the `Counter` as a JSON object.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let mut counter: Counter<&str> = Counter::new();
+    /// counter.update_from_iter(vec!["a", "b", "a", "say \"hi\""].into_iter());
+    /// assert_eq!(
+    ///     counter.to_json_string(),
+    ///     r#"{"a":2,"b":1,"say \"hi\"":1}"#
+    /// );
+    /// ```
+    pub fn to_json_string(&self) -> String {
+        let mut json = String::from("{");
+        for (idx, (key, count)) in self.iter().enumerate() {
+            if idx > 0 {
+                json.push(',');
+            }
+            write_json_string(&mut json, &key.to_string());
+            let _ = write!(json, ":{}", count);
+        }
+        json.push('}');
+        json
+    }
+}
+
+impl<T: Ord + FromStr> Counter<T> {
+    /// Create a new `Counter` from a JSON object mapping keys to counts.
+    ///
+    /// Counts must be non negative integers. Counts of keys appearing
+    /// several times in the object are summed, failing if they overflow.
+    ///
+    /// # Arguments
+    /// * json - The JSON document
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// let counter: Counter<String> =
+    ///     Counter::from_json_str(r#" { "a": 2, "été": 1 } "#).unwrap();
+    /// assert_eq!(counter["a"], 2);
+    /// assert_eq!(counter["été"], 1);
+    ///
+    /// let counter: Counter<u32> = Counter::from_json_str(r#"{"404": 3}"#).unwrap();
+    /// assert_eq!(counter[&404], 3);
+    ///
+    /// assert!(Counter::<String>::from_json_str(r#"{"a": -1}"#).is_err());
+    /// assert!(Counter::<String>::from_json_str(r#"["a"]"#).is_err());
+    /// let overflowing = format!(r#"{{"a": {}, "a": 1}}"#, u128::MAX);
+    /// assert!(Counter::<String>::from_json_str(&overflowing).is_err());
+    /// ```
+    pub fn from_json_str(json: &str) -> Result<Self, ParseJsonError> {
+        let mut parser = Parser {
+            chars: json.char_indices().peekable(),
+            len: json.len(),
+        };
+        let mut counter: Counter<T> = Counter::new();
+
+        parser.expect('{')?;
+        if parser.peek() != Some('}') {
+            loop {
+                let position = parser.position();
+                let key = parser.string()?;
+                let key = key.parse().map_err(|_| ParseJsonError {
+                    message: format!("invalid key {:?}", key),
+                    position,
+                })?;
+                parser.expect(':')?;
+                let position = parser.position();
+                let count = parser.count()?;
+                counter
+                    .try_add_count(key, count)
+                    .map_err(|_| ParseJsonError {
+                        message: String::from("count overflow"),
+                        position,
+                    })?;
+                if parser.peek() == Some(',') {
+                    parser.expect(',')?;
+                } else {
+                    break;
+                }
+            }
+        }
+        parser.expect('}')?;
+
+        if parser.peek().is_some() {
+            return Err(parser.error("trailing characters"));
+        }
+        Ok(counter)
+    }
+}
+
+fn write_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+struct Parser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    len: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn position(&mut self) -> usize {
+        self.chars.peek().map_or(self.len, |(idx, _)| *idx)
+    }
+
+    fn error(&mut self, message: &str) -> ParseJsonError {
+        ParseJsonError {
+            message: String::from(message),
+            position: self.position(),
+        }
+    }
+
+    /// Peek the next non whitespace character.
+    fn peek(&mut self) -> Option<char> {
+        while let Some((_, c)) = self.chars.peek() {
+            if !c.is_ascii_whitespace() {
+                return Some(*c);
+            }
+            let _ = self.chars.next();
+        }
+        None
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseJsonError> {
+        if self.peek() == Some(expected) {
+            let _ = self.chars.next();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", expected)))
+        }
+    }
+
+    fn count(&mut self) -> Result<u128, ParseJsonError> {
+        let _ = self.peek();
+        let position = self.position();
+        let mut digits = String::new();
+        while let Some((_, c)) = self.chars.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            digits.push(*c);
+            let _ = self.chars.next();
+        }
+        digits.parse().map_err(|_| ParseJsonError {
+            message: String::from("expected a non negative integer count"),
+            position,
+        })
+    }
+
+    fn string(&mut self) -> Result<String, ParseJsonError> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(value),
+                Some((_, '\\')) => value.push(self.escape()?),
+                Some((_, c)) if (c as u32) < 0x20 => {
+                    return Err(self.error("unescaped control character"))
+                }
+                Some((_, c)) => value.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, ParseJsonError> {
+        let escaped = match self.chars.next() {
+            Some((_, '"')) => '"',
+            Some((_, '\\')) => '\\',
+            Some((_, '/')) => '/',
+            Some((_, 'b')) => '\u{8}',
+            Some((_, 'f')) => '\u{c}',
+            Some((_, 'n')) => '\n',
+            Some((_, 'r')) => '\r',
+            Some((_, 't')) => '\t',
+            Some((_, 'u')) => {
+                let high = self.hex_code_unit()?;
+                let code = if (0xD800..0xDC00).contains(&high) {
+                    if self.chars.next().map(|(_, c)| c) != Some('\\')
+                        || self.chars.next().map(|(_, c)| c) != Some('u')
+                    {
+                        return Err(self.error("expected a low surrogate"));
+                    }
+                    let low = self.hex_code_unit()?;
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return Err(self.error("invalid low surrogate"));
+                    }
+                    0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                } else {
+                    high
+                };
+                match char::from_u32(code) {
+                    Some(c) => c,
+                    None => return Err(self.error("invalid unicode escape")),
+                }
+            }
+            _ => return Err(self.error("invalid escape")),
+        };
+        Ok(escaped)
+    }
+
+    fn hex_code_unit(&mut self) -> Result<u32, ParseJsonError> {
+        let mut code: u32 = 0;
+        for _ in 0..4 {
+            match self.chars.next().and_then(|(_, c)| c.to_digit(16)) {
+                Some(digit) => code = code * 16 + digit,
+                None => return Err(self.error("invalid unicode escape")),
+            }
+        }
+        Ok(code)
+    }
+}
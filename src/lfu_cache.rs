@@ -0,0 +1,232 @@
+//! This module implements a cache evicting the least frequently used entry.
+//!
+//! Every `get` and `put` of a key is counted by a `Counter`, and entries are
+//! indexed by their frequency then their last use, so the evicted entry is
+//! the least frequently used one, the least recently used among ties. The
+//! frequency of an evicted key is forgotten.
+//!
+use crate::Counter;
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Structure that cache at most `capacity` `V` values by `K` key
+#[derive(Debug, Clone)]
+pub struct LfuCache<K, V> {
+    entries: BTreeMap<K, (V, u64)>,
+    frequencies: Counter<K>,
+    order: BTreeSet<(u128, u64, K)>,
+    capacity: usize,
+    clock: u64,
+}
+
+impl<K: Ord + Clone, V> LfuCache<K, V> {
+    /// Create a new empty `LfuCache`.
+    ///
+    /// # Arguments
+    /// * capacity - The maximum number of entries, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::LfuCache;
+    /// let cache: LfuCache<String, Vec<u8>> = LfuCache::new(128);
+    /// # assert_eq!(cache.capacity(), 128);
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        LfuCache {
+            entries: BTreeMap::new(),
+            frequencies: Counter::new(),
+            order: BTreeSet::new(),
+            capacity,
+            clock: 0,
+        }
+    }
+
+    /// Returns the maximum number of entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the `LfuCache` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of times a cached key was used, 0 if it is absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn frequency_of<Q>(&self, key: &Q) -> u128
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.frequencies[key]
+    }
+
+    /// Returns the use counts of the cached keys.
+    pub fn frequencies(&self) -> &Counter<K> {
+        &self.frequencies
+    }
+
+    /// Returns the value of a key and counts a use of it, `None` if it is
+    /// absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.touch(key)?;
+        self.entries.get(key).map(|(value, _)| value)
+    }
+
+    /// Returns a mutable reference to the value of a key and counts a use
+    /// of it, `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.touch(key)?;
+        self.entries.get_mut(key).map(|(value, _)| value)
+    }
+
+    /// Returns the value of a key without counting a use, `None` if it is
+    /// absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entries.get(key).map(|(value, _)| value)
+    }
+
+    /// Returns `true` if the key is cached, `false` otherwise, without
+    /// counting a use.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entries.contains_key(key)
+    }
+
+    /// Cache a value and count a use of its key, replacing the value of the
+    /// key if it is already cached.
+    ///
+    /// Returns the least frequently used entry if it was evicted to make
+    /// room.
+    ///
+    /// # Arguments
+    /// * key - The key of the value
+    /// * value - The value cached
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::LfuCache;
+    /// let mut cache: LfuCache<&str, u32> = LfuCache::new(2);
+    /// assert_eq!(cache.put("a", 1), None);
+    /// assert_eq!(cache.put("b", 2), None);
+    /// assert_eq!(cache.get(&"a"), Some(&1));
+    /// assert_eq!(cache.get(&"a"), Some(&1));
+    /// assert_eq!(cache.frequency_of(&"a"), 3);
+    /// // "b" was used once, less than "a".
+    /// assert_eq!(cache.put("c", 3), Some(("b", 2)));
+    /// assert_eq!(cache.frequency_of(&"b"), 0);
+    /// ```
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.0 = value;
+            let _ = self.touch(&key);
+            return None;
+        }
+        let evicted = if self.entries.len() == self.capacity {
+            self.pop_lfu()
+        } else {
+            None
+        };
+        let _ = self.entries.insert(key.clone(), (value, self.clock));
+        self.frequencies.update_from_value(key.clone());
+        let _ = self.order.insert((self.frequencies[&key], self.clock, key));
+        self.clock += 1;
+        evicted
+    }
+
+    /// Remove a key and forget its frequency, returning its value if it was
+    /// cached.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (key, (value, last_used)) = self.entries.remove_entry(key)?;
+        let frequency = self.frequencies.remove::<K>(&key);
+        let _ = self.order.remove(&(frequency, last_used, key));
+        Some(value)
+    }
+
+    /// Remove the least frequently used entry, the least recently used
+    /// among ties, returning it.
+    pub fn pop_lfu(&mut self) -> Option<(K, V)> {
+        let (_, _, key) = self.order.pop_first()?;
+        let _ = self.frequencies.remove(&key);
+        let (value, _) = self
+            .entries
+            .remove(&key)
+            .expect("every ordered key has an entry");
+        Some((key, value))
+    }
+
+    /// Remove every entry and forget every frequency.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.frequencies = Counter::new();
+        self.order.clear();
+    }
+
+    /// Count a use of a cached key, `None` if it is absent.
+    fn touch<Q>(&mut self, key: &Q) -> Option<()>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let key = self.entries.get_key_value(key)?.0.clone();
+        self.count_use(key);
+        Some(())
+    }
+
+    /// Count a use of a cached key, moving it in the eviction order.
+    fn count_use(&mut self, key: K) {
+        let last_used = &mut self
+            .entries
+            .get_mut(&key)
+            .expect("only cached keys are used")
+            .1;
+        let frequency = self.frequencies[&key];
+        let _ = self.order.remove(&(frequency, *last_used, key.clone()));
+        *last_used = self.clock;
+        self.frequencies.update_from_value(key.clone());
+        let _ = self.order.insert((self.frequencies[&key], self.clock, key));
+        self.clock += 1;
+    }
+}
@@ -1,5 +1,5 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(bad_style)]
-#![deny(const_err)]
 #![deny(dead_code)]
 #![deny(improper_ctypes)]
 // #![deny(missing_docs)]
@@ -10,7 +10,6 @@
 #![deny(overflowing_literals)]
 #![deny(path_statements)]
 #![deny(patterns_in_fns_without_body)]
-#![deny(private_in_public)]
 #![deny(trivial_casts)]
 #![deny(trivial_numeric_casts)]
 #![deny(unconditional_recursion)]
@@ -24,8 +23,264 @@
 #![deny(unused_results)]
 #![deny(while_true)]
 
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod accumulate;
+#[cfg(feature = "std")]
+mod atomic_counter;
+#[cfg(feature = "std")]
+mod binary;
+#[cfg(feature = "std")]
+mod bit_set;
+#[cfg(feature = "std")]
 mod bits;
+#[cfg(feature = "std")]
+mod bounded_counter;
+#[cfg(feature = "std")]
+mod bucketed_counter;
+#[cfg(feature = "std")]
+mod chain_map;
+#[cfg(feature = "std")]
+mod compressed_bit_set;
+#[cfg(feature = "std")]
+mod concurrent_counter;
+#[cfg(feature = "std")]
+mod confusion_matrix;
+#[cfg(feature = "std")]
+mod count_sketch;
 mod counter;
+#[cfg(feature = "std")]
+mod csv;
+#[cfg(feature = "std")]
+mod decaying_counter;
+#[cfg(feature = "std")]
+mod distinct;
+#[cfg(feature = "std")]
+mod external_counter;
+#[cfg(feature = "std")]
+mod fenwick_tree;
+#[cfg(feature = "std")]
+mod frequency_table;
+#[cfg(feature = "std")]
+mod frozen_map;
+#[cfg(feature = "std")]
+mod grid;
+#[cfg(feature = "std")]
+mod group_by;
+#[cfg(feature = "std")]
+mod grouped_counter;
+#[cfg(feature = "std")]
+mod hdr_histogram;
+#[cfg(feature = "std")]
+mod heap_size;
+#[cfg(feature = "std")]
+mod heapq;
+#[cfg(feature = "std")]
+mod heavy_hitters;
+#[cfg(feature = "std")]
+mod index_map;
+#[cfg(feature = "std")]
+mod interval_map;
+#[cfg(feature = "std")]
+mod iterator_ext;
+#[cfg(feature = "std")]
+mod json;
+#[cfg(feature = "std")]
+mod lfu_cache;
+#[cfg(feature = "std")]
+mod lru_cache;
+#[cfg(feature = "std")]
+mod min_hash;
+#[cfg(feature = "std")]
+mod min_max_heap;
+#[cfg(feature = "std")]
+mod multi_map;
+#[cfg(feature = "std")]
+mod order_statistic_map;
+#[cfg(feature = "std")]
+mod ordered_dict;
+#[cfg(feature = "std")]
+mod parallel;
+#[cfg(feature = "std")]
+mod partition;
+#[cfg(feature = "std")]
+mod persistent_map;
+#[cfg(feature = "std")]
+mod priority_queue;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "std")]
+mod quantile_sketch;
+#[cfg(feature = "std")]
+mod radix_map;
+#[cfg(feature = "std")]
+mod range_set;
+#[cfg(feature = "std")]
+mod reservoir_sampler;
+#[cfg(feature = "std")]
+mod rle_vec;
+#[cfg(feature = "std")]
+mod rolling_window;
+#[cfg(feature = "std")]
+mod segment_tree;
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "std")]
+mod signed_counter;
+#[cfg(feature = "std")]
+mod skip_list_map;
+#[cfg(feature = "std")]
+mod slab;
+#[cfg(feature = "std")]
+mod small_counter;
+#[cfg(feature = "std")]
+mod sorted_multi_set;
+#[cfg(feature = "std")]
+mod sorted_vec;
+#[cfg(feature = "std")]
+mod sparse_bit_set;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
+mod top_k;
+#[cfg(feature = "std")]
+mod tracked_counter;
+#[cfg(feature = "std")]
+mod transition_counter;
+#[cfg(feature = "std")]
+mod trie;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "std")]
+mod watched_counter;
+#[cfg(feature = "std")]
+mod weighted_alias;
+#[cfg(feature = "std")]
+mod window_extrema;
 
+#[cfg(feature = "std")]
+pub use accumulate::accumulate;
+#[cfg(feature = "std")]
+pub use atomic_counter::AtomicCounter;
+#[cfg(feature = "std")]
+pub use bit_set::BitSet;
+#[cfg(feature = "std")]
 pub use bits::Bits;
-pub use counter::Counter;
+#[cfg(feature = "std")]
+pub use bounded_counter::BoundedCounter;
+#[cfg(feature = "std")]
+pub use bucketed_counter::BucketedCounter;
+#[cfg(feature = "std")]
+pub use chain_map::ChainMap;
+#[cfg(feature = "std")]
+pub use compressed_bit_set::CompressedBitSet;
+#[cfg(feature = "std")]
+pub use concurrent_counter::ConcurrentCounter;
+#[cfg(feature = "std")]
+pub use confusion_matrix::ConfusionMatrix;
+#[cfg(feature = "std")]
+pub use count_sketch::CountSketch;
+pub use counter::{
+    CountOverflowError, Counter, CounterDiff, OverflowPolicy, SortOrder, TextOptions,
+};
+#[cfg(feature = "std")]
+pub use decaying_counter::{DecayClock, DecayingCounter};
+#[cfg(feature = "std")]
+pub use distinct::{unique_ordered, DistinctOrdered};
+#[cfg(feature = "std")]
+pub use external_counter::{ExternalCounter, ExternalCounts};
+#[cfg(feature = "std")]
+pub use fenwick_tree::FenwickTree;
+#[cfg(feature = "std")]
+pub use frequency_table::FrequencyTable;
+#[cfg(feature = "std")]
+pub use frozen_map::FrozenMap;
+#[cfg(feature = "std")]
+pub use grid::Grid;
+#[cfg(feature = "std")]
+pub use group_by::{group_by, GroupBy};
+#[cfg(feature = "std")]
+pub use grouped_counter::GroupedCounter;
+#[cfg(feature = "std")]
+pub use hdr_histogram::{HdrHistogram, ValueOutOfRangeError};
+#[cfg(feature = "std")]
+pub use heap_size::HeapSize;
+#[cfg(feature = "std")]
+pub use heapq::{nlargest, nsmallest};
+#[cfg(feature = "std")]
+pub use heavy_hitters::HeavyHitters;
+#[cfg(feature = "std")]
+pub use index_map::{IndexMap, IndexSet};
+#[cfg(feature = "std")]
+pub use interval_map::{IntervalMap, IntervalSet};
+#[cfg(feature = "std")]
+pub use iterator_ext::{Batched, IteratorExt};
+#[cfg(feature = "std")]
+pub use json::ParseJsonError;
+#[cfg(feature = "std")]
+pub use lfu_cache::LfuCache;
+#[cfg(feature = "std")]
+pub use lru_cache::LruCache;
+#[cfg(feature = "std")]
+pub use min_hash::MinHash;
+#[cfg(feature = "std")]
+pub use min_max_heap::MinMaxHeap;
+#[cfg(feature = "std")]
+pub use multi_map::MultiMap;
+#[cfg(feature = "std")]
+pub use order_statistic_map::OrderStatisticMap;
+#[cfg(feature = "std")]
+pub use ordered_dict::OrderedDict;
+#[cfg(feature = "std")]
+pub use partition::partition_n;
+#[cfg(feature = "std")]
+pub use persistent_map::PersistentMap;
+#[cfg(feature = "std")]
+pub use priority_queue::PriorityQueue;
+#[cfg(feature = "std")]
+pub use quantile_sketch::QuantileSketch;
+#[cfg(feature = "std")]
+pub use radix_map::RadixMap;
+#[cfg(feature = "std")]
+pub use range_set::RangeSet;
+#[cfg(feature = "std")]
+pub use reservoir_sampler::ReservoirSampler;
+#[cfg(feature = "std")]
+pub use rle_vec::RleVec;
+#[cfg(feature = "std")]
+pub use rolling_window::RollingWindow;
+#[cfg(feature = "std")]
+pub use segment_tree::{MaxOp, MinOp, Monoid, SegmentTree, SumOp};
+#[cfg(feature = "std")]
+pub use shared::{Shared, SharedBits, SharedCounter};
+#[cfg(feature = "std")]
+pub use signed_counter::SignedCounter;
+#[cfg(feature = "std")]
+pub use skip_list_map::SkipListMap;
+#[cfg(feature = "std")]
+pub use slab::Slab;
+#[cfg(feature = "std")]
+pub use small_counter::SmallCounter;
+#[cfg(feature = "std")]
+pub use sorted_multi_set::SortedMultiSet;
+#[cfg(feature = "std")]
+pub use sorted_vec::SortedVec;
+#[cfg(feature = "std")]
+pub use sparse_bit_set::SparseBitSet;
+#[cfg(feature = "std")]
+pub use stats::Stats;
+#[cfg(feature = "std")]
+pub use top_k::TopK;
+#[cfg(feature = "std")]
+pub use tracked_counter::{TrackedCounter, Tracking};
+#[cfg(feature = "std")]
+pub use transition_counter::TransitionCounter;
+#[cfg(feature = "std")]
+pub use trie::{CountingTrie, Trie};
+#[cfg(feature = "std")]
+pub use watched_counter::WatchedCounter;
+#[cfg(feature = "std")]
+pub use weighted_alias::WeightedAlias;
+#[cfg(feature = "std")]
+pub use window_extrema::WindowExtrema;
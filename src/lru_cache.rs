@@ -0,0 +1,171 @@
+//! This module implements a cache evicting the least recently used entry.
+//!
+//! Entries are kept in an `OrderedDict` from the least to the most recently
+//! used, so a lookup moves its entry last and an eviction pops the first.
+//!
+use crate::OrderedDict;
+use std::borrow::Borrow;
+
+/// Structure that cache at most `capacity` `V` values by `K` key
+#[derive(Debug, Clone)]
+pub struct LruCache<K, V> {
+    entries: OrderedDict<K, V>,
+    capacity: usize,
+}
+
+impl<K: Ord + Clone, V> LruCache<K, V> {
+    /// Create a new empty `LruCache`.
+    ///
+    /// # Arguments
+    /// * capacity - The maximum number of entries, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::LruCache;
+    /// let cache: LruCache<String, Vec<u8>> = LruCache::new(128);
+    /// # assert_eq!(cache.capacity(), 128);
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        LruCache {
+            entries: OrderedDict::new(),
+            capacity,
+        }
+    }
+
+    /// Returns the maximum number of entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the `LruCache` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the value of a key and marks it as the most recently used,
+    /// `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        if !self.entries.move_to_end(key, true) {
+            return None;
+        }
+        self.entries.get(key)
+    }
+
+    /// Returns a mutable reference to the value of a key and marks it as the
+    /// most recently used, `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        if !self.entries.move_to_end(key, true) {
+            return None;
+        }
+        self.entries.get_mut(key)
+    }
+
+    /// Returns the value of a key without changing its recency, `None` if
+    /// it is absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entries.get(key)
+    }
+
+    /// Returns `true` if the key is cached, `false` otherwise, without
+    /// changing its recency.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entries.contains_key(key)
+    }
+
+    /// Cache a value as the most recently used, replacing the value of the
+    /// key if it is already cached.
+    ///
+    /// Returns the least recently used entry if it was evicted to make room.
+    ///
+    /// # Arguments
+    /// * key - The key of the value
+    /// * value - The value cached
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::LruCache;
+    /// let mut cache: LruCache<&str, u32> = LruCache::new(2);
+    /// assert_eq!(cache.put("a", 1), None);
+    /// assert_eq!(cache.put("b", 2), None);
+    /// assert_eq!(cache.get(&"a"), Some(&1));
+    /// // "b" is now the least recently used.
+    /// assert_eq!(cache.put("c", 3), Some(("b", 2)));
+    /// assert_eq!(cache.iter().collect::<Vec<_>>(), vec![(&"c", &3), (&"a", &1)]);
+    /// ```
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if self.entries.contains_key(&key) {
+            let _ = self.entries.move_to_end(&key, true);
+            let _ = self.entries.insert(key, value);
+            return None;
+        }
+        let evicted = if self.entries.len() == self.capacity {
+            self.entries.pop_first()
+        } else {
+            None
+        };
+        let _ = self.entries.insert(key, value);
+        evicted
+    }
+
+    /// Remove a key, returning its value if it was cached.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entries.remove(key)
+    }
+
+    /// Remove the least recently used entry, returning it.
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        self.entries.pop_first()
+    }
+
+    /// Remove every entry.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Iterate over the entries from the most to the least recently used,
+    /// without changing their recency.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.entries.iter().rev()
+    }
+}
@@ -0,0 +1,173 @@
+//! This module implements MinHash signatures, estimating the similarity of
+//! sets from a fixed amount of memory.
+//!
+//! Each of the `k` values of a signature is the smallest hash of the items
+//! seen, under its own hash function. Two sets get the same value for a
+//! hash function with probability equal to their Jaccard similarity, the
+//! size of their intersection over the size of their union, so the share
+//! of equal values estimates it with a standard error of `1 / sqrt(k)`.
+//! Items seen several times count once, as in a set.
+//!
+use crate::Counter;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Structure that summarize a set of items as a MinHash signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinHash {
+    signature: Vec<u64>,
+}
+
+impl MinHash {
+    /// Create a new empty `MinHash` with `num_hashes` hash functions.
+    ///
+    /// # Arguments
+    /// * num_hashes - The size of the signature, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::MinHash;
+    /// let sketch = MinHash::new(128);
+    /// # assert!(sketch.is_empty());
+    /// ```
+    pub fn new(num_hashes: usize) -> Self {
+        assert!(num_hashes > 0);
+        MinHash {
+            signature: vec![u64::MAX; num_hashes],
+        }
+    }
+
+    /// Returns the number of hash functions.
+    pub fn num_hashes(&self) -> usize {
+        self.signature.len()
+    }
+
+    /// Returns `true` if no item was seen, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.signature.iter().all(|min| *min == u64::MAX)
+    }
+
+    /// Add an item to the set.
+    ///
+    /// # Arguments
+    /// * item - The item added
+    pub fn update<T: Hash + ?Sized>(&mut self, item: &T) {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        let hash = hasher.finish();
+        for (idx, min) in self.signature.iter_mut().enumerate() {
+            let hash = mix(hash ^ mix(idx as u64));
+            if hash < *min {
+                *min = hash;
+            }
+        }
+    }
+
+    /// Add every item of an iterator to the set.
+    ///
+    /// # Arguments
+    /// * iter - An iterator over the items
+    pub fn update_from_iter<I>(&mut self, iter: I)
+    where
+        I: IntoIterator,
+        I::Item: Hash,
+    {
+        for item in iter {
+            self.update(&item);
+        }
+    }
+
+    /// Add the elements of a `Counter` whose count is not 0 to the set.
+    ///
+    /// # Arguments
+    /// * counter - The `Counter` whose elements are added
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, MinHash};
+    /// let words: Counter<&str> = "the quick brown fox".split(' ').collect();
+    /// let mut from_counter = MinHash::new(64);
+    /// from_counter.update_from_counter(&words);
+    /// let mut from_words = MinHash::new(64);
+    /// from_words.update_from_iter("the quick brown fox the".split(' '));
+    /// assert_eq!(from_counter, from_words);
+    /// ```
+    pub fn update_from_counter<T: Hash>(&mut self, counter: &Counter<T>) {
+        for item in counter.unique() {
+            self.update(item);
+        }
+    }
+
+    /// Returns the signature, the smallest hash under every hash function.
+    pub fn signature(&self) -> &[u64] {
+        &self.signature
+    }
+
+    /// Returns the estimated Jaccard similarity of the sets, from 0 for
+    /// disjoint sets to 1 for equal ones.
+    ///
+    /// # Arguments
+    /// * other - The `MinHash` of the other set, of the same size
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::MinHash;
+    /// let shingles = |text: &str| {
+    ///     let words: Vec<&str> = text.split(' ').collect();
+    ///     let mut sketch = MinHash::new(256);
+    ///     sketch.update_from_iter(words.windows(2));
+    ///     sketch
+    /// };
+    /// let original = shingles("a b c d e f g h i j k l m n o p q r s t");
+    /// let near_copy = shingles("a b c d e f g h i j k l m n o p q r s x");
+    /// let unrelated = shingles("u v w x y z");
+    /// assert!(original.jaccard_estimate(&near_copy) > 0.75);
+    /// assert!(original.jaccard_estimate(&unrelated) < 0.1);
+    /// ```
+    pub fn jaccard_estimate(&self, other: &MinHash) -> f64 {
+        assert_eq!(self.signature.len(), other.signature.len());
+        let equal = self
+            .signature
+            .iter()
+            .zip(other.signature.iter())
+            .filter(|(a, b)| a == b)
+            .count();
+        equal as f64 / self.signature.len() as f64
+    }
+
+    /// Add the items of another set, the signature becoming the one of the
+    /// union of the sets.
+    ///
+    /// # Arguments
+    /// * other - The `MinHash` of the other set, of the same size
+    pub fn merge(&mut self, other: &MinHash) {
+        assert_eq!(self.signature.len(), other.signature.len());
+        for (min, other_min) in self.signature.iter_mut().zip(other.signature.iter()) {
+            *min = (*min).min(*other_min);
+        }
+    }
+
+    /// Remove every item.
+    pub fn clear(&mut self) {
+        for min in self.signature.iter_mut() {
+            *min = u64::MAX;
+        }
+    }
+}
+
+/// Advance a xorshift generator, returning its new state, which must not
+/// be 0.
+pub(crate) fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// Scramble the bits of a value, the finalizer of SplitMix64.
+pub(crate) fn mix(mut value: u64) -> u64 {
+    value = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+}
@@ -0,0 +1,249 @@
+//! This module implements a double ended priority queue as an interval
+//! heap.
+//!
+//! Every node of the binary tree holds two elements, the lower one being
+//! part of a min heap and the upper one of a max heap, and the interval of
+//! a node contains the intervals of its children. Both the smallest and
+//! the largest elements sit at the root, so either end can be popped in
+//! O(log n) from a single heap. Keeping the `k` largest elements of a
+//! stream, or the two halves around a median, then needs one structure.
+//!
+use std::iter::FromIterator;
+
+/// Structure that hold `T` elements ordered from both ends
+#[derive(Debug, Clone)]
+pub struct MinMaxHeap<T> {
+    data: Vec<T>,
+}
+
+impl<T: Ord> MinMaxHeap<T> {
+    /// Create a new empty `MinMaxHeap`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::MinMaxHeap;
+    /// let heap: MinMaxHeap<u32> = MinMaxHeap::new();
+    /// # assert!(heap.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        MinMaxHeap { data: Vec::new() }
+    }
+
+    /// Create a new empty `MinMaxHeap` able to hold `capacity` elements
+    /// without reallocating.
+    ///
+    /// # Arguments
+    /// * capacity - The number of elements to reserve room for
+    pub fn with_capacity(capacity: usize) -> Self {
+        MinMaxHeap {
+            data: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the `MinMaxHeap` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Add an element.
+    ///
+    /// # Arguments
+    /// * elem - The element added
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::MinMaxHeap;
+    /// // Keep the 3 largest elements of a stream.
+    /// let mut top: MinMaxHeap<u32> = MinMaxHeap::new();
+    /// for elem in vec![5, 1, 9, 3, 7, 2] {
+    ///     top.push(elem);
+    ///     if top.len() > 3 {
+    ///         let _ = top.pop_min();
+    ///     }
+    /// }
+    /// assert_eq!(top.peek_min(), Some(&5));
+    /// assert_eq!(top.pop_max(), Some(9));
+    /// assert_eq!(top.pop_max(), Some(7));
+    /// ```
+    pub fn push(&mut self, elem: T) {
+        self.data.push(elem);
+        let pos = self.data.len() - 1;
+        let node = pos / 2;
+        if pos % 2 == 1 && self.data[pos - 1] > self.data[pos] {
+            self.data.swap(pos - 1, pos);
+        }
+        if node == 0 {
+            return;
+        }
+        let parent = (node - 1) / 2;
+        if self.data[2 * node] < self.data[2 * parent] {
+            self.sift_up_min(2 * node);
+        } else if self.data[pos] > self.data[2 * parent + 1] {
+            // When the node is a single element, that element is the upper one.
+            self.sift_up_max(pos);
+        }
+    }
+
+    /// Returns the smallest element, `None` if empty.
+    pub fn peek_min(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Returns the largest element, `None` if empty.
+    pub fn peek_max(&self) -> Option<&T> {
+        self.data.get(1).or_else(|| self.data.first())
+    }
+
+    /// Remove the smallest element, returning it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::MinMaxHeap;
+    /// let mut heap: MinMaxHeap<char> = "heap".chars().collect();
+    /// assert_eq!(heap.pop_min(), Some('a'));
+    /// assert_eq!(heap.pop_max(), Some('p'));
+    /// assert_eq!(heap.pop_min(), Some('e'));
+    /// assert_eq!(heap.pop_max(), Some('h'));
+    /// assert_eq!(heap.pop_min(), None);
+    /// ```
+    pub fn pop_min(&mut self) -> Option<T> {
+        if self.data.len() <= 1 {
+            return self.data.pop();
+        }
+        let min = self.data.swap_remove(0);
+        self.sift_down_min(0);
+        Some(min)
+    }
+
+    /// Remove the largest element, returning it.
+    pub fn pop_max(&mut self) -> Option<T> {
+        if self.data.len() <= 2 {
+            return self.data.pop();
+        }
+        let max = self.data.swap_remove(1);
+        self.sift_down_max(1);
+        Some(max)
+    }
+
+    /// Iterate over the elements, in no particular order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    /// Convert into the elements, in increasing order.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        self.data.sort();
+        self.data
+    }
+
+    /// Remove every element.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    fn sift_up_min(&mut self, mut pos: usize) {
+        while pos >= 2 {
+            let parent = 2 * ((pos / 2 - 1) / 2);
+            if self.data[pos] >= self.data[parent] {
+                break;
+            }
+            self.data.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    fn sift_up_max(&mut self, mut pos: usize) {
+        while pos >= 2 {
+            let parent = 2 * ((pos / 2 - 1) / 2) + 1;
+            if self.data[pos] <= self.data[parent] {
+                break;
+            }
+            self.data.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    fn sift_down_min(&mut self, mut pos: usize) {
+        let len = self.data.len();
+        loop {
+            // The element moved down may exceed the upper one of its node.
+            if pos + 1 < len && self.data[pos] > self.data[pos + 1] {
+                self.data.swap(pos, pos + 1);
+            }
+            let node = pos / 2;
+            let child = [2 * (2 * node + 1), 2 * (2 * node + 2)]
+                .iter()
+                .cloned()
+                .filter(|child| *child < len)
+                .min_by(|a, b| self.data[*a].cmp(&self.data[*b]));
+            match child {
+                Some(child) if self.data[child] < self.data[pos] => {
+                    self.data.swap(pos, child);
+                    pos = child;
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn sift_down_max(&mut self, mut pos: usize) {
+        let len = self.data.len();
+        loop {
+            // The element moved down may be below the lower one of its node.
+            if self.data[pos - 1] > self.data[pos] {
+                self.data.swap(pos - 1, pos);
+            }
+            let node = pos / 2;
+            let child = [2 * node + 1, 2 * node + 2]
+                .iter()
+                .filter_map(|child| {
+                    // The upper element of a single element node is itself.
+                    if 2 * child + 1 < len {
+                        Some(2 * child + 1)
+                    } else if 2 * child < len {
+                        Some(2 * child)
+                    } else {
+                        None
+                    }
+                })
+                .max_by(|a, b| self.data[*a].cmp(&self.data[*b]));
+            match child {
+                Some(child) if self.data[child] > self.data[pos] => {
+                    self.data.swap(pos, child);
+                    if child % 2 == 0 {
+                        break;
+                    }
+                    pos = child;
+                }
+                _ => break,
+            }
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for MinMaxHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap: MinMaxHeap<T> = MinMaxHeap::new();
+        heap.extend(iter);
+        heap
+    }
+}
+
+impl<T: Ord> Extend<T> for MinMaxHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<T: Ord> Default for MinMaxHeap<T> {
+    fn default() -> Self {
+        MinMaxHeap::new()
+    }
+}
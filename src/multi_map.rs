@@ -0,0 +1,254 @@
+//! This module implements a map holding several values per key.
+//!
+//! Values of a key are kept in insertion order, and a key is removed as
+//! soon as its last value is, so a `MultiMap` never holds empty groups.
+//!
+use crate::Counter;
+use std::borrow::Borrow;
+use std::collections::btree_map::{BTreeMap, Iter};
+use std::convert::TryFrom;
+use std::iter::FromIterator;
+
+/// Structure that map `K` keys to any number of `V` values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiMap<K, V> {
+    groups: BTreeMap<K, Vec<V>>,
+    len: usize,
+}
+
+impl<K: Ord, V> MultiMap<K, V> {
+    /// Create a new empty `MultiMap`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::MultiMap;
+    /// let map: MultiMap<&str, u32> = MultiMap::new();
+    /// # assert!(map.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        MultiMap {
+            groups: BTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of values, across all keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of keys.
+    pub fn keys_len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Returns `true` if the `MultiMap` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Add a value to the values of a key.
+    ///
+    /// # Arguments
+    /// * key - The key of the value
+    /// * value - The value added last to the values of the key
+    pub fn insert(&mut self, key: K, value: V) {
+        self.groups.entry(key).or_default().push(value);
+        self.len += 1;
+    }
+
+    /// Returns the first value of a key, `None` if it has none.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_all(key).first()
+    }
+
+    /// Returns the values of a key in insertion order, an empty slice if it
+    /// has none.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::MultiMap;
+    /// let owners: MultiMap<&str, &str> =
+    ///     vec![("src", "alice"), ("docs", "bob"), ("src", "carol")].into_iter().collect();
+    /// assert_eq!(owners.get_all(&"src"), &["alice", "carol"]);
+    /// assert!(owners.get_all(&"tests").is_empty());
+    /// ```
+    pub fn get_all<Q>(&self, key: &Q) -> &[V]
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.groups.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns `true` if the key has at least one value, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.groups.contains_key(key)
+    }
+
+    /// Remove the first occurence of a value from the values of a key,
+    /// removing the key along with its last value.
+    ///
+    /// Returns `true` if the value was found, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    /// * value - The value removed
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::MultiMap;
+    /// let mut tags: MultiMap<u32, &str> = MultiMap::new();
+    /// tags.insert(1, "bug");
+    /// tags.insert(1, "bug");
+    /// assert!(tags.remove_one(&1, &"bug"));
+    /// assert_eq!(tags.get_all(&1), &["bug"]);
+    /// assert!(tags.remove_one(&1, &"bug"));
+    /// assert!(!tags.contains_key(&1));
+    /// assert!(!tags.remove_one(&1, &"bug"));
+    /// ```
+    pub fn remove_one<Q>(&mut self, key: &Q, value: &V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        V: PartialEq,
+    {
+        let values = match self.groups.get_mut(key) {
+            Some(values) => values,
+            None => return false,
+        };
+        let idx = match values.iter().position(|other| other == value) {
+            Some(idx) => idx,
+            None => return false,
+        };
+        let _ = values.remove(idx);
+        if values.is_empty() {
+            let _ = self.groups.remove(key);
+        }
+        self.len -= 1;
+        true
+    }
+
+    /// Remove a key, returning its values in insertion order.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn remove_all<Q>(&mut self, key: &Q) -> Vec<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let values = self.groups.remove(key).unwrap_or_default();
+        self.len -= values.len();
+        values
+    }
+
+    /// Iterate over the keys and their values, in key order.
+    pub fn groups(&self) -> Iter<'_, K, Vec<V>> {
+        self.groups.iter()
+    }
+
+    /// Iterate over every `(key, value)` pair, in key order then insertion
+    /// order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.groups
+            .iter()
+            .flat_map(|(key, values)| values.iter().map(move |value| (key, value)))
+    }
+
+    /// Returns the number of values of every key.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::MultiMap;
+    /// let owners: MultiMap<&str, &str> =
+    ///     vec![("src", "alice"), ("docs", "bob"), ("src", "carol")].into_iter().collect();
+    /// let counts = owners.to_counter();
+    /// assert_eq!(counts[&"src"], 2);
+    /// assert_eq!(counts[&"docs"], 1);
+    /// ```
+    pub fn to_counter(&self) -> Counter<K>
+    where
+        K: Clone,
+    {
+        let mut counter: Counter<K> = Counter::new();
+        counter.extend(
+            self.groups
+                .iter()
+                .map(|(key, values)| (key.clone(), values.len() as u128)),
+        );
+        counter
+    }
+
+    /// Create a new `MultiMap` giving every element of a `Counter` as many
+    /// copies of `value` as its count.
+    ///
+    /// # Panics
+    /// Panics if a count doesn't fit in a `usize`.
+    ///
+    /// # Arguments
+    /// * counter - The `Counter` whose elements are the keys
+    /// * value - The value repeated for every occurence of an element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, MultiMap};
+    /// let counter: Counter<char> = "aab".chars().collect();
+    /// let map = MultiMap::from_counter(&counter, ());
+    /// assert_eq!(map.get_all(&'a').len(), 2);
+    /// assert_eq!(map.to_counter(), counter);
+    /// ```
+    pub fn from_counter(counter: &Counter<K>, value: V) -> Self
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let mut map: MultiMap<K, V> = MultiMap::new();
+        for (key, count) in counter.iter().filter(|(_, count)| **count > 0) {
+            let count = usize::try_from(*count).expect("count exceeds usize");
+            let values = vec![value.clone(); count];
+            map.len += values.len();
+            let _ = map.groups.insert(key.clone(), values);
+        }
+        map
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for MultiMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map: MultiMap<K, V> = MultiMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for MultiMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Ord, V> Default for MultiMap<K, V> {
+    fn default() -> Self {
+        MultiMap::new()
+    }
+}
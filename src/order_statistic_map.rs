@@ -0,0 +1,424 @@
+//! This module implements an ordered map answering rank and selection
+//! queries, as a treap augmented with subtree sizes.
+//!
+//! Nodes are ordered by key as in a binary search tree and by a random
+//! priority as in a heap, which keeps the tree balanced in expectation.
+//! Every node also records the size of its subtree, so the rank of a key,
+//! the number of smaller keys, and the `k`-th smallest key are found by a
+//! single walk down the tree in O(log n) expected time. The median of the
+//! keys inserted so far is then read online, without sorting. Insertion
+//! and removal split the tree at the rank of a key and merge the parts
+//! back by priority, which only updates the sizes of the nodes along the
+//! split, and the slots of removed nodes are reused by later insertions.
+//!
+use crate::min_hash::xorshift;
+use std::borrow::Borrow;
+use std::iter::FromIterator;
+use std::ops::Index;
+
+#[derive(Debug, Clone)]
+struct Node<K, V> {
+    key: K,
+    value: V,
+    priority: u64,
+    size: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+/// Structure that map `K` keys to `V` values, answering rank queries
+#[derive(Debug, Clone)]
+pub struct OrderStatisticMap<K, V> {
+    nodes: Vec<Option<Node<K, V>>>,
+    free: Vec<usize>,
+    root: Option<usize>,
+    seed: u64,
+}
+
+impl<K: Ord, V> OrderStatisticMap<K, V> {
+    /// Create a new empty `OrderStatisticMap`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::OrderStatisticMap;
+    /// let map: OrderStatisticMap<u64, &str> = OrderStatisticMap::new();
+    /// # assert!(map.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        OrderStatisticMap {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            seed: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    /// Returns `true` if the `OrderStatisticMap` is empty, `false`
+    /// otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Insert a value, returning the previous value of the key if any.
+    ///
+    /// # Arguments
+    /// * key - The key of the value
+    /// * value - The value inserted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::OrderStatisticMap;
+    /// let mut map: OrderStatisticMap<u32, char> = OrderStatisticMap::new();
+    /// assert_eq!(map.insert(3, 'c'), None);
+    /// assert_eq!(map.insert(1, 'a'), None);
+    /// assert_eq!(map.insert(3, 'C'), Some('c'));
+    /// assert_eq!(map.iter().collect::<Vec<_>>(), vec![(&1, &'a'), (&3, &'C')]);
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(idx) = self.find(&key) {
+            return Some(std::mem::replace(&mut self.node_mut(idx).value, value));
+        }
+        let rank = self.rank(&key);
+        let priority = xorshift(&mut self.seed);
+        let node = Node {
+            key,
+            value,
+            priority,
+            size: 1,
+            left: None,
+            right: None,
+        };
+        let idx = match self.free.pop() {
+            Some(idx) => {
+                self.nodes[idx] = Some(node);
+                idx
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        };
+        let (before, after) = self.split(self.root, rank);
+        let before = self.merge(before, Some(idx));
+        self.root = self.merge(before, after);
+        None
+    }
+
+    /// Returns the value of a key, `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.find(key)?;
+        Some(&self.node(idx).value)
+    }
+
+    /// Returns a mutable reference to the value of a key, `None` if it is
+    /// absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.find(key)?;
+        Some(&mut self.node_mut(idx).value)
+    }
+
+    /// Returns `true` if the key is present, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Remove a key, returning its value if it was present.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let _ = self.find(key)?;
+        let rank = self.rank(key);
+        Some(self.remove_at(rank).1)
+    }
+
+    /// Returns the number of keys smaller than `key`, which is the
+    /// position of `key` in order if it is present.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::OrderStatisticMap;
+    /// let scores: OrderStatisticMap<u32, &str> =
+    ///     vec![(72, "ana"), (95, "bo"), (61, "cy"), (88, "di")].into_iter().collect();
+    /// assert_eq!(scores.rank(&88), 2);
+    /// assert_eq!(scores.rank(&90), 3);
+    /// assert_eq!(scores.rank(&0), 0);
+    /// ```
+    pub fn rank<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut rank = 0;
+        let mut current = self.root;
+        while let Some(idx) = current {
+            let node = self.node(idx);
+            if node.key.borrow() < key {
+                rank += self.size(node.left) + 1;
+                current = node.right;
+            } else {
+                current = node.left;
+            }
+        }
+        rank
+    }
+
+    /// Returns the entry of the `k`-th smallest key, counting from 0,
+    /// `None` if there are not more than `k` keys.
+    ///
+    /// # Arguments
+    /// * k - The position of the key in order
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::OrderStatisticMap;
+    /// // The median latency seen so far, updated online.
+    /// let mut latencies: OrderStatisticMap<u64, ()> = OrderStatisticMap::new();
+    /// let mut medians: Vec<u64> = Vec::new();
+    /// for latency in vec![40, 10, 30, 20, 50] {
+    ///     let _ = latencies.insert(latency, ());
+    ///     let (median, _) = latencies.select(latencies.len() / 2).unwrap();
+    ///     medians.push(*median);
+    /// }
+    /// assert_eq!(medians, vec![40, 40, 30, 30, 30]);
+    /// ```
+    pub fn select(&self, k: usize) -> Option<(&K, &V)> {
+        let mut k = k;
+        let mut current = self.root;
+        while let Some(idx) = current {
+            let node = self.node(idx);
+            let left = self.size(node.left);
+            if k < left {
+                current = node.left;
+            } else if k == left {
+                return Some((&node.key, &node.value));
+            } else {
+                k -= left + 1;
+                current = node.right;
+            }
+        }
+        None
+    }
+
+    /// Remove the entry of the `k`-th smallest key, counting from 0,
+    /// returning it.
+    ///
+    /// # Arguments
+    /// * k - The position of the key in order, lower than the number of
+    ///   keys
+    pub fn remove_at(&mut self, k: usize) -> (K, V) {
+        assert!(k < self.len());
+        let (before, rest) = self.split(self.root, k);
+        let (removed, after) = self.split(rest, 1);
+        self.root = self.merge(before, after);
+        let idx = removed.expect("the removed node exists");
+        let node = self.nodes[idx].take().expect("the node is linked");
+        self.free.push(idx);
+        (node.key, node.value)
+    }
+
+    /// Returns the entry of smallest key, `None` if empty.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.select(0)
+    }
+
+    /// Returns the entry of largest key, `None` if empty.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.select(self.len().checked_sub(1)?)
+    }
+
+    /// Remove the entry of smallest key, returning it.
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(self.remove_at(0))
+    }
+
+    /// Remove the entry of largest key, returning it.
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let last = self.len().checked_sub(1)?;
+        Some(self.remove_at(last))
+    }
+
+    /// Iterate over the entries, in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        let mut stack: Vec<usize> = Vec::new();
+        let mut current = self.root;
+        std::iter::from_fn(move || {
+            while let Some(idx) = current {
+                stack.push(idx);
+                current = self.node(idx).left;
+            }
+            let node = self.node(stack.pop()?);
+            current = node.right;
+            Some((&node.key, &node.value))
+        })
+    }
+
+    /// Iterate over the keys, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Iterate over the values, in key order.
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Remove every entry.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.root = None;
+    }
+
+    fn node(&self, idx: usize) -> &Node<K, V> {
+        self.nodes[idx].as_ref().expect("the node is linked")
+    }
+
+    fn node_mut(&mut self, idx: usize) -> &mut Node<K, V> {
+        self.nodes[idx].as_mut().expect("the node is linked")
+    }
+
+    fn size(&self, tree: Option<usize>) -> usize {
+        tree.map_or(0, |idx| self.node(idx).size)
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut current = self.root;
+        while let Some(idx) = current {
+            let node = self.node(idx);
+            current = match key.cmp(node.key.borrow()) {
+                std::cmp::Ordering::Less => node.left,
+                std::cmp::Ordering::Equal => return Some(idx),
+                std::cmp::Ordering::Greater => node.right,
+            };
+        }
+        None
+    }
+
+    /// Set the subtrees of the node `idx`, recomputing its size.
+    fn attach(&mut self, idx: usize, left: Option<usize>, right: Option<usize>) {
+        let size = self.size(left) + self.size(right) + 1;
+        let node = self.node_mut(idx);
+        node.left = left;
+        node.right = right;
+        node.size = size;
+    }
+
+    /// Split a tree into its `k` smallest keys and the others.
+    fn split(&mut self, tree: Option<usize>, k: usize) -> (Option<usize>, Option<usize>) {
+        let idx = match tree {
+            Some(idx) => idx,
+            None => return (None, None),
+        };
+        let (left, right) = (self.node(idx).left, self.node(idx).right);
+        let left_size = self.size(left);
+        if k <= left_size {
+            let (before, after) = self.split(left, k);
+            self.attach(idx, after, right);
+            (before, Some(idx))
+        } else {
+            let (before, after) = self.split(right, k - left_size - 1);
+            self.attach(idx, left, before);
+            (Some(idx), after)
+        }
+    }
+
+    /// Merge two trees, every key of `first` being smaller than every key
+    /// of `second`.
+    fn merge(&mut self, first: Option<usize>, second: Option<usize>) -> Option<usize> {
+        let (a, b) = match (first, second) {
+            (Some(a), Some(b)) => (a, b),
+            (first, None) => return first,
+            (None, second) => return second,
+        };
+        if self.node(a).priority > self.node(b).priority {
+            let (left, right) = (self.node(a).left, self.node(a).right);
+            let right = self.merge(right, Some(b));
+            self.attach(a, left, right);
+            Some(a)
+        } else {
+            let (left, right) = (self.node(b).left, self.node(b).right);
+            let left = self.merge(Some(a), left);
+            self.attach(b, left, right);
+            Some(b)
+        }
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for OrderStatisticMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map: OrderStatisticMap<K, V> = OrderStatisticMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for OrderStatisticMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            let _ = self.insert(key, value);
+        }
+    }
+}
+
+impl<K, V, Q> Index<&Q> for OrderStatisticMap<K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    /// Returns the value of a key.
+    ///
+    /// # Panics
+    /// Panics if the key is absent.
+    fn index(&self, key: &Q) -> &Self::Output {
+        self.get(key).expect("key not found")
+    }
+}
+
+impl<K: Ord, V> Default for OrderStatisticMap<K, V> {
+    fn default() -> Self {
+        OrderStatisticMap::new()
+    }
+}
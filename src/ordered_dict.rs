@@ -0,0 +1,308 @@
+//! This module implements a map remembering the insertion order of its keys.
+//!
+//! As with python `OrderedDict`, iteration follows the order in which keys
+//! were first inserted, replacing the value of a key keeps its position and
+//! `move_to_end` moves a key to either end.
+//!
+//! Keys are indexed by a `BTreeMap` pointing to their position in a second
+//! `BTreeMap` ordered by insertion, so lookups, insertions, removals and
+//! moves are all O(log n).
+//!
+use std::borrow::Borrow;
+use std::collections::btree_map::{BTreeMap, IntoValues};
+use std::iter::FromIterator;
+use std::ops::Index;
+
+/// Structure that map `K` keys to `V` values in insertion order
+#[derive(Debug, Clone)]
+pub struct OrderedDict<K, V> {
+    positions: BTreeMap<K, i64>,
+    entries: BTreeMap<i64, (K, V)>,
+    next_position: i64,
+}
+
+impl<K, V> OrderedDict<K, V> {
+    /// Create a new empty `OrderedDict`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::OrderedDict;
+    /// let dict: OrderedDict<&str, u32> = OrderedDict::new();
+    /// # assert!(dict.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        OrderedDict {
+            positions: BTreeMap::new(),
+            entries: BTreeMap::new(),
+            next_position: 0,
+        }
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the `OrderedDict` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over the keys and their values, in insertion order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> + '_ {
+        self.entries.values().map(|(key, value)| (key, value))
+    }
+
+    /// Iterate over the keys, in insertion order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + '_ {
+        self.entries.values().map(|(key, _)| key)
+    }
+
+    /// Iterate over the values, in the insertion order of their keys.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + '_ {
+        self.entries.values().map(|(_, value)| value)
+    }
+
+    /// Returns the first inserted key and its value, `None` if empty.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.iter().next()
+    }
+
+    /// Returns the last inserted key and its value, `None` if empty.
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.iter().next_back()
+    }
+}
+
+impl<K: Ord + Clone, V> OrderedDict<K, V> {
+    /// Insert a value, returning the previous value of the key if any.
+    ///
+    /// A new key is placed last, an existing key keeps its position.
+    ///
+    /// # Arguments
+    /// * key - The key of the value
+    /// * value - The value inserted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::OrderedDict;
+    /// let mut dict: OrderedDict<&str, u32> = OrderedDict::new();
+    /// assert_eq!(dict.insert("b", 1), None);
+    /// assert_eq!(dict.insert("a", 2), None);
+    /// assert_eq!(dict.insert("b", 3), Some(1));
+    /// assert_eq!(dict.iter().collect::<Vec<_>>(), vec![(&"b", &3), (&"a", &2)]);
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(position) = self.positions.get(&key) {
+            let entry = self
+                .entries
+                .get_mut(position)
+                .expect("every position points to an entry");
+            return Some(std::mem::replace(&mut entry.1, value));
+        }
+        let position = self.next_position;
+        self.next_position += 1;
+        let _ = self.positions.insert(key.clone(), position);
+        let _ = self.entries.insert(position, (key, value));
+        None
+    }
+
+    /// Returns the value of a key, `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let position = self.positions.get(key)?;
+        self.entries.get(position).map(|(_, value)| value)
+    }
+
+    /// Returns a mutable reference to the value of a key, `None` if it is
+    /// absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let position = self.positions.get(key)?;
+        self.entries.get_mut(position).map(|(_, value)| value)
+    }
+
+    /// Returns `true` if the key is present, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.positions.contains_key(key)
+    }
+
+    /// Remove a key, returning its value if it was present.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let position = self.positions.remove(key)?;
+        self.entries.remove(&position).map(|(_, value)| value)
+    }
+
+    /// Move a key to the end, or to the front if `last` is `false`.
+    ///
+    /// Returns `false` if the key is absent, `true` otherwise.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    /// * last - Whether the key is moved last or first
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::OrderedDict;
+    /// let mut dict: OrderedDict<char, u32> = "abcd".chars().zip(0..).collect();
+    /// assert!(dict.move_to_end(&'b', true));
+    /// assert!(dict.move_to_end(&'d', false));
+    /// assert!(!dict.move_to_end(&'z', true));
+    /// assert_eq!(dict.keys().collect::<String>(), "dacb");
+    /// ```
+    pub fn move_to_end<Q>(&mut self, key: &Q, last: bool) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let edge = if last {
+            self.entries.keys().next_back()
+        } else {
+            self.entries.keys().next()
+        };
+        let edge = match edge {
+            Some(edge) => *edge,
+            None => return false,
+        };
+        let position = match self.positions.get_mut(key) {
+            Some(position) => position,
+            None => return false,
+        };
+        if *position == edge {
+            return true;
+        }
+        let entry = self
+            .entries
+            .remove(position)
+            .expect("every position points to an entry");
+        // Positions below the first entry are free, so moving to the front
+        // takes the one just below it.
+        *position = if last {
+            let next = self.next_position;
+            self.next_position += 1;
+            next
+        } else {
+            edge - 1
+        };
+        let _ = self.entries.insert(*position, entry);
+        true
+    }
+
+    /// Remove the first inserted key, returning it along with its value.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::OrderedDict;
+    /// let mut dict: OrderedDict<&str, u32> = vec![("a", 1), ("b", 2)].into_iter().collect();
+    /// assert_eq!(dict.pop_first(), Some(("a", 1)));
+    /// assert_eq!(dict.pop_last(), Some(("b", 2)));
+    /// assert_eq!(dict.pop_last(), None);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let position = *self.entries.keys().next()?;
+        self.pop_at(position)
+    }
+
+    /// Remove the last inserted key, returning it along with its value.
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let position = *self.entries.keys().next_back()?;
+        self.pop_at(position)
+    }
+
+    /// Remove every key.
+    pub fn clear(&mut self) {
+        self.positions.clear();
+        self.entries.clear();
+        self.next_position = 0;
+    }
+
+    fn pop_at(&mut self, position: i64) -> Option<(K, V)> {
+        let (key, value) = self.entries.remove(&position)?;
+        let _ = self.positions.remove(&key);
+        Some((key, value))
+    }
+}
+
+impl<K: Ord + Clone, V> FromIterator<(K, V)> for OrderedDict<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut dict: OrderedDict<K, V> = OrderedDict::new();
+        dict.extend(iter);
+        dict
+    }
+}
+
+impl<K: Ord + Clone, V> Extend<(K, V)> for OrderedDict<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            let _ = self.insert(key, value);
+        }
+    }
+}
+
+impl<K, V> IntoIterator for OrderedDict<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoValues<i64, (K, V)>;
+
+    /// Iterate over the keys and their values, in insertion order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_values()
+    }
+}
+
+impl<K, V, Q> Index<&Q> for OrderedDict<K, V>
+where
+    K: Ord + Clone + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    /// Returns the value of a key.
+    ///
+    /// # Panics
+    /// Panics if the key is absent.
+    fn index(&self, index: &Q) -> &Self::Output {
+        self.get(index).expect("key not found in OrderedDict")
+    }
+}
+
+/// Two `OrderedDict` are equal if they hold the same keys and values in the
+/// same order.
+impl<K: PartialEq, V: PartialEq> PartialEq for OrderedDict<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for OrderedDict<K, V> {}
+
+impl<K, V> Default for OrderedDict<K, V> {
+    fn default() -> Self {
+        OrderedDict::new()
+    }
+}
@@ -0,0 +1,107 @@
+//! This module implements the counting of a file from several threads.
+//!
+//! The file is split in chunks ending on line boundaries, each chunk is
+//! streamed and counted by its own thread and the partial counters are
+//! then merged, so the file is never loaded in memory.
+//!
+use crate::Counter;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::panic;
+use std::path::Path;
+use std::thread;
+
+impl<T: Ord + Send> Counter<T> {
+    /// Create a new `Counter` from the tokens of a file, counted by
+    /// `n_threads` threads.
+    ///
+    /// # Arguments
+    /// * path - The path of the file, it must be valid UTF-8
+    /// * n_threads - The number of threads, must be greater than 0
+    /// * tokenizer - A function splitting a line, without its line ending, into tokens
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Counter;
+    /// # use std::fs;
+    /// let path = std::env::temp_dir().join("collectors-count-file-parallel.log");
+    /// let log: String = (0..1000)
+    ///     .map(|i| format!("GET /page/{} {}\n", i % 7, if i % 10 == 0 { 500 } else { 200 }))
+    ///     .collect();
+    /// fs::write(&path, &log).unwrap();
+    ///
+    /// let statuses: Counter<String> = Counter::count_file_parallel(&path, 4, |line| {
+    ///     line.rsplit(' ').next().map(String::from)
+    /// })
+    /// .unwrap();
+    /// assert_eq!(statuses["200"], 900);
+    /// assert_eq!(statuses["500"], 100);
+    ///
+    /// let sequential: Counter<String> =
+    ///     Counter::count_from_read(log.as_bytes(), |line| line.rsplit(' ').next().map(String::from))
+    ///         .unwrap();
+    /// assert_eq!(statuses, sequential);
+    /// # fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn count_file_parallel<P, F, I>(path: P, n_threads: usize, tokenizer: F) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+        F: Fn(&str) -> I + Sync,
+        I: IntoIterator<Item = T>,
+    {
+        assert!(n_threads > 0);
+        let path = path.as_ref();
+        let mut file = File::open(path)?;
+        let len = file.metadata()?.len();
+
+        let mut boundaries: Vec<u64> = Vec::with_capacity(n_threads + 1);
+        for idx in 0..n_threads as u64 {
+            let boundary = line_boundary(&mut file, len * idx / n_threads as u64, len)?;
+            boundaries.push(boundary);
+        }
+        boundaries.push(len);
+
+        let tokenizer = &tokenizer;
+        let partials: Vec<io::Result<Counter<T>>> = thread::scope(|scope| {
+            let handles: Vec<_> = boundaries
+                .windows(2)
+                .filter(|chunk| chunk[0] < chunk[1])
+                .map(|chunk| {
+                    let (start, end) = (chunk[0], chunk[1]);
+                    scope.spawn(move || {
+                        let mut file = File::open(path)?;
+                        let _ = file.seek(SeekFrom::Start(start))?;
+                        Counter::count_from_read(file.take(end - start), tokenizer)
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|err| panic::resume_unwind(err))
+                })
+                .collect()
+        });
+
+        let mut counter: Counter<T> = Counter::new();
+        for partial in partials {
+            counter.extend(partial?);
+        }
+        Ok(counter)
+    }
+}
+
+/// Returns the offset of the first line starting at or after `offset`.
+fn line_boundary(file: &mut File, offset: u64, len: u64) -> io::Result<u64> {
+    if offset == 0 {
+        return Ok(0);
+    }
+    // Starting from the previous byte finds `offset` itself when a line
+    // ends just before it.
+    let _ = file.seek(SeekFrom::Start(offset - 1))?;
+    let mut skipped = Vec::new();
+    let read = BufReader::new(file).read_until(b'\n', &mut skipped)?;
+    Ok((offset - 1 + read as u64).min(len))
+}
@@ -0,0 +1,42 @@
+//! This module implements the partition of an iterator into a fixed number
+//! of collections.
+//!
+//! `Iterator::partition` splits elements in two by a predicate;
+//! `partition_n` generalizes it to `N` buckets picked by index, in a single
+//! pass, each bucket keeping its elements in iteration order.
+//!
+/// Split the elements of an iterator into `N` buckets, by the index
+/// `bucket` gives them.
+///
+/// # Arguments
+/// * iter - The elements split
+/// * bucket - A function giving the index of the bucket of an element, must
+///   be lower than `N`
+///
+/// # Examples
+/// ```
+/// # use collectors::partition_n;
+/// // Route log lines to one processor per level.
+/// let lines = vec!["E disk full", "I started", "W slow query", "I stopped"];
+/// let [errors, warnings, infos] = partition_n(lines, |line| match &line[..1] {
+///     "E" => 0,
+///     "W" => 1,
+///     _ => 2,
+/// });
+/// assert_eq!(errors, vec!["E disk full"]);
+/// assert_eq!(warnings, vec!["W slow query"]);
+/// assert_eq!(infos, vec!["I started", "I stopped"]);
+/// ```
+pub fn partition_n<I, F, const N: usize>(iter: I, mut bucket: F) -> [Vec<I::Item>; N]
+where
+    I: IntoIterator,
+    F: FnMut(&I::Item) -> usize,
+{
+    let mut buckets: [Vec<I::Item>; N] = std::array::from_fn(|_| Vec::new());
+    for elem in iter {
+        let idx = bucket(&elem);
+        assert!(idx < N);
+        buckets[idx].push(elem);
+    }
+    buckets
+}
@@ -0,0 +1,376 @@
+//! This module implements a persistent ordered map, whose versions share
+//! their structure.
+//!
+//! The map is an AVL tree of reference-counted nodes that are never
+//! modified. Inserting or removing a key copies only the O(log n) nodes on
+//! the path to it, the new version sharing every other node with the old
+//! one, which stays valid and unchanged. Keeping a snapshot is then a
+//! clone of the root, in O(1), and a history of versions costs O(log n)
+//! memory per change. Entries are shared too, so keys and values need not
+//! be `Clone`. The nodes are atomically counted, so versions can be sent
+//! to other threads.
+//!
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+use std::ops::Index;
+use std::sync::Arc;
+
+type Tree<K, V> = Option<Arc<Node<K, V>>>;
+
+#[derive(Debug)]
+struct Node<K, V> {
+    entry: Arc<(K, V)>,
+    height: usize,
+    left: Tree<K, V>,
+    right: Tree<K, V>,
+}
+
+/// Structure that map `K` keys to `V` values, every change making a new
+/// version
+#[derive(Debug)]
+pub struct PersistentMap<K, V> {
+    root: Tree<K, V>,
+    len: usize,
+}
+
+impl<K: Ord, V> PersistentMap<K, V> {
+    /// Create a new empty `PersistentMap`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::PersistentMap;
+    /// let map: PersistentMap<&str, u128> = PersistentMap::new();
+    /// # assert!(map.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        PersistentMap { root: None, len: 0 }
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the `PersistentMap` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns a new version holding a value for a key, replacing its
+    /// previous value if any.
+    ///
+    /// # Arguments
+    /// * key - The key of the value
+    /// * value - The value inserted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::PersistentMap;
+    /// // Tallies per analysis pass, every pass keeping the previous ones.
+    /// let mut passes: Vec<PersistentMap<&str, u128>> = vec![PersistentMap::new()];
+    /// for word in vec!["a", "b", "a"] {
+    ///     let last = passes.last().unwrap();
+    ///     let count = last.get(word).copied().unwrap_or(0);
+    ///     passes.push(last.insert(word, count + 1));
+    /// }
+    /// assert_eq!(passes[3].get("a"), Some(&2));
+    /// assert_eq!(passes[2].get("a"), Some(&1));
+    /// assert_eq!(passes[1].get("b"), None);
+    /// assert!(passes[0].is_empty());
+    /// ```
+    pub fn insert(&self, key: K, value: V) -> Self {
+        let (root, replaced) = insert(&self.root, key, value);
+        PersistentMap {
+            root: Some(root),
+            len: if replaced { self.len } else { self.len + 1 },
+        }
+    }
+
+    /// Returns a new version without a key, the same version if the key is
+    /// absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::PersistentMap;
+    /// let before: PersistentMap<u32, char> = vec![(1, 'a'), (2, 'b')].into_iter().collect();
+    /// let after = before.remove(&1);
+    /// assert_eq!(after.len(), 1);
+    /// assert_eq!(before.get(&1), Some(&'a'));
+    /// assert!(after.remove(&1).ptr_eq(&after));
+    /// ```
+    pub fn remove<Q>(&self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match remove(&self.root, key) {
+            Some(root) => PersistentMap {
+                root,
+                len: self.len - 1,
+            },
+            None => self.clone(),
+        }
+    }
+
+    /// Returns the value of a key, `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    /// Returns the entry of a key, `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut current = &self.root;
+        while let Some(node) = current {
+            current = match key.cmp(node.entry.0.borrow()) {
+                Ordering::Less => &node.left,
+                Ordering::Equal => return Some((&node.entry.0, &node.entry.1)),
+                Ordering::Greater => &node.right,
+            };
+        }
+        None
+    }
+
+    /// Returns `true` if the key is present, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).is_some()
+    }
+
+    /// Returns the entry of smallest key, `None` if empty.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_ref()?;
+        while let Some(left) = &node.left {
+            node = left;
+        }
+        Some((&node.entry.0, &node.entry.1))
+    }
+
+    /// Returns the entry of largest key, `None` if empty.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_ref()?;
+        while let Some(right) = &node.right {
+            node = right;
+        }
+        Some((&node.entry.0, &node.entry.1))
+    }
+
+    /// Iterate over the entries, in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        let mut stack: Vec<&Node<K, V>> = Vec::new();
+        let mut current = self.root.as_deref();
+        std::iter::from_fn(move || {
+            while let Some(node) = current {
+                stack.push(node);
+                current = node.left.as_deref();
+            }
+            let node = stack.pop()?;
+            current = node.right.as_deref();
+            Some((&node.entry.0, &node.entry.1))
+        })
+    }
+
+    /// Iterate over the keys, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Iterate over the values, in key order.
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Returns `true` if both versions share their whole structure, which
+    /// implies they are equal, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * other - The other version
+    pub fn ptr_eq(&self, other: &PersistentMap<K, V>) -> bool {
+        match (&self.root, &other.root) {
+            (Some(root), Some(other_root)) => Arc::ptr_eq(root, other_root),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+fn height<K, V>(tree: &Tree<K, V>) -> usize {
+    tree.as_ref().map_or(0, |node| node.height)
+}
+
+fn make<K, V>(entry: Arc<(K, V)>, left: Tree<K, V>, right: Tree<K, V>) -> Arc<Node<K, V>> {
+    Arc::new(Node {
+        entry,
+        height: height(&left).max(height(&right)) + 1,
+        left,
+        right,
+    })
+}
+
+/// Returns a node of `entry` over two subtrees whose heights differ by at
+/// most 2, rotating to bring the difference back to at most 1.
+fn balance<K, V>(entry: Arc<(K, V)>, left: Tree<K, V>, right: Tree<K, V>) -> Arc<Node<K, V>> {
+    let (left_height, right_height) = (height(&left), height(&right));
+    if left_height > right_height + 1 {
+        let left = left.expect("the left subtree is higher");
+        if height(&left.left) >= height(&left.right) {
+            let right = make(entry, left.right.clone(), right);
+            make(left.entry.clone(), left.left.clone(), Some(right))
+        } else {
+            let middle = left.right.as_ref().expect("the middle subtree is higher");
+            let new_left = make(left.entry.clone(), left.left.clone(), middle.left.clone());
+            let new_right = make(entry, middle.right.clone(), right);
+            make(middle.entry.clone(), Some(new_left), Some(new_right))
+        }
+    } else if right_height > left_height + 1 {
+        let right = right.expect("the right subtree is higher");
+        if height(&right.right) >= height(&right.left) {
+            let left = make(entry, left, right.left.clone());
+            make(right.entry.clone(), Some(left), right.right.clone())
+        } else {
+            let middle = right.left.as_ref().expect("the middle subtree is higher");
+            let new_left = make(entry, left, middle.left.clone());
+            let new_right = make(
+                right.entry.clone(),
+                middle.right.clone(),
+                right.right.clone(),
+            );
+            make(middle.entry.clone(), Some(new_left), Some(new_right))
+        }
+    } else {
+        make(entry, left, right)
+    }
+}
+
+/// Returns the tree with an entry inserted, along with whether it
+/// replaced the entry of the key.
+fn insert<K: Ord, V>(tree: &Tree<K, V>, key: K, value: V) -> (Arc<Node<K, V>>, bool) {
+    let node = match tree {
+        Some(node) => node,
+        None => return (make(Arc::new((key, value)), None, None), false),
+    };
+    match key.cmp(&node.entry.0) {
+        Ordering::Less => {
+            let (left, replaced) = insert(&node.left, key, value);
+            let node = balance(node.entry.clone(), Some(left), node.right.clone());
+            (node, replaced)
+        }
+        Ordering::Equal => {
+            let node = make(
+                Arc::new((key, value)),
+                node.left.clone(),
+                node.right.clone(),
+            );
+            (node, true)
+        }
+        Ordering::Greater => {
+            let (right, replaced) = insert(&node.right, key, value);
+            let node = balance(node.entry.clone(), node.left.clone(), Some(right));
+            (node, replaced)
+        }
+    }
+}
+
+/// Returns the tree with a key removed, `None` if it is absent.
+fn remove<K, V, Q>(tree: &Tree<K, V>, key: &Q) -> Option<Tree<K, V>>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    let node = tree.as_ref()?;
+    let tree = match key.cmp(node.entry.0.borrow()) {
+        Ordering::Less => {
+            let left = remove(&node.left, key)?;
+            balance(node.entry.clone(), left, node.right.clone())
+        }
+        Ordering::Equal => match (&node.left, &node.right) {
+            (None, right) => return Some(right.clone()),
+            (left, None) => return Some(left.clone()),
+            (left, Some(right)) => {
+                let (first, rest) = remove_first(right);
+                balance(first, left.clone(), rest)
+            }
+        },
+        Ordering::Greater => {
+            let right = remove(&node.right, key)?;
+            balance(node.entry.clone(), node.left.clone(), right)
+        }
+    };
+    Some(Some(tree))
+}
+
+/// Returns the entry of smallest key of a tree and the tree without it.
+fn remove_first<K, V>(node: &Arc<Node<K, V>>) -> (Arc<(K, V)>, Tree<K, V>) {
+    match &node.left {
+        Some(left) => {
+            let (first, rest) = remove_first(left);
+            let tree = balance(node.entry.clone(), rest, node.right.clone());
+            (first, Some(tree))
+        }
+        None => (node.entry.clone(), node.right.clone()),
+    }
+}
+
+impl<K, V> Clone for PersistentMap<K, V> {
+    fn clone(&self) -> Self {
+        PersistentMap {
+            root: self.root.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for PersistentMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map: PersistentMap<K, V> = PersistentMap::new();
+        for (key, value) in iter {
+            map = map.insert(key, value);
+        }
+        map
+    }
+}
+
+impl<K, V, Q> Index<&Q> for PersistentMap<K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    /// Returns the value of a key.
+    ///
+    /// # Panics
+    /// Panics if the key is absent.
+    fn index(&self, key: &Q) -> &Self::Output {
+        self.get(key).expect("key not found")
+    }
+}
+
+impl<K: Ord, V> Default for PersistentMap<K, V> {
+    fn default() -> Self {
+        PersistentMap::new()
+    }
+}
@@ -0,0 +1,255 @@
+//! This module implements a priority queue whose priorities can change.
+//!
+//! As with `BinaryHeap`, the queue is a binary max heap, but every item
+//! also has its position in the heap indexed, so the priority of an item
+//! can be changed, or the item removed, in O(log n). Items are unique:
+//! pushing an item already queued changes its priority.
+//!
+//! Wrapping priorities in `Reverse` makes a min queue, as needed by
+//! Dijkstra's algorithm.
+//!
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+/// Structure that queue unique `T` items by decreasing `P` priority
+#[derive(Debug, Clone)]
+pub struct PriorityQueue<T, P> {
+    heap: Vec<(T, P)>,
+    positions: BTreeMap<T, usize>,
+}
+
+impl<T: Ord + Clone, P: Ord> PriorityQueue<T, P> {
+    /// Create a new empty `PriorityQueue`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::PriorityQueue;
+    /// let queue: PriorityQueue<&str, u32> = PriorityQueue::new();
+    /// # assert!(queue.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        PriorityQueue {
+            heap: Vec::new(),
+            positions: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of items.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if the `PriorityQueue` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Queue an item, changing its priority if it is already queued.
+    ///
+    /// Returns the previous priority of the item, if any.
+    ///
+    /// # Arguments
+    /// * item - The item queued
+    /// * priority - The priority of the item
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::PriorityQueue;
+    /// let mut queue: PriorityQueue<&str, u32> = PriorityQueue::new();
+    /// assert_eq!(queue.push("write docs", 1), None);
+    /// assert_eq!(queue.push("fix bug", 5), None);
+    /// assert_eq!(queue.push("write docs", 9), Some(1));
+    /// assert_eq!(queue.pop(), Some(("write docs", 9)));
+    /// assert_eq!(queue.pop(), Some(("fix bug", 5)));
+    /// assert_eq!(queue.pop(), None);
+    /// ```
+    pub fn push(&mut self, item: T, priority: P) -> Option<P> {
+        if self.positions.contains_key(&item) {
+            return self.change_priority(&item, priority);
+        }
+        let position = self.heap.len();
+        let _ = self.positions.insert(item.clone(), position);
+        self.heap.push((item, priority));
+        self.sift_up(position);
+        None
+    }
+
+    /// Returns the item of highest priority along with its priority,
+    /// `None` if empty.
+    pub fn peek(&self) -> Option<(&T, &P)> {
+        self.heap.first().map(|(item, priority)| (item, priority))
+    }
+
+    /// Remove the item of highest priority, returning it along with its
+    /// priority.
+    pub fn pop(&mut self) -> Option<(T, P)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        Some(self.remove_at(0))
+    }
+
+    /// Returns the priority of an item, `None` if it is not queued.
+    ///
+    /// # Arguments
+    /// * item - A borrowed form of the item
+    pub fn priority<Q>(&self, item: &Q) -> Option<&P>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let position = *self.positions.get(item)?;
+        Some(&self.heap[position].1)
+    }
+
+    /// Returns `true` if the item is queued, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * item - A borrowed form of the item
+    pub fn contains<Q>(&self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.positions.contains_key(item)
+    }
+
+    /// Change the priority of a queued item, returning its previous
+    /// priority, `None` if it is not queued.
+    ///
+    /// # Arguments
+    /// * item - A borrowed form of the item
+    /// * priority - The new priority of the item
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::PriorityQueue;
+    /// # use std::cmp::Reverse;
+    /// // Tentative distances of Dijkstra's algorithm, the closest first.
+    /// let mut distances: PriorityQueue<char, Reverse<u32>> = PriorityQueue::new();
+    /// let _ = distances.push('a', Reverse(7));
+    /// let _ = distances.push('b', Reverse(3));
+    /// assert_eq!(distances.change_priority(&'a', Reverse(1)), Some(Reverse(7)));
+    /// assert_eq!(distances.change_priority(&'z', Reverse(1)), None);
+    /// assert_eq!(distances.pop(), Some(('a', Reverse(1))));
+    /// ```
+    pub fn change_priority<Q>(&mut self, item: &Q, priority: P) -> Option<P>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let position = *self.positions.get(item)?;
+        let previous = std::mem::replace(&mut self.heap[position].1, priority);
+        if self.heap[position].1 > previous {
+            self.sift_up(position);
+        } else {
+            self.sift_down(position);
+        }
+        Some(previous)
+    }
+
+    /// Remove a queued item, returning its priority, `None` if it is not
+    /// queued.
+    ///
+    /// # Arguments
+    /// * item - A borrowed form of the item
+    pub fn remove<Q>(&mut self, item: &Q) -> Option<P>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let position = *self.positions.get(item)?;
+        Some(self.remove_at(position).1)
+    }
+
+    /// Iterate over the items and their priorities, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, &P)> + '_ {
+        self.heap.iter().map(|(item, priority)| (item, priority))
+    }
+
+    /// Convert into the items and their priorities, by decreasing priority.
+    pub fn into_sorted_vec(mut self) -> Vec<(T, P)> {
+        let mut sorted: Vec<(T, P)> = Vec::with_capacity(self.len());
+        while let Some(entry) = self.pop() {
+            sorted.push(entry);
+        }
+        sorted
+    }
+
+    /// Remove every item.
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.positions.clear();
+    }
+
+    fn remove_at(&mut self, position: usize) -> (T, P) {
+        let last = self.heap.len() - 1;
+        self.swap(position, last);
+        let (item, priority) = self.heap.pop().expect("the heap is not empty");
+        let _ = self.positions.remove(&item);
+        if position < self.heap.len() {
+            self.sift_down(position);
+            self.sift_up(position);
+        }
+        (item, priority)
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        for position in [a, b].iter() {
+            if let Some(index) = self.positions.get_mut(&self.heap[*position].0) {
+                *index = *position;
+            }
+        }
+    }
+
+    fn sift_up(&mut self, mut position: usize) {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if self.heap[position].1 <= self.heap[parent].1 {
+                break;
+            }
+            self.swap(position, parent);
+            position = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut position: usize) {
+        loop {
+            let mut largest = position;
+            for child in [2 * position + 1, 2 * position + 2].iter() {
+                if *child < self.heap.len() && self.heap[*child].1 > self.heap[largest].1 {
+                    largest = *child;
+                }
+            }
+            if largest == position {
+                break;
+            }
+            self.swap(position, largest);
+            position = largest;
+        }
+    }
+}
+
+impl<T: Ord + Clone, P: Ord> FromIterator<(T, P)> for PriorityQueue<T, P> {
+    fn from_iter<I: IntoIterator<Item = (T, P)>>(iter: I) -> Self {
+        let mut queue: PriorityQueue<T, P> = PriorityQueue::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
+impl<T: Ord + Clone, P: Ord> Extend<(T, P)> for PriorityQueue<T, P> {
+    fn extend<I: IntoIterator<Item = (T, P)>>(&mut self, iter: I) {
+        for (item, priority) in iter {
+            let _ = self.push(item, priority);
+        }
+    }
+}
+
+impl<T: Ord + Clone, P: Ord> Default for PriorityQueue<T, P> {
+    fn default() -> Self {
+        PriorityQueue::new()
+    }
+}
@@ -0,0 +1,143 @@
+//! This module implements Python bindings for `Counter`, behind the
+//! `python` feature.
+//!
+//! The extension module is named `collectors` and exposes a `Counter`
+//! class counting `str` and `int` elements, convertible from and to
+//! `collections.Counter`. Texts can be counted word by word without
+//! creating a Python object per word through `update_words`.
+//!
+//! The extension is built as a dynamic library, with maturin or with
+//! `cargo rustc --release --features python --crate-type cdylib`.
+//!
+//! ```python
+//! import collectors
+//!
+//! counter = collectors.Counter(["a", "b", "a"])
+//! counter.update_words("the cat and the dog")
+//! assert counter["a"] == 2
+//! assert counter.most_common(1) == [("a", 2)]
+//! assert counter.to_counter()["the"] == 2
+//! ```
+//!
+use crate::Counter;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::cmp::Reverse;
+
+/// Element of a Python `Counter`, ints sort before strings
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, FromPyObject, IntoPyObject)]
+enum PyKey {
+    Int(i64),
+    Str(String),
+}
+
+/// Python class wrapping a `Counter`
+#[pyclass(name = "Counter", module = "collectors")]
+#[derive(Debug, Default)]
+pub struct PyCounter {
+    counter: Counter<PyKey>,
+}
+
+#[pymethods]
+impl PyCounter {
+    /// Create a new `Counter`, counting the elements of `iterable` if given.
+    #[new]
+    #[pyo3(signature = (iterable = None))]
+    fn py_new(iterable: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        let mut counter = PyCounter::default();
+        if let Some(iterable) = iterable {
+            counter.update(iterable)?;
+        }
+        Ok(counter)
+    }
+
+    /// Count the elements of `iterable`, which must be `str` or `int`.
+    fn update(&mut self, iterable: &Bound<'_, PyAny>) -> PyResult<()> {
+        for item in iterable.try_iter()? {
+            let key: PyKey = item?.extract()?;
+            self.counter.update_from_value(key);
+        }
+        Ok(())
+    }
+
+    /// Count the whitespace separated words of `text`.
+    fn update_words(&mut self, text: &str) {
+        self.counter.update_from_iter(
+            text.split_whitespace()
+                .map(|word| PyKey::Str(String::from(word))),
+        );
+    }
+
+    /// Returns the sum of all counts.
+    fn total(&self) -> u128 {
+        self.counter.total()
+    }
+
+    /// Returns the `n` most counted elements and their counts, every
+    /// element if `n` is not given.
+    #[pyo3(signature = (n = None))]
+    fn most_common(&self, n: Option<usize>) -> Vec<(PyKey, u128)> {
+        let mut elements: Vec<(PyKey, u128)> = self
+            .counter
+            .iter()
+            .map(|(key, count)| (key.clone(), *count))
+            .collect();
+        elements.sort_by_key(|(_, count)| Reverse(*count));
+        elements.truncate(n.unwrap_or(elements.len()));
+        elements
+    }
+
+    /// Convert into a `collections.Counter`.
+    fn to_counter<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let counts = PyDict::new(py);
+        for (key, count) in self.counter.iter() {
+            counts.set_item(key.clone(), *count)?;
+        }
+        py.import("collections")?
+            .getattr("Counter")?
+            .call1((counts,))
+    }
+
+    /// Create a new `Counter` from a `collections.Counter` or any mapping
+    /// of elements to non negative counts.
+    #[staticmethod]
+    fn from_counter(counter: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let mut converted = PyCounter::default();
+        for item in counter.call_method0("items")?.try_iter()? {
+            let (key, count): (PyKey, u128) = item?.extract()?;
+            converted.counter.extend(Some((key, count)));
+        }
+        Ok(converted)
+    }
+
+    fn __getitem__(&self, key: PyKey) -> u128 {
+        self.counter[&key]
+    }
+
+    fn __contains__(&self, key: PyKey) -> bool {
+        self.counter[&key] > 0
+    }
+
+    fn __len__(&self) -> usize {
+        self.counter.len()
+    }
+
+    fn __repr__(&self) -> String {
+        let elements: Vec<String> = self
+            .most_common(None)
+            .into_iter()
+            .map(|(key, count)| match key {
+                PyKey::Int(key) => format!("{}: {}", key, count),
+                PyKey::Str(key) => format!("{:?}: {}", key, count),
+            })
+            .collect();
+        format!("Counter({{{}}})", elements.join(", "))
+    }
+}
+
+/// Fast counting collectors implemented in Rust.
+#[pymodule(name = "collectors")]
+mod python_module {
+    #[pymodule_export]
+    use super::PyCounter;
+}
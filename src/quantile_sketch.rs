@@ -0,0 +1,244 @@
+//! This module implements a t-digest, estimating the quantiles of a stream
+//! of numeric observations in a bounded amount of memory.
+//!
+//! Observations are summarized by centroids, a mean and a weight, that are
+//! kept small near the extreme quantiles and allowed to grow around the
+//! median. Hence tail quantiles such as p99 stay accurate while the number
+//! of centroids is roughly bounded by the `compression` parameter.
+//!
+use std::cmp::Ordering;
+use std::f64::consts::PI;
+
+/// Structure that estimate the quantiles of a stream of `f64` observations
+#[derive(Debug, Clone)]
+pub struct QuantileSketch {
+    centroids: Vec<(f64, f64)>,
+    buffer: Vec<f64>,
+    compression: f64,
+    count: u64,
+    min: f64,
+    max: f64,
+}
+
+impl QuantileSketch {
+    /// Create a new empty `QuantileSketch`.
+    ///
+    /// # Arguments
+    /// * compression - The accuracy to size trade-off, must be greater than 0, 100 is a common choice
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::QuantileSketch;
+    /// let sketch = QuantileSketch::new(100.0);
+    /// # assert!(sketch.is_empty());
+    /// ```
+    pub fn new(compression: f64) -> Self {
+        assert!(compression > 0.0);
+        QuantileSketch {
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            compression,
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Returns the compression parameter.
+    pub fn compression(&self) -> f64 {
+        self.compression
+    }
+
+    /// Returns the number of inserted observations.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns `true` if no observation was inserted, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the lowest observation, `None` if the sketch is empty.
+    pub fn min(&self) -> Option<f64> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.min)
+        }
+    }
+
+    /// Returns the highest observation, `None` if the sketch is empty.
+    pub fn max(&self) -> Option<f64> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.max)
+        }
+    }
+
+    /// Insert an observation.
+    ///
+    /// # Arguments
+    /// * value - The observation, must not be NaN
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::QuantileSketch;
+    /// let mut sketch = QuantileSketch::new(100.0);
+    /// sketch.insert(3.5);
+    /// sketch.insert(-1.0);
+    /// assert_eq!(sketch.count(), 2);
+    /// assert_eq!(sketch.min(), Some(-1.0));
+    /// ```
+    pub fn insert(&mut self, value: f64) {
+        assert!(!value.is_nan());
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.buffer.push(value);
+        if self.buffer.len() as f64 >= 5.0 * self.compression {
+            self.flush();
+        }
+    }
+
+    /// Insert every observation of an iterator.
+    ///
+    /// # Arguments
+    /// * iter - An iterator over the observations
+    pub fn insert_from_iter<I>(&mut self, iter: I)
+    where
+        I: Iterator<Item = f64>,
+    {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+
+    /// Merge the observations of another sketch into this one.
+    ///
+    /// # Arguments
+    /// * other - The sketch to merge, its compression may differ
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::QuantileSketch;
+    /// let mut even = QuantileSketch::new(100.0);
+    /// even.insert_from_iter((0..500).map(|i| (2 * i) as f64));
+    /// let mut odd = QuantileSketch::new(100.0);
+    /// odd.insert_from_iter((0..500).map(|i| (2 * i + 1) as f64));
+    /// even.merge(&odd);
+    /// assert_eq!(even.count(), 1000);
+    /// assert_eq!(even.max(), Some(999.0));
+    /// let median = even.quantile(0.5).unwrap();
+    /// assert!((median - 500.0).abs() < 10.0);
+    /// ```
+    pub fn merge(&mut self, other: &QuantileSketch) {
+        if other.is_empty() {
+            return;
+        }
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        let mut points = other.points();
+        points.append(&mut self.points());
+        self.buffer.clear();
+        self.centroids = compress(points, self.compression);
+    }
+
+    /// Estimate the value below which a fraction `q` of the observations
+    /// fall, `None` if the sketch is empty.
+    ///
+    /// # Arguments
+    /// * q - The quantile to estimate, between 0 and 1
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::QuantileSketch;
+    /// let mut sketch = QuantileSketch::new(100.0);
+    /// sketch.insert_from_iter((1..=10_000).map(|i| i as f64));
+    /// let p50 = sketch.quantile(0.5).unwrap();
+    /// let p99 = sketch.quantile(0.99).unwrap();
+    /// assert!((p50 - 5_000.0).abs() < 50.0);
+    /// assert!((p99 - 9_900.0).abs() < 10.0);
+    /// assert_eq!(sketch.quantile(1.0), Some(10_000.0));
+    /// ```
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        assert!((0.0..=1.0).contains(&q));
+        if self.is_empty() {
+            return None;
+        }
+        let centroids = if self.buffer.is_empty() {
+            self.centroids.clone()
+        } else {
+            compress(self.points(), self.compression)
+        };
+
+        // The weight of a centroid is spread around its mean, the ends of
+        // the curve are pinned to the lowest and highest observations.
+        let target = q * self.count as f64;
+        let mut previous = (0.0, self.min);
+        let mut cumulative = 0.0;
+        for (mean, weight) in centroids {
+            let center = cumulative + weight / 2.0;
+            if target < center {
+                return Some(interpolate(previous, (center, mean), target));
+            }
+            previous = (center, mean);
+            cumulative += weight;
+        }
+        Some(interpolate(previous, (self.count as f64, self.max), target))
+    }
+
+    fn flush(&mut self) {
+        let points = self.points();
+        self.buffer.clear();
+        self.centroids = compress(points, self.compression);
+    }
+
+    fn points(&self) -> Vec<(f64, f64)> {
+        let mut points = self.centroids.clone();
+        points.extend(self.buffer.iter().map(|value| (*value, 1.0)));
+        points
+    }
+}
+
+/// Sort and merge points into centroids whose size follows the t-digest scale
+/// function.
+fn compress(mut points: Vec<(f64, f64)>, compression: f64) -> Vec<(f64, f64)> {
+    points.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    let total: f64 = points.iter().map(|(_, weight)| weight).sum();
+    let scale = |q: f64| compression / (2.0 * PI) * (2.0 * q - 1.0).asin();
+
+    let mut centroids: Vec<(f64, f64)> = Vec::new();
+    let mut points = points.into_iter();
+    let mut current = match points.next() {
+        Some(point) => point,
+        None => return centroids,
+    };
+    let mut before = 0.0;
+    let mut lower_bound = scale(0.0);
+    for (mean, weight) in points {
+        let upper_q = ((before + current.1 + weight) / total).min(1.0);
+        if scale(upper_q) - lower_bound <= 1.0 {
+            let merged = current.1 + weight;
+            current.0 += (mean - current.0) * weight / merged;
+            current.1 = merged;
+        } else {
+            before += current.1;
+            lower_bound = scale((before / total).min(1.0));
+            centroids.push(current);
+            current = (mean, weight);
+        }
+    }
+    centroids.push(current);
+    centroids
+}
+
+fn interpolate((x0, y0): (f64, f64), (x1, y1): (f64, f64), x: f64) -> f64 {
+    if x1 <= x0 {
+        y1
+    } else {
+        y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+    }
+}
@@ -0,0 +1,336 @@
+//! This module implements a compressed prefix tree over byte string keys.
+//!
+//! Unlike a `Trie`, whose nodes hold a single byte, every node of a
+//! `RadixMap` holds the whole run of bytes its keys share, so chains of
+//! single child nodes never exist and large sets of keys with long common
+//! prefixes, such as URLs or file paths, use few nodes. On top of exact
+//! lookups, `longest_prefix` returns the value of the longest key that is
+//! a prefix of the query, as a routing table does.
+//!
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+#[derive(Debug, Clone)]
+struct Node<V> {
+    label: Vec<u8>,
+    value: Option<V>,
+    children: BTreeMap<u8, Node<V>>,
+}
+
+impl<V> Node<V> {
+    fn new(label: Vec<u8>, value: Option<V>) -> Self {
+        Node {
+            label,
+            value,
+            children: BTreeMap::new(),
+        }
+    }
+
+    /// Insert the value of `key`, relative to this node.
+    fn insert(&mut self, key: &[u8], value: V) -> Option<V> {
+        let first = match key.first() {
+            Some(first) => *first,
+            None => return self.value.replace(value),
+        };
+        let mut child = match self.children.remove(&first) {
+            Some(child) => child,
+            None => {
+                let _ = self
+                    .children
+                    .insert(first, Node::new(key.to_vec(), Some(value)));
+                return None;
+            }
+        };
+        let common = common_prefix_len(&child.label, key);
+        let previous = if common == child.label.len() {
+            child.insert(&key[common..], value)
+        } else {
+            // The key diverges inside the label, which is split in two.
+            let mut parent: Node<V> = Node::new(child.label[..common].to_vec(), None);
+            let _ = child.label.drain(..common);
+            let _ = parent.children.insert(child.label[0], child);
+            child = parent;
+            child.insert(&key[common..], value)
+        };
+        let _ = self.children.insert(first, child);
+        previous
+    }
+
+    /// Remove the value of `key`, relative to this node, merging the nodes
+    /// left with a single child and no value.
+    fn remove(&mut self, key: &[u8]) -> Option<V> {
+        let first = match key.first() {
+            Some(first) => first,
+            None => return self.value.take(),
+        };
+        let child = self.children.get_mut(first)?;
+        if !key.starts_with(&child.label) {
+            return None;
+        }
+        let label_len = child.label.len();
+        let value = child.remove(&key[label_len..])?;
+        if child.value.is_none() {
+            match child.children.len() {
+                0 => {
+                    let _ = self.children.remove(first);
+                }
+                1 => {
+                    let grandchild = child
+                        .children
+                        .pop_first()
+                        .map(|(_, grandchild)| grandchild)
+                        .expect("the child has one child");
+                    child.label.extend(grandchild.label);
+                    child.value = grandchild.value;
+                    child.children = grandchild.children;
+                }
+                _ => {}
+            }
+        }
+        Some(value)
+    }
+
+    /// Iterate over the keys and values below this node, in key order,
+    /// the key of this node being `key`.
+    fn iter(&self, key: Vec<u8>) -> impl Iterator<Item = (Vec<u8>, &V)> + '_ {
+        let mut stack: Vec<(Vec<u8>, &Node<V>)> = vec![(key, self)];
+        std::iter::from_fn(move || {
+            while let Some((key, node)) = stack.pop() {
+                for child in node.children.values().rev() {
+                    let mut child_key = key.clone();
+                    child_key.extend_from_slice(&child.label);
+                    stack.push((child_key, child));
+                }
+                if let Some(value) = &node.value {
+                    return Some((key, value));
+                }
+            }
+            None
+        })
+    }
+
+    fn count_nodes(&self) -> usize {
+        1 + self.children.values().map(Node::count_nodes).sum::<usize>()
+    }
+}
+
+/// Returns the length of the longest common prefix of two byte strings.
+fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b.iter()).take_while(|(a, b)| a == b).count()
+}
+
+/// Structure that map byte string keys to `V` values in a compressed
+/// prefix tree
+#[derive(Debug, Clone)]
+pub struct RadixMap<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+impl<V> RadixMap<V> {
+    /// Create a new empty `RadixMap`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::RadixMap;
+    /// let map: RadixMap<u32> = RadixMap::new();
+    /// # assert!(map.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        RadixMap {
+            root: Node::new(Vec::new(), None),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the `RadixMap` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of nodes of the tree, the root included.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::RadixMap;
+    /// let map: RadixMap<()> = vec!["/api/v1/users", "/api/v1/groups", "/static"]
+    ///     .into_iter()
+    ///     .map(|path| (path, ()))
+    ///     .collect();
+    /// // The root, "/", "api/v1/", "users", "groups" and "static".
+    /// assert_eq!(map.node_count(), 6);
+    /// ```
+    pub fn node_count(&self) -> usize {
+        self.root.count_nodes()
+    }
+
+    /// Insert a value, returning the previous value of the key if any.
+    ///
+    /// # Arguments
+    /// * key - The key of the value
+    /// * value - The value inserted
+    pub fn insert<K: AsRef<[u8]>>(&mut self, key: K, value: V) -> Option<V> {
+        let previous = self.root.insert(key.as_ref(), value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    /// Returns the value of a key, `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * key - The key looked up
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Option<&V> {
+        let mut node = &self.root;
+        let mut rest = key.as_ref();
+        while let Some(first) = rest.first() {
+            node = node.children.get(first)?;
+            rest = rest.strip_prefix(node.label.as_slice())?;
+        }
+        node.value.as_ref()
+    }
+
+    /// Returns a mutable reference to the value of a key, `None` if it is
+    /// absent.
+    ///
+    /// # Arguments
+    /// * key - The key looked up
+    pub fn get_mut<K: AsRef<[u8]>>(&mut self, key: K) -> Option<&mut V> {
+        let mut node = &mut self.root;
+        let mut rest = key.as_ref();
+        while let Some(first) = rest.first() {
+            node = node.children.get_mut(first)?;
+            rest = rest.strip_prefix(node.label.as_slice())?;
+        }
+        node.value.as_mut()
+    }
+
+    /// Returns `true` if the key is present, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * key - The key looked up
+    pub fn contains_key<K: AsRef<[u8]>>(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Remove a key, returning its value if it was present.
+    ///
+    /// # Arguments
+    /// * key - The key removed
+    pub fn remove<K: AsRef<[u8]>>(&mut self, key: K) -> Option<V> {
+        let value = self.root.remove(key.as_ref())?;
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Returns the longest key that is a prefix of `key`, as its length,
+    /// along with its value, `None` if no key is.
+    ///
+    /// # Arguments
+    /// * key - The key whose prefixes are looked up
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::RadixMap;
+    /// let routes: RadixMap<&str> = vec![("/", "root"), ("/api/", "api"), ("/api/v2/", "v2")]
+    ///     .into_iter()
+    ///     .collect();
+    /// assert_eq!(routes.longest_prefix("/api/v2/users"), Some((8, &"v2")));
+    /// assert_eq!(routes.longest_prefix("/api/v1/users"), Some((5, &"api")));
+    /// assert_eq!(routes.longest_prefix("/index.html"), Some((1, &"root")));
+    /// assert_eq!(routes.longest_prefix("index.html"), None);
+    /// ```
+    pub fn longest_prefix<K: AsRef<[u8]>>(&self, key: K) -> Option<(usize, &V)> {
+        let key = key.as_ref();
+        let mut node = &self.root;
+        let mut consumed = 0;
+        let mut longest = node.value.as_ref().map(|value| (0, value));
+        while let Some(child) = key.get(consumed).and_then(|first| node.children.get(first)) {
+            if !key[consumed..].starts_with(&child.label) {
+                break;
+            }
+            consumed += child.label.len();
+            node = child;
+            if let Some(value) = &node.value {
+                longest = Some((consumed, value));
+            }
+        }
+        longest
+    }
+
+    /// Iterate over the keys starting with `prefix` and their values, in
+    /// key order.
+    ///
+    /// # Arguments
+    /// * prefix - The prefix of the keys
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::RadixMap;
+    /// let map: RadixMap<u32> = vec![("src/lib.rs", 1), ("src/bin/main.rs", 2), ("README.md", 3)]
+    ///     .into_iter()
+    ///     .collect();
+    /// let keys: Vec<Vec<u8>> = map.iter_prefix("src/").map(|(key, _)| key).collect();
+    /// assert_eq!(keys, vec![b"src/bin/main.rs".to_vec(), b"src/lib.rs".to_vec()]);
+    /// ```
+    pub fn iter_prefix<K: AsRef<[u8]>>(
+        &self,
+        prefix: K,
+    ) -> impl Iterator<Item = (Vec<u8>, &V)> + '_ {
+        let prefix = prefix.as_ref();
+        let mut node = &self.root;
+        let mut key: Vec<u8> = Vec::new();
+        let mut found = true;
+        while key.len() < prefix.len() {
+            let rest = &prefix[key.len()..];
+            match node.children.get(&rest[0]) {
+                // The prefix may end inside the label of the child.
+                Some(child) if child.label.starts_with(rest) || rest.starts_with(&child.label) => {
+                    key.extend_from_slice(&child.label);
+                    node = child;
+                }
+                _ => {
+                    found = false;
+                    break;
+                }
+            }
+        }
+        Some(node)
+            .filter(|_| found)
+            .into_iter()
+            .flat_map(move |node| node.iter(key.clone()))
+    }
+
+    /// Iterate over the keys and their values, in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec<u8>, &V)> + '_ {
+        self.root.iter(Vec::new())
+    }
+}
+
+impl<K: AsRef<[u8]>, V> FromIterator<(K, V)> for RadixMap<V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map: RadixMap<V> = RadixMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: AsRef<[u8]>, V> Extend<(K, V)> for RadixMap<V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            let _ = self.insert(key, value);
+        }
+    }
+}
+
+impl<V> Default for RadixMap<V> {
+    fn default() -> Self {
+        RadixMap::new()
+    }
+}
@@ -0,0 +1,201 @@
+//! This module implements a set of `T` stored as disjoint ranges.
+//!
+//! Ranges are half open and kept coalesced: inserting a range merges it
+//! with the ranges it overlaps or touches, so `[0, 4)` and `[4, 8)` are
+//! stored as `[0, 8)`. Removing a range splits the ranges it cuts through.
+//!
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+use std::ops::Range;
+
+/// Structure that hold a set of `T` as disjoint ranges
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    runs: BTreeMap<T, T>,
+}
+
+impl<T: Ord + Clone> RangeSet<T> {
+    /// Create a new empty `RangeSet`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::RangeSet;
+    /// let set: RangeSet<u64> = RangeSet::new();
+    /// # assert!(set.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        RangeSet {
+            runs: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of disjoint ranges.
+    pub fn len(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Returns `true` if the `RangeSet` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Add a range, merging it with the ranges it overlaps or touches.
+    ///
+    /// Empty ranges are ignored.
+    ///
+    /// # Arguments
+    /// * range - The range added
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::RangeSet;
+    /// // Offsets of a stream acknowledged so far.
+    /// let mut acked: RangeSet<u64> = RangeSet::new();
+    /// acked.insert_range(0..100);
+    /// acked.insert_range(200..300);
+    /// acked.insert_range(100..150);
+    /// assert_eq!(acked.iter().collect::<Vec<_>>(), vec![0..150, 200..300]);
+    /// assert_eq!(acked.gaps().collect::<Vec<_>>(), vec![150..200]);
+    /// ```
+    pub fn insert_range(&mut self, range: Range<T>) {
+        if range.start >= range.end {
+            return;
+        }
+        let Range { mut start, mut end } = range;
+        if let Some((prev_start, prev_end)) = self.runs.range(..=start.clone()).next_back() {
+            if *prev_end >= start {
+                start = prev_start.clone();
+                if *prev_end > end {
+                    end = prev_end.clone();
+                }
+            }
+        }
+        let merged: Vec<T> = self
+            .runs
+            .range(start.clone()..=end.clone())
+            .map(|(run_start, _)| run_start.clone())
+            .collect();
+        for run_start in merged {
+            let run_end = self.runs.remove(&run_start).expect("the run was found");
+            if run_end > end {
+                end = run_end;
+            }
+        }
+        let _ = self.runs.insert(start, end);
+    }
+
+    /// Remove a range, splitting the ranges it cuts through.
+    ///
+    /// # Arguments
+    /// * range - The range removed
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::RangeSet;
+    /// let mut set: RangeSet<i32> = vec![0..10, 20..30].into_iter().collect();
+    /// set.remove_range(5..25);
+    /// assert_eq!(set.iter().collect::<Vec<_>>(), vec![0..5, 25..30]);
+    /// assert!(set.contains(&4) && !set.contains(&5));
+    /// ```
+    pub fn remove_range(&mut self, range: Range<T>) {
+        if range.start >= range.end {
+            return;
+        }
+        let mut cut: Vec<(T, T)> = Vec::new();
+        if let Some((start, end)) = self.runs.range(..range.start.clone()).next_back() {
+            if *end > range.start {
+                cut.push((start.clone(), end.clone()));
+            }
+        }
+        cut.extend(
+            self.runs
+                .range(range.start.clone()..range.end.clone())
+                .map(|(start, end)| (start.clone(), end.clone())),
+        );
+        for (start, end) in cut {
+            let _ = self.runs.remove(&start);
+            if start < range.start {
+                let _ = self.runs.insert(start, range.start.clone());
+            }
+            if end > range.end {
+                let _ = self.runs.insert(range.end.clone(), end);
+            }
+        }
+    }
+
+    /// Returns `true` if a range contains `point`, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * point - The point looked up
+    pub fn contains(&self, point: &T) -> bool {
+        self.runs
+            .range(..=point.clone())
+            .next_back()
+            .is_some_and(|(_, end)| end > point)
+    }
+
+    /// Returns `true` if a single range covers the whole of `range`, `false`
+    /// otherwise. Empty ranges are always covered.
+    ///
+    /// # Arguments
+    /// * range - The range looked up
+    pub fn contains_range(&self, range: &Range<T>) -> bool {
+        range.start >= range.end
+            || self
+                .runs
+                .range(..=range.start.clone())
+                .next_back()
+                .is_some_and(|(_, end)| *end >= range.end)
+    }
+
+    /// Iterate over the disjoint ranges, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = Range<T>> + '_ {
+        self.runs
+            .iter()
+            .map(|(start, end)| start.clone()..end.clone())
+    }
+
+    /// Iterate over the ranges between the disjoint ranges, in increasing
+    /// order.
+    pub fn gaps(&self) -> impl Iterator<Item = Range<T>> + '_ {
+        self.runs
+            .iter()
+            .zip(self.runs.keys().skip(1))
+            .map(|((_, end), next_start)| end.clone()..next_start.clone())
+    }
+
+    /// Returns the smallest start and the largest end of the ranges, `None`
+    /// if empty.
+    pub fn span(&self) -> Option<Range<T>> {
+        let start = self.runs.keys().next()?;
+        let end = self.runs.values().next_back()?;
+        Some(start.clone()..end.clone())
+    }
+
+    /// Remove every range.
+    pub fn clear(&mut self) {
+        self.runs.clear();
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set: RangeSet<T> = RangeSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Ord + Clone> Extend<Range<T>> for RangeSet<T> {
+    fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert_range(range);
+        }
+    }
+}
+
+impl<T: Ord + Clone> Default for RangeSet<T> {
+    fn default() -> Self {
+        RangeSet::new()
+    }
+}
@@ -0,0 +1,170 @@
+//! This module implements the uniform sampling of a stream of unknown
+//! length.
+//!
+//! A `ReservoirSampler` keeps `k` elements, Algorithm R: the `n`-th element
+//! offered replaces a random kept element with probability `k / n`, so at
+//! any time every element seen so far is kept with the same probability.
+//! Two samplers of separate streams merge into a uniform sample of both,
+//! each side contributing in proportion to the number of elements it saw.
+//!
+//! Random numbers come from an internal xorshift generator, seeded with
+//! `with_seed` for reproducible samples.
+//!
+use crate::min_hash::xorshift;
+/// Structure that keep a uniform sample of `T` elements from a stream
+#[derive(Debug, Clone)]
+pub struct ReservoirSampler<T> {
+    samples: Vec<T>,
+    capacity: usize,
+    seen: u128,
+    seed: u64,
+}
+
+impl<T> ReservoirSampler<T> {
+    /// Create a new empty `ReservoirSampler` keeping `capacity` elements.
+    ///
+    /// # Arguments
+    /// * capacity - The size of the sample, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ReservoirSampler;
+    /// let sampler: ReservoirSampler<u32> = ReservoirSampler::new(10);
+    /// # assert!(sampler.is_empty());
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        ReservoirSampler::with_seed(capacity, 0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Create a new empty `ReservoirSampler` keeping `capacity` elements,
+    /// its random numbers being generated from `seed`.
+    ///
+    /// # Arguments
+    /// * capacity - The size of the sample, must be greater than 0
+    /// * seed - The seed of the random numbers, must not be 0
+    pub fn with_seed(capacity: usize, seed: u64) -> Self {
+        assert!(capacity > 0);
+        assert!(seed != 0);
+        ReservoirSampler {
+            samples: Vec::with_capacity(capacity),
+            capacity,
+            seen: 0,
+            seed,
+        }
+    }
+
+    /// Returns the size of the sample once enough elements were offered.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of elements kept.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns `true` if no element was offered, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the number of elements offered.
+    pub fn seen(&self) -> u128 {
+        self.seen
+    }
+
+    /// Offer an element, which is kept or dropped at random.
+    ///
+    /// # Arguments
+    /// * elem - The element offered
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ReservoirSampler;
+    /// let mut sampler: ReservoirSampler<u32> = ReservoirSampler::with_seed(3, 42);
+    /// for elem in 0..1000 {
+    ///     sampler.offer(elem);
+    /// }
+    /// assert_eq!(sampler.len(), 3);
+    /// assert_eq!(sampler.seen(), 1000);
+    /// assert!(sampler.samples().iter().all(|elem| *elem < 1000));
+    /// ```
+    pub fn offer(&mut self, elem: T) {
+        self.seen += 1;
+        if self.samples.len() < self.capacity {
+            self.samples.push(elem);
+            return;
+        }
+        let idx = self.random_below(self.seen);
+        if idx < self.capacity as u128 {
+            self.samples[idx as usize] = elem;
+        }
+    }
+
+    /// Returns the elements kept, in no particular order.
+    pub fn samples(&self) -> &[T] {
+        &self.samples
+    }
+
+    /// Convert into the elements kept, in no particular order.
+    pub fn into_samples(self) -> Vec<T> {
+        self.samples
+    }
+
+    /// Merge the sample of another stream, the result being a uniform
+    /// sample of both streams.
+    ///
+    /// When the capacities differ, the smaller one is kept, as a larger
+    /// sample could not be uniform.
+    ///
+    /// # Arguments
+    /// * other - The sampler of the other stream
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::ReservoirSampler;
+    /// let mut small: ReservoirSampler<&str> = ReservoirSampler::with_seed(4, 1);
+    /// small.offer("a");
+    /// let mut large: ReservoirSampler<&str> = ReservoirSampler::with_seed(4, 2);
+    /// for _ in 0..10_000 {
+    ///     large.offer("b");
+    /// }
+    /// small.merge(large);
+    /// assert_eq!(small.seen(), 10_001);
+    /// assert_eq!(small.len(), 4);
+    /// ```
+    pub fn merge(&mut self, other: ReservoirSampler<T>) {
+        let mut mine = std::mem::take(&mut self.samples);
+        let mut theirs = other.samples;
+        let (mut mine_left, mut theirs_left) = (self.seen, other.seen);
+        self.seen += other.seen;
+        self.capacity = self.capacity.min(other.capacity);
+        // Every pick comes from a side with probability proportional to
+        // the number of its elements not picked yet.
+        while self.samples.len() < self.capacity && mine_left + theirs_left > 0 {
+            let side = if self.random_below(mine_left + theirs_left) < mine_left {
+                mine_left -= 1;
+                &mut mine
+            } else {
+                theirs_left -= 1;
+                &mut theirs
+            };
+            let idx = self.random_below(side.len() as u128) as usize;
+            let elem = side.swap_remove(idx);
+            self.samples.push(elem);
+        }
+    }
+
+    /// Remove every element, the seed being kept.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.seen = 0;
+    }
+
+    /// Returns a random value in `[0, bound)`, `bound` being greater than 0.
+    fn random_below(&mut self, bound: u128) -> u128 {
+        let high = xorshift(&mut self.seed) as u128;
+        let low = xorshift(&mut self.seed) as u128;
+        ((high << 64) | low) % bound
+    }
+}
@@ -0,0 +1,219 @@
+//! This module implements a run-length encoded vector.
+//!
+//! Consecutive equal values are stored once as a run, along with the
+//! position where the run ends, so a long sequence of few distinct values
+//! takes memory proportional to its number of runs. Reading an element is
+//! a binary search over the ends of the runs, in O(log r) for r runs, and
+//! pushing extends the last run whenever the value is equal to it.
+//!
+use crate::Counter;
+use std::iter::FromIterator;
+use std::ops::Index;
+
+/// Structure that hold a sequence of `T` values as runs of equal values
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RleVec<T> {
+    values: Vec<T>,
+    ends: Vec<usize>,
+}
+
+impl<T: PartialEq> RleVec<T> {
+    /// Create a new empty `RleVec`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::RleVec;
+    /// let states: RleVec<&str> = RleVec::new();
+    /// # assert!(states.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        RleVec {
+            values: Vec::new(),
+            ends: Vec::new(),
+        }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.ends.last().copied().unwrap_or(0)
+    }
+
+    /// Returns `true` if the `RleVec` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Returns the number of runs.
+    pub fn num_runs(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Append an element.
+    ///
+    /// # Arguments
+    /// * value - The element appended
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::RleVec;
+    /// let mut states: RleVec<&str> = RleVec::new();
+    /// states.push("idle");
+    /// states.push("idle");
+    /// states.push("busy");
+    /// states.push("idle");
+    /// assert_eq!(states.len(), 4);
+    /// assert_eq!(states.num_runs(), 3);
+    /// assert_eq!(states[1], "idle");
+    /// assert_eq!(states[2], "busy");
+    /// ```
+    pub fn push(&mut self, value: T) {
+        self.push_n(value, 1);
+    }
+
+    /// Append `count` copies of an element.
+    ///
+    /// # Arguments
+    /// * value - The element appended
+    /// * count - The number of copies
+    pub fn push_n(&mut self, value: T, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let len = self.len();
+        match self.values.last() {
+            Some(last) if *last == value => {
+                if let Some(end) = self.ends.last_mut() {
+                    *end += count;
+                }
+            }
+            _ => {
+                self.values.push(value);
+                self.ends.push(len + count);
+            }
+        }
+    }
+
+    /// Remove the last element, returning it.
+    pub fn pop(&mut self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let end = self.ends.last_mut()?;
+        *end -= 1;
+        let start = if self.ends.len() > 1 {
+            self.ends[self.ends.len() - 2]
+        } else {
+            0
+        };
+        if self.len() == start {
+            let _ = self.ends.pop();
+            return self.values.pop();
+        }
+        self.values.last().cloned()
+    }
+
+    /// Returns the element at position `idx`, `None` if out of bounds.
+    ///
+    /// # Arguments
+    /// * idx - The position of the element
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        let run = self.ends.partition_point(|end| *end <= idx);
+        self.values.get(run)
+    }
+
+    /// Returns the first element, `None` if empty.
+    pub fn first(&self) -> Option<&T> {
+        self.values.first()
+    }
+
+    /// Returns the last element, `None` if empty.
+    pub fn last(&self) -> Option<&T> {
+        self.values.last()
+    }
+
+    /// Iterate over the runs, as a value and the number of times it is
+    /// repeated.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::RleVec;
+    /// let bits: RleVec<u8> = vec![0, 0, 0, 1, 1, 0].into_iter().collect();
+    /// let runs: Vec<(&u8, usize)> = bits.runs().collect();
+    /// assert_eq!(runs, vec![(&0, 3), (&1, 2), (&0, 1)]);
+    /// ```
+    pub fn runs(&self) -> impl Iterator<Item = (&T, usize)> + '_ {
+        let starts = std::iter::once(0).chain(self.ends.iter().copied());
+        self.values
+            .iter()
+            .zip(self.ends.iter().zip(starts))
+            .map(|(value, (end, start))| (value, end - start))
+    }
+
+    /// Iterate over the elements, in order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.runs()
+            .flat_map(|(value, count)| std::iter::repeat_n(value, count))
+    }
+
+    /// Returns a `Counter` of the elements.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::RleVec;
+    /// let weather: RleVec<char> = "sssrrsss".chars().collect();
+    /// let days = weather.to_counter();
+    /// assert_eq!(days[&'s'], 6);
+    /// assert_eq!(days[&'r'], 2);
+    /// ```
+    pub fn to_counter(&self) -> Counter<T>
+    where
+        T: Ord + Clone,
+    {
+        let mut counter: Counter<T> = Counter::new();
+        counter.extend(
+            self.runs()
+                .map(|(value, count)| (value.clone(), count as u128)),
+        );
+        counter
+    }
+
+    /// Remove every element.
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.ends.clear();
+    }
+}
+
+impl<T: PartialEq> FromIterator<T> for RleVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut rle: RleVec<T> = RleVec::new();
+        rle.extend(iter);
+        rle
+    }
+}
+
+impl<T: PartialEq> Extend<T> for RleVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: PartialEq> Index<usize> for RleVec<T> {
+    type Output = T;
+
+    /// Returns the element at position `idx`.
+    ///
+    /// # Panics
+    /// Panics if `idx` is out of bounds.
+    fn index(&self, idx: usize) -> &Self::Output {
+        self.get(idx).expect("index out of bounds")
+    }
+}
+
+impl<T: PartialEq> Default for RleVec<T> {
+    fn default() -> Self {
+        RleVec::new()
+    }
+}
@@ -0,0 +1,185 @@
+//! This module implements aggregates over the last values of a stream.
+//!
+//! A `RollingWindow` keeps the last `n` values pushed, along with their
+//! running sum, so the sum and the mean are read in O(1). The minimum and
+//! the maximum are read in O(1) too, from monotonic deques: a value no
+//! longer matters for the minimum once a smaller value is pushed after it,
+//! so it is dropped, making every push O(1) amortized.
+//!
+use std::collections::VecDeque;
+use std::ops::{Add, Sub};
+
+/// Structure that aggregate the last `T` values of a stream
+#[derive(Debug, Clone)]
+pub struct RollingWindow<T> {
+    values: VecDeque<T>,
+    capacity: usize,
+    sum: T,
+    pushed: u64,
+    mins: VecDeque<(u64, T)>,
+    maxs: VecDeque<(u64, T)>,
+}
+
+impl<T> RollingWindow<T>
+where
+    T: Copy + PartialOrd + Default + Add<Output = T> + Sub<Output = T>,
+{
+    /// Create a new empty `RollingWindow` of length `capacity`.
+    ///
+    /// # Arguments
+    /// * capacity - The number of values aggregated, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::RollingWindow;
+    /// let window: RollingWindow<f64> = RollingWindow::new(60);
+    /// # assert!(window.is_empty());
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        RollingWindow {
+            values: VecDeque::with_capacity(capacity),
+            capacity,
+            sum: T::default(),
+            pushed: 0,
+            mins: VecDeque::new(),
+            maxs: VecDeque::new(),
+        }
+    }
+
+    /// Returns the length of the window.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of values in the window.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if no value was pushed, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns `true` if the window holds `capacity` values, `false`
+    /// otherwise.
+    pub fn is_full(&self) -> bool {
+        self.values.len() == self.capacity
+    }
+
+    /// Push a value, returning the oldest value if it left the window.
+    ///
+    /// # Arguments
+    /// * value - The value pushed
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::RollingWindow;
+    /// let mut window: RollingWindow<i32> = RollingWindow::new(3);
+    /// assert_eq!(window.push(4), None);
+    /// assert_eq!(window.push(1), None);
+    /// assert_eq!(window.push(7), None);
+    /// assert_eq!(window.push(2), Some(4));
+    /// assert_eq!(window.sum(), 10);
+    /// assert_eq!(window.min(), Some(1));
+    /// assert_eq!(window.max(), Some(7));
+    /// ```
+    pub fn push(&mut self, value: T) -> Option<T> {
+        let evicted = if self.is_full() {
+            self.values.pop_front()
+        } else {
+            None
+        };
+        if let Some(evicted) = evicted {
+            self.sum = self.sum - evicted;
+        }
+        self.values.push_back(value);
+        self.sum = self.sum + value;
+
+        let idx = self.pushed;
+        self.pushed += 1;
+        while self.mins.back().is_some_and(|(_, min)| *min >= value) {
+            let _ = self.mins.pop_back();
+        }
+        self.mins.push_back((idx, value));
+        while self.maxs.back().is_some_and(|(_, max)| *max <= value) {
+            let _ = self.maxs.pop_back();
+        }
+        self.maxs.push_back((idx, value));
+        // Values pushed before `oldest` left the window.
+        let oldest = self.pushed - self.values.len() as u64;
+        while self.mins.front().is_some_and(|(idx, _)| *idx < oldest) {
+            let _ = self.mins.pop_front();
+        }
+        while self.maxs.front().is_some_and(|(idx, _)| *idx < oldest) {
+            let _ = self.maxs.pop_front();
+        }
+        evicted
+    }
+
+    /// Returns the sum of the values in the window.
+    pub fn sum(&self) -> T {
+        self.sum
+    }
+
+    /// Returns the mean of the values in the window, `None` if empty.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::RollingWindow;
+    /// // Smooth a noisy metric over its last 4 samples.
+    /// let mut window: RollingWindow<f64> = RollingWindow::new(4);
+    /// let smoothed: Vec<f64> = vec![10.0, 30.0, 20.0, 40.0, 0.0]
+    ///     .into_iter()
+    ///     .map(|sample| {
+    ///         let _ = window.push(sample);
+    ///         window.mean().unwrap()
+    ///     })
+    ///     .collect();
+    /// assert_eq!(smoothed, vec![10.0, 20.0, 20.0, 25.0, 22.5]);
+    /// ```
+    pub fn mean(&self) -> Option<f64>
+    where
+        T: Into<f64>,
+    {
+        if self.values.is_empty() {
+            return None;
+        }
+        Some(self.sum.into() / self.values.len() as f64)
+    }
+
+    /// Returns the smallest value in the window, `None` if empty.
+    pub fn min(&self) -> Option<T> {
+        self.mins.front().map(|(_, min)| *min)
+    }
+
+    /// Returns the largest value in the window, `None` if empty.
+    pub fn max(&self) -> Option<T> {
+        self.maxs.front().map(|(_, max)| *max)
+    }
+
+    /// Iterate over the values in the window, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.values.iter()
+    }
+
+    /// Remove every value.
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.sum = T::default();
+        self.mins.clear();
+        self.maxs.clear();
+    }
+}
+
+impl<T> Extend<T> for RollingWindow<T>
+where
+    T: Copy + PartialOrd + Default + Add<Output = T> + Sub<Output = T>,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            let _ = self.push(value);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! This module implements a segment tree, answering queries over ranges
+//! of a sequence of values.
+//!
+//! Each node of the tree holds the combination of the values below it
+//! under an operation, with the leaves holding the values themselves, so
+//! a range is the combination of O(log n) nodes and updating a value
+//! recomputes the O(log n) nodes above it. The operation is a `Monoid`:
+//! associative, with an identity standing for an empty range. Sums,
+//! minimums and maximums are provided by `SumOp`, `MinOp` and `MaxOp`,
+//! other operations implement the trait. The operation need not be
+//! commutative, values being combined from left to right.
+//!
+use std::fmt::{self, Debug, Formatter};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::ops::{Add, Bound, RangeBounds};
+
+/// Trait of an associative operation over `T` values with an identity
+///
+/// # Examples
+/// ```
+/// # use collectors::{Monoid, SegmentTree};
+/// #[derive(Debug)]
+/// struct Gcd;
+///
+/// impl Monoid<u64> for Gcd {
+///     fn identity() -> u64 {
+///         0
+///     }
+///
+///     fn combine(left: &u64, right: &u64) -> u64 {
+///         let (mut a, mut b) = (*left, *right);
+///         while b != 0 {
+///             let r = a % b;
+///             a = b;
+///             b = r;
+///         }
+///         a
+///     }
+/// }
+///
+/// let tree: SegmentTree<u64, Gcd> = vec![12, 18, 24, 7].into_iter().collect();
+/// assert_eq!(tree.query(0..3), 6);
+/// assert_eq!(tree.query(..), 1);
+/// ```
+pub trait Monoid<T> {
+    /// Returns the identity, the value of an empty range.
+    fn identity() -> T;
+
+    /// Returns the combination of two values, `left` coming first.
+    fn combine(left: &T, right: &T) -> T;
+}
+
+/// Operation summing values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SumOp;
+
+impl<T: Clone + Default + Add<Output = T>> Monoid<T> for SumOp {
+    fn identity() -> T {
+        T::default()
+    }
+
+    fn combine(left: &T, right: &T) -> T {
+        left.clone() + right.clone()
+    }
+}
+
+/// Operation taking the minimum of values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MinOp;
+
+/// Operation taking the maximum of values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MaxOp;
+
+macro_rules! impl_extrema_ops {
+    ($($t:ty => $min:expr, $max:expr);*) => {
+        $(
+            impl Monoid<$t> for MinOp {
+                fn identity() -> $t {
+                    $max
+                }
+
+                fn combine(left: &$t, right: &$t) -> $t {
+                    (*left).min(*right)
+                }
+            }
+
+            impl Monoid<$t> for MaxOp {
+                fn identity() -> $t {
+                    $min
+                }
+
+                fn combine(left: &$t, right: &$t) -> $t {
+                    (*left).max(*right)
+                }
+            }
+        )*
+    };
+}
+
+impl_extrema_ops!(
+    u8 => u8::MIN, u8::MAX;
+    u16 => u16::MIN, u16::MAX;
+    u32 => u32::MIN, u32::MAX;
+    u64 => u64::MIN, u64::MAX;
+    u128 => u128::MIN, u128::MAX;
+    usize => usize::MIN, usize::MAX;
+    i8 => i8::MIN, i8::MAX;
+    i16 => i16::MIN, i16::MAX;
+    i32 => i32::MIN, i32::MAX;
+    i64 => i64::MIN, i64::MAX;
+    i128 => i128::MIN, i128::MAX;
+    isize => isize::MIN, isize::MAX;
+    f32 => f32::NEG_INFINITY, f32::INFINITY;
+    f64 => f64::NEG_INFINITY, f64::INFINITY
+);
+
+/// Structure that answer `Op` queries over ranges of `T` values
+pub struct SegmentTree<T, Op> {
+    nodes: Vec<T>,
+    len: usize,
+    op: PhantomData<Op>,
+}
+
+impl<T: Clone, Op: Monoid<T>> SegmentTree<T, Op> {
+    /// Create a new `SegmentTree` of `len` values equal to the identity.
+    ///
+    /// # Arguments
+    /// * len - The number of values
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{MinOp, SegmentTree};
+    /// let latencies: SegmentTree<u64, MinOp> = SegmentTree::new(1024);
+    /// # assert_eq!(latencies.len(), 1024);
+    /// # assert_eq!(latencies.query(..), u64::MAX);
+    /// ```
+    pub fn new(len: usize) -> Self {
+        SegmentTree {
+            nodes: vec![Op::identity(); 2 * len],
+            len,
+            op: PhantomData,
+        }
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the `SegmentTree` holds no value, `false`
+    /// otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the value at position `idx`.
+    ///
+    /// # Arguments
+    /// * idx - The position, must be lower than the number of values
+    pub fn get(&self, idx: usize) -> &T {
+        assert!(idx < self.len);
+        &self.nodes[self.len + idx]
+    }
+
+    /// Replace the value at position `idx`.
+    ///
+    /// # Arguments
+    /// * idx - The position, must be lower than the number of values
+    /// * value - The new value
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{MaxOp, SegmentTree};
+    /// // Peak queue depth per minute.
+    /// let mut depths: SegmentTree<u32, MaxOp> = vec![3, 9, 4, 1].into_iter().collect();
+    /// assert_eq!(depths.query(2..), 4);
+    /// depths.set(3, 12);
+    /// assert_eq!(depths.query(2..), 12);
+    /// assert_eq!(depths.query(..2), 9);
+    /// ```
+    pub fn set(&mut self, idx: usize, value: T) {
+        assert!(idx < self.len);
+        let mut pos = self.len + idx;
+        self.nodes[pos] = value;
+        while pos > 1 {
+            pos /= 2;
+            self.nodes[pos] = Op::combine(&self.nodes[2 * pos], &self.nodes[2 * pos + 1]);
+        }
+    }
+
+    /// Returns the combination of the values in a range of positions, the
+    /// identity if the range is empty.
+    ///
+    /// # Arguments
+    /// * range - The range of positions, within the values
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{SegmentTree, SumOp};
+    /// let sums: SegmentTree<i64, SumOp> = vec![5, -2, 7, 1].into_iter().collect();
+    /// assert_eq!(sums.query(1..=2), 5);
+    /// assert_eq!(sums.query(2..2), 0);
+    /// ```
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let start = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => end + 1,
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => self.len,
+        };
+        assert!(start <= end && end <= self.len);
+        let mut left = Op::identity();
+        let mut right = Op::identity();
+        let mut start = start + self.len;
+        let mut end = end + self.len;
+        while start < end {
+            if start % 2 == 1 {
+                left = Op::combine(&left, &self.nodes[start]);
+                start += 1;
+            }
+            if end % 2 == 1 {
+                end -= 1;
+                right = Op::combine(&self.nodes[end], &right);
+            }
+            start /= 2;
+            end /= 2;
+        }
+        Op::combine(&left, &right)
+    }
+
+    /// Returns the values, in order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.nodes[self.len..]
+    }
+}
+
+impl<T: Clone, Op: Monoid<T>> FromIterator<T> for SegmentTree<T, Op> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let values: Vec<T> = iter.into_iter().collect();
+        let len = values.len();
+        let mut nodes: Vec<T> = vec![Op::identity(); len];
+        nodes.extend(values);
+        for pos in (1..len).rev() {
+            nodes[pos] = Op::combine(&nodes[2 * pos], &nodes[2 * pos + 1]);
+        }
+        SegmentTree {
+            nodes,
+            len,
+            op: PhantomData,
+        }
+    }
+}
+
+impl<T: Debug, Op> Debug for SegmentTree<T, Op> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SegmentTree")
+            .field("values", &&self.nodes[self.len..])
+            .finish()
+    }
+}
+
+impl<T: Clone, Op> Clone for SegmentTree<T, Op> {
+    fn clone(&self) -> Self {
+        SegmentTree {
+            nodes: self.nodes.clone(),
+            len: self.len,
+            op: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! This module implements copy-on-write sharing of collections.
+//!
+//! A `Shared` holds a collection behind an `Arc`, so cloning it, or
+//! sending a clone to another thread, only bumps a reference count. Reads
+//! go straight through to the collection, while `to_mut` copies it first
+//! if another clone still refers to it, so a large `Counter` or `Bits` is
+//! copied only when someone actually writes, and other holders keep
+//! seeing the version they had.
+//!
+use crate::{Bits, Counter};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Structure that share a `T` value, copying it on write
+#[derive(Debug)]
+pub struct Shared<T> {
+    inner: Arc<T>,
+}
+
+/// A `Counter` shared with copy-on-write
+pub type SharedCounter<T> = Shared<Counter<T>>;
+
+/// A `Bits` stream shared with copy-on-write
+pub type SharedBits = Shared<Bits>;
+
+impl<T> Shared<T> {
+    /// Create a new `Shared` owning a value.
+    ///
+    /// # Arguments
+    /// * value - The value shared
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, SharedCounter};
+    /// let counter: Counter<&str> = vec!["a", "b", "a"].into_iter().collect();
+    /// let shared = SharedCounter::new(counter);
+    /// # assert_eq!(shared[&"a"], 2);
+    /// ```
+    pub fn new(value: T) -> Self {
+        Shared {
+            inner: Arc::new(value),
+        }
+    }
+
+    /// Returns a mutable reference to the value, copying it first if
+    /// other clones refer to it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, SharedCounter};
+    /// let snapshot = SharedCounter::new(vec!["a", "b"].into_iter().collect::<Counter<&str>>());
+    /// let mut working = snapshot.clone();
+    /// assert!(working.ptr_eq(&snapshot));
+    /// working.to_mut().update_from_value("a");
+    /// assert!(!working.ptr_eq(&snapshot));
+    /// assert_eq!(working[&"a"], 2);
+    /// assert_eq!(snapshot[&"a"], 1);
+    /// ```
+    pub fn to_mut(&mut self) -> &mut T
+    where
+        T: Clone,
+    {
+        Arc::make_mut(&mut self.inner)
+    }
+
+    /// Returns `true` if both `Shared` refer to the same value, `false`
+    /// otherwise.
+    ///
+    /// # Arguments
+    /// * other - The other `Shared`
+    pub fn ptr_eq(&self, other: &Shared<T>) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    /// Returns `true` if no other clone refers to the value, so writing
+    /// does not copy it, `false` otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Bits, SharedBits};
+    /// use std::thread;
+    ///
+    /// let stream = SharedBits::new(Bits::from_u8_big_endian(&[0xAB, 0xCD]));
+    /// let readers: Vec<_> = (0..4)
+    ///     .map(|_| {
+    ///         let stream = stream.clone();
+    ///         thread::spawn(move || stream.as_vec_bool().len())
+    ///     })
+    ///     .collect();
+    /// for reader in readers {
+    ///     assert_eq!(reader.join().unwrap(), 16);
+    /// }
+    /// assert!(stream.is_unique());
+    /// ```
+    pub fn is_unique(&self) -> bool {
+        Arc::strong_count(&self.inner) == 1
+    }
+
+    /// Convert into the value, copying it if other clones refer to it.
+    pub fn into_inner(self) -> T
+    where
+        T: Clone,
+    {
+        Arc::try_unwrap(self.inner).unwrap_or_else(|inner| (*inner).clone())
+    }
+}
+
+impl<T> Deref for Shared<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> AsRef<T> for Shared<T> {
+    fn as_ref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T> From<T> for Shared<T> {
+    fn from(value: T) -> Self {
+        Shared::new(value)
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Shared {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for Shared<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || *self.inner == *other.inner
+    }
+}
+
+impl<T: Eq> Eq for Shared<T> {}
+
+impl<T: Default> Default for Shared<T> {
+    fn default() -> Self {
+        Shared::new(T::default())
+    }
+}
@@ -0,0 +1,264 @@
+//! This module implements a `Counter` whose counts may go negative.
+//!
+//! As with python `Counter` after a `subtract`, counts are signed so that
+//! debits and credits can be modeled. Views meant to represent a multiset,
+//! such as `elements`, skip the elements whose count is not positive.
+//!
+use crate::counter::signed;
+use crate::Counter;
+use std::borrow::Borrow;
+use std::collections::btree_map::{BTreeMap, Iter};
+use std::convert::TryFrom;
+use std::iter::{self, FromIterator};
+use std::ops::Index;
+
+/// Structure that count occurences of `T` elements with signed counts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedCounter<T> {
+    state: BTreeMap<T, i128>,
+}
+
+impl<T> SignedCounter<T> {
+    /// Create a new empty `SignedCounter`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SignedCounter;
+    /// let counter: SignedCounter<char> = SignedCounter::new();
+    /// # assert_eq!(counter.len(), 0);
+    /// ```
+    pub fn new() -> Self {
+        SignedCounter {
+            state: BTreeMap::new(),
+        }
+    }
+
+    /// Iterate over the elements and their counts, including non positive ones.
+    pub fn iter(&self) -> Iter<'_, T, i128> {
+        self.state.iter()
+    }
+
+    /// Returns the number of elements in the map, including those whose
+    /// count is not positive.
+    pub fn len(&self) -> usize {
+        self.state.len()
+    }
+
+    /// Returns `true` if the `SignedCounter` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.state.is_empty()
+    }
+}
+
+impl<T: Ord> SignedCounter<T> {
+    /// Add `count` to the count of an element, `count` may be negative.
+    ///
+    /// # Panics
+    /// Panics if the count overflows an `i128`, which also applies to the
+    /// updates and subtractions below.
+    ///
+    /// # Arguments
+    /// * elem - The element whose count is changed
+    /// * count - The signed amount added to the count
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SignedCounter;
+    /// let mut balance: SignedCounter<&str> = SignedCounter::new();
+    /// balance.add("alice", 10);
+    /// balance.add("alice", -25);
+    /// assert_eq!(balance[&"alice"], -15);
+    /// ```
+    pub fn add(&mut self, elem: T, count: i128) {
+        let current = self.state.entry(elem).or_insert(0);
+        *current = current.checked_add(count).expect("count overflow");
+    }
+
+    /// Update the `SignedCounter` with a value, incrementing its count.
+    ///
+    /// # Arguments
+    /// * elem - A value used to update the `SignedCounter`
+    pub fn update_from_value(&mut self, elem: T) {
+        self.add(elem, 1);
+    }
+
+    /// Update the `SignedCounter` with an iterator, incrementing the count
+    /// of every yielded value.
+    ///
+    /// # Arguments
+    /// * iter - An iterator used to update the `SignedCounter`
+    pub fn update_from_iter<I>(&mut self, iter: I)
+    where
+        I: Iterator<Item = T>,
+    {
+        for elem in iter {
+            self.add(elem, 1);
+        }
+    }
+
+    /// Update the `SignedCounter` with a value, decrementing its count.
+    ///
+    /// # Arguments
+    /// * elem - A value used to update the `SignedCounter`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SignedCounter;
+    /// let mut counter: SignedCounter<char> = SignedCounter::new();
+    /// counter.subtract_from_value('a');
+    /// assert_eq!(counter[&'a'], -1);
+    /// ```
+    pub fn subtract_from_value(&mut self, elem: T) {
+        self.add(elem, -1);
+    }
+
+    /// Update the `SignedCounter` with an iterator, decrementing the count
+    /// of every yielded value.
+    ///
+    /// # Arguments
+    /// * iter - An iterator used to update the `SignedCounter`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SignedCounter;
+    /// let mut counter: SignedCounter<char> = "aab".chars().collect();
+    /// counter.subtract_from_iter("abbb".chars());
+    /// assert_eq!(counter[&'a'], 1);
+    /// assert_eq!(counter[&'b'], -2);
+    /// ```
+    pub fn subtract_from_iter<I>(&mut self, iter: I)
+    where
+        I: Iterator<Item = T>,
+    {
+        for elem in iter {
+            self.add(elem, -1);
+        }
+    }
+
+    /// Add the counts of a `Counter`.
+    ///
+    /// # Arguments
+    /// * other - The `Counter` whose counts are added
+    pub fn update_from_counter(&mut self, other: &Counter<T>)
+    where
+        T: Clone,
+    {
+        for (key, count) in other.iter() {
+            self.add(key.clone(), signed(*count));
+        }
+    }
+
+    /// Subtract the counts of a `Counter`.
+    ///
+    /// # Arguments
+    /// * other - The `Counter` whose counts are subtracted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, SignedCounter};
+    /// let mut stock: SignedCounter<&str> = SignedCounter::new();
+    /// stock.add("apple", 3);
+    /// let order: Counter<&str> = vec!["apple"; 5].into_iter().collect();
+    /// stock.subtract_from_counter(&order);
+    /// assert_eq!(stock[&"apple"], -2);
+    /// ```
+    pub fn subtract_from_counter(&mut self, other: &Counter<T>)
+    where
+        T: Clone,
+    {
+        for (key, count) in other.iter() {
+            self.add(key.clone(), -signed(*count));
+        }
+    }
+
+    /// Iterate over each element as many times as its count, skipping the
+    /// elements whose count is not positive.
+    ///
+    /// # Panics
+    /// Panics if a count doesn't fit in a `usize`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SignedCounter;
+    /// let mut counter: SignedCounter<char> = SignedCounter::new();
+    /// counter.add('a', 2);
+    /// counter.add('b', -1);
+    /// counter.add('c', 1);
+    /// let elements: String = counter.elements().collect();
+    /// assert_eq!(elements, "aac");
+    /// ```
+    pub fn elements(&self) -> impl Iterator<Item = &T> + '_ {
+        self.state
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .flat_map(|(key, count)| {
+                iter::repeat_n(key, usize::try_from(*count).expect("count exceeds usize"))
+            })
+    }
+
+    /// Returns a `Counter` holding only the elements whose count is positive.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SignedCounter;
+    /// let mut counter: SignedCounter<char> = SignedCounter::new();
+    /// counter.add('a', 2);
+    /// counter.add('b', -1);
+    /// let positive = counter.positive();
+    /// assert_eq!(positive[&'a'], 2);
+    /// assert_eq!(positive.len(), 1);
+    /// ```
+    pub fn positive(&self) -> Counter<T>
+    where
+        T: Clone,
+    {
+        let mut counter: Counter<T> = Counter::new();
+        counter.extend(
+            self.state
+                .iter()
+                .filter(|(_, count)| **count > 0)
+                .map(|(key, count)| (key.clone(), *count as u128)),
+        );
+        counter
+    }
+}
+
+impl<T: Ord> From<Counter<T>> for SignedCounter<T> {
+    fn from(counter: Counter<T>) -> Self {
+        SignedCounter {
+            state: counter
+                .into_iter()
+                .map(|(key, count)| (key, signed(count)))
+                .collect(),
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SignedCounter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter: SignedCounter<T> = SignedCounter::new();
+        counter.update_from_iter(iter.into_iter());
+        counter
+    }
+}
+
+impl<T, Q> Index<&Q> for SignedCounter<T>
+where
+    T: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = i128;
+
+    fn index(&self, index: &Q) -> &Self::Output {
+        match self.state.get(index) {
+            Some(value) => value,
+            None => &0,
+        }
+    }
+}
+
+impl<T> Default for SignedCounter<T> {
+    fn default() -> Self {
+        SignedCounter::new()
+    }
+}
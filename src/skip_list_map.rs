@@ -0,0 +1,419 @@
+//! This module implements an ordered map as a skip list.
+//!
+//! Entries are linked in key order on the bottom level, each one also being
+//! linked on the levels above with a probability halving at every level, so
+//! that searches skip over most of the entries and take O(log n) expected
+//! time. The last entry of every level is tracked, so appending keys larger
+//! than every key present, as when collecting timestamps in order, does not
+//! search at all. Nodes live in a vector and link by index, which keeps the
+//! structure clear of unsafe code.
+//!
+use crate::min_hash::xorshift;
+use std::borrow::Borrow;
+use std::iter::FromIterator;
+use std::ops::{Bound, Index, RangeBounds};
+
+/// The maximum number of levels of the list.
+const MAX_HEIGHT: usize = 32;
+
+#[derive(Debug, Clone)]
+struct Node<K, V> {
+    key: K,
+    value: V,
+    next: Vec<Option<usize>>,
+}
+
+/// Structure that map `K` keys to `V` values in key order
+#[derive(Debug, Clone)]
+pub struct SkipListMap<K, V> {
+    nodes: Vec<Option<Node<K, V>>>,
+    free: Vec<usize>,
+    head: Vec<Option<usize>>,
+    tails: Vec<Option<usize>>,
+    len: usize,
+    seed: u64,
+}
+
+impl<K: Ord, V> SkipListMap<K, V> {
+    /// Create a new empty `SkipListMap`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SkipListMap;
+    /// let map: SkipListMap<u64, &str> = SkipListMap::new();
+    /// # assert!(map.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        SkipListMap {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            head: Vec::new(),
+            tails: Vec::new(),
+            len: 0,
+            seed: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the `SkipListMap` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert a value, returning the previous value of the key if any.
+    ///
+    /// Keys larger than every key present are appended without searching.
+    ///
+    /// # Arguments
+    /// * key - The key of the value
+    /// * value - The value inserted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SkipListMap;
+    /// let mut map: SkipListMap<u32, char> = SkipListMap::new();
+    /// assert_eq!(map.insert(3, 'c'), None);
+    /// assert_eq!(map.insert(1, 'a'), None);
+    /// assert_eq!(map.insert(3, 'C'), Some('c'));
+    /// assert_eq!(map.iter().collect::<Vec<_>>(), vec![(&1, &'a'), (&3, &'C')]);
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut update = match self.tails.first() {
+            Some(Some(last)) if self.node(*last).key < key => self.tails.clone(),
+            _ => self.predecessors(|other| *other < key),
+        };
+        if let Some(pred) = update.first() {
+            if let Some(idx) = self.next(*pred, 0) {
+                if self.node(idx).key == key {
+                    return Some(std::mem::replace(&mut self.node_mut(idx).value, value));
+                }
+            }
+        }
+        let height = self.random_height();
+        while self.head.len() < height {
+            self.head.push(None);
+            self.tails.push(None);
+            update.push(None);
+        }
+        let next: Vec<Option<usize>> = (0..height)
+            .map(|level| self.next(update[level], level))
+            .collect();
+        let node = Node { key, value, next };
+        let idx = match self.free.pop() {
+            Some(idx) => {
+                self.nodes[idx] = Some(node);
+                idx
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        };
+        for (level, pred) in update.into_iter().enumerate().take(height) {
+            self.set_next(pred, level, Some(idx));
+            if self.node(idx).next[level].is_none() {
+                self.tails[level] = Some(idx);
+            }
+        }
+        self.len += 1;
+        None
+    }
+
+    /// Returns the value of a key, `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.find(key)?;
+        Some(&self.node(idx).value)
+    }
+
+    /// Returns a mutable reference to the value of a key, `None` if it is
+    /// absent.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.find(key)?;
+        Some(&mut self.node_mut(idx).value)
+    }
+
+    /// Returns `true` if the key is present, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Remove a key, returning its value if it was present.
+    ///
+    /// # Arguments
+    /// * key - A borrowed form of the key
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SkipListMap;
+    /// let mut map: SkipListMap<&str, u32> = vec![("a", 1), ("b", 2)].into_iter().collect();
+    /// assert_eq!(map.remove("a"), Some(1));
+    /// assert_eq!(map.remove("a"), None);
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let update = self.predecessors(|other| other.borrow() < key);
+        let idx = self
+            .next(*update.first()?, 0)
+            .filter(|idx| self.node(*idx).key.borrow() == key)?;
+        Some(self.unlink(idx, &update).1)
+    }
+
+    /// Returns the entry of smallest key, `None` if empty.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        let idx = (*self.head.first()?)?;
+        let node = self.node(idx);
+        Some((&node.key, &node.value))
+    }
+
+    /// Returns the entry of largest key, `None` if empty.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        let idx = (*self.tails.first()?)?;
+        let node = self.node(idx);
+        Some((&node.key, &node.value))
+    }
+
+    /// Remove the entry of smallest key, returning it.
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let idx = (*self.head.first()?)?;
+        let update = vec![None; self.head.len()];
+        Some(self.unlink(idx, &update))
+    }
+
+    /// Remove the entry of largest key, returning it.
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let idx = (*self.tails.first()?)?;
+        let update = self.predecessors(|other| *other < self.node(idx).key);
+        Some(self.unlink(idx, &update))
+    }
+
+    /// Iterate over the entries within `range`, in key order.
+    ///
+    /// # Arguments
+    /// * range - The range of keys to iterate over
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SkipListMap;
+    /// // Events keyed by timestamp, appended in order.
+    /// let mut events: SkipListMap<u64, &str> = SkipListMap::new();
+    /// for (timestamp, event) in vec![(10, "start"), (20, "tick"), (30, "tick"), (40, "stop")] {
+    ///     let _ = events.insert(timestamp, event);
+    /// }
+    /// let window: Vec<(&u64, &&str)> = events.range(15..=30).collect();
+    /// assert_eq!(window, vec![(&20, &"tick"), (&30, &"tick")]);
+    /// assert_eq!(events.range(..15).count(), 1);
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> impl Iterator<Item = (&K, &V)> + '_
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let first = match range.start_bound() {
+            Bound::Included(start) => self.first_after(|key| key.borrow() < start),
+            Bound::Excluded(start) => self.first_after(|key| key.borrow() <= start),
+            Bound::Unbounded => self.head.first().cloned().flatten(),
+        };
+        let stop = match range.end_bound() {
+            Bound::Included(end) => self.first_after(|key| key.borrow() <= end),
+            Bound::Excluded(end) => self.first_after(|key| key.borrow() < end),
+            Bound::Unbounded => None,
+        };
+        // A start past the end would otherwise never reach `stop`.
+        let first =
+            first.filter(|idx| Some(*idx) != stop && range.contains(self.node(*idx).key.borrow()));
+        self.walk(first, stop)
+    }
+
+    /// Iterate over the entries, in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.walk(self.head.first().cloned().flatten(), None)
+    }
+
+    /// Iterate over the keys, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Iterate over the values, in key order.
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Remove every entry.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.head.clear();
+        self.tails.clear();
+        self.len = 0;
+    }
+
+    fn node(&self, idx: usize) -> &Node<K, V> {
+        self.nodes[idx].as_ref().expect("the node is linked")
+    }
+
+    fn node_mut(&mut self, idx: usize) -> &mut Node<K, V> {
+        self.nodes[idx].as_mut().expect("the node is linked")
+    }
+
+    /// Returns the node following `from` on `level`, `None` standing for
+    /// the head of the list.
+    fn next(&self, from: Option<usize>, level: usize) -> Option<usize> {
+        match from {
+            Some(idx) => self.node(idx).next[level],
+            None => self.head[level],
+        }
+    }
+
+    fn set_next(&mut self, from: Option<usize>, level: usize, to: Option<usize>) {
+        match from {
+            Some(idx) => self.node_mut(idx).next[level] = to,
+            None => self.head[level] = to,
+        }
+    }
+
+    /// Returns, for every level, the last node whose key is `before` the
+    /// searched one, `None` standing for the head of the list.
+    fn predecessors<F>(&self, before: F) -> Vec<Option<usize>>
+    where
+        F: Fn(&K) -> bool,
+    {
+        let mut update: Vec<Option<usize>> = vec![None; self.head.len()];
+        let mut current: Option<usize> = None;
+        for level in (0..self.head.len()).rev() {
+            while let Some(next) = self.next(current, level) {
+                if !before(&self.node(next).key) {
+                    break;
+                }
+                current = Some(next);
+            }
+            update[level] = current;
+        }
+        update
+    }
+
+    /// Returns the first node whose key is not `before` the searched one.
+    fn first_after<F>(&self, before: F) -> Option<usize>
+    where
+        F: Fn(&K) -> bool,
+    {
+        let update = self.predecessors(before);
+        self.next(*update.first()?, 0)
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.first_after(|other| other.borrow() < key)
+            .filter(|idx| self.node(*idx).key.borrow() == key)
+    }
+
+    /// Unlink the node `idx`, whose predecessors are `update`.
+    fn unlink(&mut self, idx: usize, update: &[Option<usize>]) -> (K, V) {
+        let node = self.nodes[idx].take().expect("the node is linked");
+        self.free.push(idx);
+        for (level, next) in node.next.iter().enumerate() {
+            self.set_next(update[level], level, *next);
+            if self.tails[level] == Some(idx) {
+                self.tails[level] = update[level];
+            }
+        }
+        while self.head.last() == Some(&None) {
+            let _ = self.head.pop();
+            let _ = self.tails.pop();
+        }
+        self.len -= 1;
+        (node.key, node.value)
+    }
+
+    /// Iterate over the nodes from `first` until `stop`, excluded.
+    fn walk(
+        &self,
+        first: Option<usize>,
+        stop: Option<usize>,
+    ) -> impl Iterator<Item = (&K, &V)> + '_ {
+        let mut current = first;
+        std::iter::from_fn(move || {
+            let idx = current.filter(|idx| Some(*idx) != stop)?;
+            let node = self.node(idx);
+            current = node.next[0];
+            Some((&node.key, &node.value))
+        })
+    }
+
+    /// Draw the height of a new node, every level being kept with
+    /// probability one half.
+    fn random_height(&mut self) -> usize {
+        (xorshift(&mut self.seed).trailing_ones() as usize + 1).min(MAX_HEIGHT)
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for SkipListMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map: SkipListMap<K, V> = SkipListMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for SkipListMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            let _ = self.insert(key, value);
+        }
+    }
+}
+
+impl<K, V, Q> Index<&Q> for SkipListMap<K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    /// Returns the value of a key.
+    ///
+    /// # Panics
+    /// Panics if the key is absent.
+    fn index(&self, key: &Q) -> &Self::Output {
+        self.get(key).expect("key not found")
+    }
+}
+
+impl<K: Ord, V> Default for SkipListMap<K, V> {
+    fn default() -> Self {
+        SkipListMap::new()
+    }
+}
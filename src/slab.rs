@@ -0,0 +1,276 @@
+//! This module implements a slab, an arena handing out stable keys.
+//!
+//! Elements are stored in a vector and referred to by their position,
+//! which never changes while the element is held, so other collections can
+//! reference them by a plain `usize`. Removing an element leaves a vacant
+//! slot chained into a free list, and the next insertion reuses the slot
+//! removed last, so both are O(1) and the vector only grows when no slot
+//! is vacant.
+//!
+use std::iter::FromIterator;
+use std::ops::{Index, IndexMut};
+
+#[derive(Debug, Clone)]
+enum Slot<T> {
+    Occupied(T),
+    Vacant(Option<usize>),
+}
+
+/// Structure that hold `T` elements under stable `usize` keys
+#[derive(Debug, Clone)]
+pub struct Slab<T> {
+    slots: Vec<Slot<T>>,
+    free: Option<usize>,
+    len: usize,
+}
+
+impl<T> Slab<T> {
+    /// Create a new empty `Slab`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Slab;
+    /// let frames: Slab<Vec<u8>> = Slab::new();
+    /// # assert!(frames.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Slab {
+            slots: Vec::new(),
+            free: None,
+            len: 0,
+        }
+    }
+
+    /// Create a new empty `Slab` with room for `capacity` elements.
+    ///
+    /// # Arguments
+    /// * capacity - The number of elements held without reallocating
+    pub fn with_capacity(capacity: usize) -> Self {
+        Slab {
+            slots: Vec::with_capacity(capacity),
+            free: None,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the `Slab` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the key the next inserted element will get.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Slab;
+    /// // A node knowing its own key.
+    /// let mut nodes: Slab<(usize, &str)> = Slab::new();
+    /// let key = nodes.vacant_key();
+    /// assert_eq!(nodes.insert((key, "root")), key);
+    /// assert_eq!(nodes[key].0, key);
+    /// ```
+    pub fn vacant_key(&self) -> usize {
+        self.free.unwrap_or(self.slots.len())
+    }
+
+    /// Insert an element, returning its key.
+    ///
+    /// # Arguments
+    /// * elem - The element inserted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Slab;
+    /// let mut frames: Slab<&str> = Slab::new();
+    /// let first = frames.insert("SYN");
+    /// let second = frames.insert("ACK");
+    /// assert_eq!(frames.remove(first), Some("SYN"));
+    /// let third = frames.insert("FIN");
+    /// assert_eq!(third, first);
+    /// assert_eq!(frames[second], "ACK");
+    /// assert_eq!(frames.len(), 2);
+    /// ```
+    pub fn insert(&mut self, elem: T) -> usize {
+        self.len += 1;
+        match self.free {
+            Some(key) => {
+                let slot = std::mem::replace(&mut self.slots[key], Slot::Occupied(elem));
+                if let Slot::Vacant(next) = slot {
+                    self.free = next;
+                }
+                key
+            }
+            None => {
+                self.slots.push(Slot::Occupied(elem));
+                self.slots.len() - 1
+            }
+        }
+    }
+
+    /// Returns a reference to the element of a key, `None` if absent.
+    ///
+    /// # Arguments
+    /// * key - The key of the element
+    pub fn get(&self, key: usize) -> Option<&T> {
+        match self.slots.get(key)? {
+            Slot::Occupied(elem) => Some(elem),
+            Slot::Vacant(_) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the element of a key, `None` if
+    /// absent.
+    ///
+    /// # Arguments
+    /// * key - The key of the element
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        match self.slots.get_mut(key)? {
+            Slot::Occupied(elem) => Some(elem),
+            Slot::Vacant(_) => None,
+        }
+    }
+
+    /// Returns `true` if an element is held under the key, `false`
+    /// otherwise.
+    ///
+    /// # Arguments
+    /// * key - The key looked up
+    pub fn contains(&self, key: usize) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Remove the element of a key, returning it, its key being reused by
+    /// a later insertion.
+    ///
+    /// # Arguments
+    /// * key - The key of the element
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        match self.slots.get(key)? {
+            Slot::Occupied(_) => {}
+            Slot::Vacant(_) => return None,
+        }
+        let slot = std::mem::replace(&mut self.slots[key], Slot::Vacant(self.free));
+        self.free = Some(key);
+        self.len -= 1;
+        match slot {
+            Slot::Occupied(elem) => Some(elem),
+            Slot::Vacant(_) => None,
+        }
+    }
+
+    /// Keep only the elements for which `f` returns `true`.
+    ///
+    /// # Arguments
+    /// * f - The predicate over the keys and elements
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &mut T) -> bool,
+    {
+        for key in 0..self.slots.len() {
+            let keep = match &mut self.slots[key] {
+                Slot::Occupied(elem) => f(key, elem),
+                Slot::Vacant(_) => true,
+            };
+            if !keep {
+                let _ = self.remove(key);
+            }
+        }
+    }
+
+    /// Iterate over the keys and elements, in key order.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Slab;
+    /// let mut slab: Slab<char> = "abc".chars().collect();
+    /// let _ = slab.remove(1);
+    /// let held: Vec<(usize, &char)> = slab.iter().collect();
+    /// assert_eq!(held, vec![(0, &'a'), (2, &'c')]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(key, slot)| match slot {
+                Slot::Occupied(elem) => Some((key, elem)),
+                Slot::Vacant(_) => None,
+            })
+    }
+
+    /// Iterate over the keys and mutable elements, in key order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (usize, &mut T)> + '_ {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(key, slot)| match slot {
+                Slot::Occupied(elem) => Some((key, elem)),
+                Slot::Vacant(_) => None,
+            })
+    }
+
+    /// Iterate over the keys, in order.
+    pub fn keys(&self) -> impl Iterator<Item = usize> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Iterate over the elements, in key order.
+    pub fn values(&self) -> impl Iterator<Item = &T> + '_ {
+        self.iter().map(|(_, elem)| elem)
+    }
+
+    /// Remove every element, keys starting again from 0.
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.free = None;
+        self.len = 0;
+    }
+}
+
+impl<T> FromIterator<T> for Slab<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut slab: Slab<T> = Slab::new();
+        slab.extend(iter);
+        slab
+    }
+}
+
+impl<T> Extend<T> for Slab<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            let _ = self.insert(elem);
+        }
+    }
+}
+
+impl<T> Index<usize> for Slab<T> {
+    type Output = T;
+
+    /// Returns the element of a key.
+    ///
+    /// # Panics
+    /// Panics if no element is held under the key.
+    fn index(&self, key: usize) -> &Self::Output {
+        self.get(key).expect("no element for this key")
+    }
+}
+
+impl<T> IndexMut<usize> for Slab<T> {
+    /// Returns the element of a key, mutably.
+    ///
+    /// # Panics
+    /// Panics if no element is held under the key.
+    fn index_mut(&mut self, key: usize) -> &mut Self::Output {
+        self.get_mut(key).expect("no element for this key")
+    }
+}
+
+impl<T> Default for Slab<T> {
+    fn default() -> Self {
+        Slab::new()
+    }
+}
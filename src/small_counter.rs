@@ -0,0 +1,246 @@
+//! This module implements a `Counter` storing few elements inline.
+//!
+//! Up to `N` distinct elements are counted in an array held by the
+//! `SmallCounter` itself, sorted by key, so counting the handful of
+//! categories of a record allocates nothing and scans a few contiguous
+//! entries. The first element beyond `N` spills every count into a
+//! `Counter`, which holds them from then on.
+//!
+use crate::Counter;
+use std::borrow::Borrow;
+use std::iter::FromIterator;
+use std::ops::Index;
+
+#[derive(Debug, Clone)]
+enum Storage<T, const N: usize> {
+    Inline {
+        entries: [Option<(T, u128)>; N],
+        len: usize,
+    },
+    Spilled(Counter<T>),
+}
+
+/// Structure that count occurences of `T` elements, up to `N` distinct
+/// ones without allocating
+#[derive(Debug, Clone)]
+pub struct SmallCounter<T, const N: usize> {
+    storage: Storage<T, N>,
+}
+
+impl<T: Ord, const N: usize> SmallCounter<T, N> {
+    /// Create a new empty `SmallCounter`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SmallCounter;
+    /// let counter: SmallCounter<&str, 4> = SmallCounter::new();
+    /// # assert!(counter.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        SmallCounter {
+            storage: Storage::Inline {
+                entries: std::array::from_fn(|_| None),
+                len: 0,
+            },
+        }
+    }
+
+    /// Returns the number of distinct elements.
+    pub fn len(&self) -> usize {
+        match &self.storage {
+            Storage::Inline { len, .. } => *len,
+            Storage::Spilled(counter) => counter.len(),
+        }
+    }
+
+    /// Returns `true` if no element was counted, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the counts are still held inline, `false` once
+    /// they spilled into a `Counter`.
+    pub fn is_inline(&self) -> bool {
+        match self.storage {
+            Storage::Inline { .. } => true,
+            Storage::Spilled(_) => false,
+        }
+    }
+
+    /// Count an element once.
+    ///
+    /// # Arguments
+    /// * elem - The element counted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SmallCounter;
+    /// let mut counter: SmallCounter<char, 2> = SmallCounter::new();
+    /// counter.update_from_value('b');
+    /// counter.update_from_value('a');
+    /// counter.update_from_value('b');
+    /// assert!(counter.is_inline());
+    /// counter.update_from_value('c');
+    /// assert!(!counter.is_inline());
+    /// assert_eq!(counter[&'b'], 2);
+    /// assert_eq!(counter.len(), 3);
+    /// ```
+    pub fn update_from_value(&mut self, elem: T) {
+        self.add(elem, 1);
+    }
+
+    /// Count every element of an iterator.
+    ///
+    /// # Arguments
+    /// * iter - An iterator over the elements counted
+    pub fn update_from_iter<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for elem in iter {
+            self.add(elem, 1);
+        }
+    }
+
+    /// Add `count` occurences of an element.
+    ///
+    /// # Arguments
+    /// * elem - The element counted
+    /// * count - The number of occurences added
+    ///
+    /// # Panics
+    /// Panics if the count of the element overflows.
+    pub fn add(&mut self, elem: T, count: u128) {
+        let (entries, len) = match &mut self.storage {
+            Storage::Inline { entries, len } => (entries, len),
+            Storage::Spilled(counter) => {
+                counter.extend(Some((elem, count)));
+                return;
+            }
+        };
+        let idx = entries[..*len].partition_point(|entry| entry_key(entry) < &elem);
+        if let Some((key, current)) = entries[..*len].get_mut(idx).and_then(Option::as_mut) {
+            if *key == elem {
+                *current = current.checked_add(count).expect("count overflow");
+                return;
+            }
+        }
+        if *len == N {
+            let mut counter: Counter<T> = Counter::new();
+            counter.extend(entries.iter_mut().filter_map(Option::take));
+            counter.extend(Some((elem, count)));
+            self.storage = Storage::Spilled(counter);
+            return;
+        }
+        entries[idx..=*len].rotate_right(1);
+        entries[idx] = Some((elem, count));
+        *len += 1;
+    }
+
+    /// Returns the count of an element, 0 if it was never counted.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element
+    pub fn get<Q>(&self, elem: &Q) -> u128
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        *self.count_ref(elem)
+    }
+
+    /// Returns the sum of the counts.
+    pub fn total(&self) -> u128 {
+        self.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Iterate over the elements and their counts, in key order.
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (&T, u128)> + '_> {
+        match &self.storage {
+            Storage::Inline { entries, len } => Box::new(
+                entries[..*len]
+                    .iter()
+                    .flatten()
+                    .map(|(key, count)| (key, *count)),
+            ),
+            Storage::Spilled(counter) => Box::new(counter.iter().map(|(key, count)| (key, *count))),
+        }
+    }
+
+    /// Convert into a `Counter` of the same counts.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, SmallCounter};
+    /// let small: SmallCounter<char, 8> = "hello".chars().collect();
+    /// let counter: Counter<char> = "hello".chars().collect();
+    /// assert_eq!(small.into_counter(), counter);
+    /// ```
+    pub fn into_counter(self) -> Counter<T> {
+        match self.storage {
+            Storage::Inline { entries, .. } => {
+                let mut counter: Counter<T> = Counter::new();
+                counter.extend(IntoIterator::into_iter(entries).flatten());
+                counter
+            }
+            Storage::Spilled(counter) => counter,
+        }
+    }
+
+    /// Remove every count, going back to inline storage.
+    pub fn clear(&mut self) {
+        *self = SmallCounter::new();
+    }
+
+    fn count_ref<Q>(&self, elem: &Q) -> &u128
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match &self.storage {
+            Storage::Inline { entries, len } => entries[..*len]
+                .iter()
+                .flatten()
+                .find(|(key, _)| key.borrow() == elem)
+                .map_or(&0, |(_, count)| count),
+            Storage::Spilled(counter) => &counter[elem],
+        }
+    }
+}
+
+/// Returns the key of an inline entry, which must be occupied.
+fn entry_key<T>(entry: &Option<(T, u128)>) -> &T {
+    &entry.as_ref().expect("the entry is occupied").0
+}
+
+impl<T: Ord, const N: usize> FromIterator<T> for SmallCounter<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter: SmallCounter<T, N> = SmallCounter::new();
+        counter.update_from_iter(iter);
+        counter
+    }
+}
+
+impl<T: Ord, const N: usize> Extend<T> for SmallCounter<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.update_from_iter(iter);
+    }
+}
+
+impl<T, Q, const N: usize> Index<&Q> for SmallCounter<T, N>
+where
+    T: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = u128;
+
+    fn index(&self, index: &Q) -> &Self::Output {
+        self.count_ref(index)
+    }
+}
+
+impl<T: Ord, const N: usize> Default for SmallCounter<T, N> {
+    fn default() -> Self {
+        SmallCounter::new()
+    }
+}
@@ -0,0 +1,309 @@
+//! This module implements a sorted collection keeping duplicate elements.
+//!
+//! Where a `Counter` only remembers how many times an element was seen, a
+//! `SortedMultiSet` stores every element inserted, so elements comparing
+//! equal but carrying other data, such as records ordered by a single
+//! field, are all kept. Equal elements stay in insertion order. Elements
+//! are held in a sorted vector: positional queries such as `nth` and
+//! `rank` are immediate or logarithmic, at the cost of a linear time
+//! insertion.
+//!
+use crate::Counter;
+use std::borrow::Borrow;
+use std::iter::FromIterator;
+use std::ops::{Bound, RangeBounds};
+
+/// Structure that hold sorted `T` elements, duplicates included
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortedMultiSet<T> {
+    elems: Vec<T>,
+}
+
+impl<T: Ord> SortedMultiSet<T> {
+    /// Create a new empty `SortedMultiSet`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SortedMultiSet;
+    /// let set: SortedMultiSet<u32> = SortedMultiSet::new();
+    /// # assert!(set.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        SortedMultiSet { elems: Vec::new() }
+    }
+
+    /// Returns the number of elements, duplicates included.
+    pub fn len(&self) -> usize {
+        self.elems.len()
+    }
+
+    /// Returns `true` if the `SortedMultiSet` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.elems.is_empty()
+    }
+
+    /// Add an element, after the elements equal to it.
+    ///
+    /// # Arguments
+    /// * elem - The element added
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SortedMultiSet;
+    /// # use std::cmp::Ordering;
+    /// // Jobs ordered by priority only.
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Job(u32, &'static str);
+    /// impl Ord for Job {
+    ///     fn cmp(&self, other: &Self) -> Ordering {
+    ///         self.0.cmp(&other.0)
+    ///     }
+    /// }
+    /// impl PartialOrd for Job {
+    ///     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    ///         Some(self.cmp(other))
+    ///     }
+    /// }
+    /// let mut jobs: SortedMultiSet<Job> = SortedMultiSet::new();
+    /// jobs.insert(Job(2, "build"));
+    /// jobs.insert(Job(1, "fetch"));
+    /// jobs.insert(Job(2, "test"));
+    /// let names: Vec<&str> = jobs.iter().map(|job| job.1).collect();
+    /// assert_eq!(names, vec!["fetch", "build", "test"]);
+    /// ```
+    pub fn insert(&mut self, elem: T) {
+        let idx = self.elems.partition_point(|other| *other <= elem);
+        self.elems.insert(idx, elem);
+    }
+
+    /// Remove the earliest inserted element equal to `elem`, returning it.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SortedMultiSet;
+    /// let mut set: SortedMultiSet<char> = "abba".chars().collect();
+    /// assert_eq!(set.remove_one(&'a'), Some('a'));
+    /// assert_eq!(set.count(&'a'), 1);
+    /// assert_eq!(set.remove_one(&'z'), None);
+    /// ```
+    pub fn remove_one<Q>(&mut self, elem: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.rank(elem);
+        if self.elems.get(idx)?.borrow() != elem {
+            return None;
+        }
+        Some(self.elems.remove(idx))
+    }
+
+    /// Remove every element equal to `elem`, returning them in insertion
+    /// order.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element
+    pub fn remove_all<Q>(&mut self, elem: &Q) -> Vec<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (start, end) = self.bounds((Bound::Included(elem), Bound::Included(elem)));
+        self.elems.drain(start..end).collect()
+    }
+
+    /// Returns the number of elements equal to `elem`.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element
+    pub fn count<Q>(&self, elem: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.range((Bound::Included(elem), Bound::Included(elem)))
+            .len()
+    }
+
+    /// Returns `true` if an element is equal to `elem`, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element
+    pub fn contains<Q>(&self, elem: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.count(elem) > 0
+    }
+
+    /// Returns the elements within `range`, in order.
+    ///
+    /// # Arguments
+    /// * range - The range of elements returned
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SortedMultiSet;
+    /// let latencies: SortedMultiSet<u32> = vec![12, 40, 12, 95, 40, 7].into_iter().collect();
+    /// assert_eq!(latencies.range(10..50), &[12, 12, 40, 40]);
+    /// assert_eq!(latencies.range(90..), &[95]);
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> &[T]
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = self.bounds((range.start_bound(), range.end_bound()));
+        &self.elems[start..end]
+    }
+
+    /// Returns the `n`-th smallest element, from 0, `None` if there are not
+    /// that many elements.
+    ///
+    /// # Arguments
+    /// * n - The position of the element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SortedMultiSet;
+    /// let set: SortedMultiSet<u32> = vec![5, 1, 5, 3].into_iter().collect();
+    /// assert_eq!(set.nth(2), Some(&5));
+    /// assert_eq!(set.nth(4), None);
+    /// // The median.
+    /// assert_eq!(set.nth(set.len() / 2), Some(&5));
+    /// ```
+    pub fn nth(&self, n: usize) -> Option<&T> {
+        self.elems.get(n)
+    }
+
+    /// Returns the number of elements strictly smaller than `elem`.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SortedMultiSet;
+    /// let set: SortedMultiSet<u32> = vec![5, 1, 5, 3].into_iter().collect();
+    /// assert_eq!(set.rank(&5), 2);
+    /// assert_eq!(set.rank(&6), 4);
+    /// assert_eq!(set.rank(&0), 0);
+    /// ```
+    pub fn rank<Q>(&self, elem: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.elems.partition_point(|other| other.borrow() < elem)
+    }
+
+    /// Returns the smallest element, `None` if empty.
+    pub fn first(&self) -> Option<&T> {
+        self.elems.first()
+    }
+
+    /// Returns the largest element, `None` if empty.
+    pub fn last(&self) -> Option<&T> {
+        self.elems.last()
+    }
+
+    /// Remove the smallest element, returning it.
+    pub fn pop_first(&mut self) -> Option<T> {
+        if self.elems.is_empty() {
+            return None;
+        }
+        Some(self.elems.remove(0))
+    }
+
+    /// Remove the largest element, returning it.
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.elems.pop()
+    }
+
+    /// Iterate over the elements, in order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.elems.iter()
+    }
+
+    /// Returns the elements, in order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.elems
+    }
+
+    /// Returns the number of elements equal to every element.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SortedMultiSet;
+    /// let set: SortedMultiSet<char> = "hello".chars().collect();
+    /// assert_eq!(set.to_counter()[&'l'], 2);
+    /// ```
+    pub fn to_counter(&self) -> Counter<T>
+    where
+        T: Clone,
+    {
+        self.elems.iter().cloned().collect()
+    }
+
+    /// Remove every element.
+    pub fn clear(&mut self) {
+        self.elems.clear();
+    }
+
+    /// Returns the positions of the first element within `bounds` and of
+    /// the first element past them.
+    fn bounds<Q>(&self, bounds: (Bound<&Q>, Bound<&Q>)) -> (usize, usize)
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let start = match bounds.0 {
+            Bound::Included(start) => self.rank(start),
+            Bound::Excluded(start) => self.elems.partition_point(|elem| elem.borrow() <= start),
+            Bound::Unbounded => 0,
+        };
+        let end = match bounds.1 {
+            Bound::Included(end) => self.elems.partition_point(|elem| elem.borrow() <= end),
+            Bound::Excluded(end) => self.rank(end),
+            Bound::Unbounded => self.elems.len(),
+        };
+        (start, end.max(start))
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SortedMultiSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut elems: Vec<T> = iter.into_iter().collect();
+        // A stable sort keeps equal elements in insertion order.
+        elems.sort();
+        SortedMultiSet { elems }
+    }
+}
+
+impl<T: Ord> Extend<T> for SortedMultiSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.insert(elem);
+        }
+    }
+}
+
+impl<T> IntoIterator for SortedMultiSet<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elems.into_iter()
+    }
+}
+
+impl<T: Ord> Default for SortedMultiSet<T> {
+    fn default() -> Self {
+        SortedMultiSet::new()
+    }
+}
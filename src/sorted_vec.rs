@@ -0,0 +1,262 @@
+//! This module implements a vector kept sorted.
+//!
+//! Elements are stored contiguously in order, so lookups are binary
+//! searches over a slice and iteration is a plain slice walk, which beats
+//! the pointer chasing of a `BTreeSet` when reads dominate. Inserting an
+//! element shifts the following ones, so building large vectors is best
+//! done in bulk with `extend_sorted`, which merges a whole batch in one
+//! pass. A `SortedVec` created with `new_dedup` holds each element once.
+//!
+use std::borrow::Borrow;
+use std::iter::FromIterator;
+
+/// Structure that hold `T` elements in a sorted vector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortedVec<T> {
+    elems: Vec<T>,
+    dedup: bool,
+}
+
+impl<T: Ord> SortedVec<T> {
+    /// Create a new empty `SortedVec` keeping duplicate elements.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SortedVec;
+    /// let elems: SortedVec<u32> = SortedVec::new();
+    /// # assert!(elems.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        SortedVec {
+            elems: Vec::new(),
+            dedup: false,
+        }
+    }
+
+    /// Create a new empty `SortedVec` holding each element once.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SortedVec;
+    /// let mut elems: SortedVec<u32> = SortedVec::new_dedup();
+    /// assert_eq!(elems.insert(3), Some(0));
+    /// assert_eq!(elems.insert(3), None);
+    /// assert_eq!(elems.len(), 1);
+    /// ```
+    pub fn new_dedup() -> Self {
+        SortedVec {
+            elems: Vec::new(),
+            dedup: true,
+        }
+    }
+
+    /// Returns `true` if each element is held once, `false` otherwise.
+    pub fn is_dedup(&self) -> bool {
+        self.dedup
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.elems.len()
+    }
+
+    /// Returns `true` if the `SortedVec` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.elems.is_empty()
+    }
+
+    /// Insert an element after the elements equal to it, returning its
+    /// position, `None` if the `SortedVec` holds each element once and
+    /// already holds this one.
+    ///
+    /// # Arguments
+    /// * elem - The element inserted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SortedVec;
+    /// let mut elems: SortedVec<char> = SortedVec::new();
+    /// assert_eq!(elems.insert('m'), Some(0));
+    /// assert_eq!(elems.insert('a'), Some(0));
+    /// assert_eq!(elems.insert('m'), Some(2));
+    /// assert_eq!(elems.as_slice(), &['a', 'm', 'm']);
+    /// ```
+    pub fn insert(&mut self, elem: T) -> Option<usize> {
+        let idx = self.elems.partition_point(|other| *other <= elem);
+        if self.dedup && idx > 0 && self.elems[idx - 1] == elem {
+            return None;
+        }
+        self.elems.insert(idx, elem);
+        Some(idx)
+    }
+
+    /// Insert every element of an iterator, in a single merge pass over
+    /// the elements already held.
+    ///
+    /// The new elements need not be sorted. Among equal elements, those
+    /// already held come first.
+    ///
+    /// # Arguments
+    /// * iter - An iterator over the elements inserted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SortedVec;
+    /// let mut ids: SortedVec<u32> = SortedVec::new_dedup();
+    /// ids.extend_sorted(vec![10, 30, 50]);
+    /// ids.extend_sorted(vec![40, 20, 30, 60, 20]);
+    /// assert_eq!(ids.as_slice(), &[10, 20, 30, 40, 50, 60]);
+    /// ```
+    pub fn extend_sorted<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut batch: Vec<T> = iter.into_iter().collect();
+        if batch.is_empty() {
+            return;
+        }
+        batch.sort();
+        let mut merged: Vec<T> = Vec::with_capacity(self.elems.len() + batch.len());
+        let mut old = std::mem::take(&mut self.elems).into_iter().peekable();
+        let mut new = batch.into_iter().peekable();
+        loop {
+            let take_old = match (old.peek(), new.peek()) {
+                (Some(old_elem), Some(new_elem)) => old_elem <= new_elem,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            let elem = if take_old { old.next() } else { new.next() };
+            merged.extend(elem);
+        }
+        if self.dedup {
+            merged.dedup();
+        }
+        self.elems = merged;
+    }
+
+    /// Search an element, returning `Ok` with the position of an element
+    /// equal to it, or `Err` with the position it would be inserted at.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element
+    pub fn binary_search<Q>(&self, elem: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.elems
+            .binary_search_by(|other| other.borrow().cmp(elem))
+    }
+
+    /// Returns `true` if an element is equal to `elem`, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element
+    pub fn contains<Q>(&self, elem: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.binary_search(elem).is_ok()
+    }
+
+    /// Returns the element at position `idx`, `None` if out of bounds.
+    ///
+    /// # Arguments
+    /// * idx - The position of the element
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        self.elems.get(idx)
+    }
+
+    /// Remove the first element equal to `elem`, returning it.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element
+    pub fn remove<Q>(&mut self, elem: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.elems.partition_point(|other| other.borrow() < elem);
+        if self.elems.get(idx)?.borrow() != elem {
+            return None;
+        }
+        Some(self.elems.remove(idx))
+    }
+
+    /// Remove the element at position `idx`, returning it.
+    ///
+    /// # Arguments
+    /// * idx - The position of the element, must be in bounds
+    pub fn remove_index(&mut self, idx: usize) -> T {
+        self.elems.remove(idx)
+    }
+
+    /// Keep only the elements for which `f` returns `true`.
+    ///
+    /// # Arguments
+    /// * f - The predicate over the elements
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.elems.retain(f);
+    }
+
+    /// Returns the smallest element, `None` if empty.
+    pub fn first(&self) -> Option<&T> {
+        self.elems.first()
+    }
+
+    /// Returns the largest element, `None` if empty.
+    pub fn last(&self) -> Option<&T> {
+        self.elems.last()
+    }
+
+    /// Iterate over the elements, in order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.elems.iter()
+    }
+
+    /// Returns the elements, in order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.elems
+    }
+
+    /// Convert into the elements, in order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.elems
+    }
+
+    /// Remove every element.
+    pub fn clear(&mut self) {
+        self.elems.clear();
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SortedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut elems: SortedVec<T> = SortedVec::new();
+        elems.extend_sorted(iter);
+        elems
+    }
+}
+
+impl<T: Ord> Extend<T> for SortedVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.extend_sorted(iter);
+    }
+}
+
+impl<T> IntoIterator for SortedVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elems.into_iter()
+    }
+}
+
+impl<T: Ord> Default for SortedVec<T> {
+    fn default() -> Self {
+        SortedVec::new()
+    }
+}
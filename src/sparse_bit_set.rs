@@ -0,0 +1,316 @@
+//! This module implements a set of `u64` packed as bits in sparse blocks.
+//!
+//! Element `i` is bit `i % 64` of block `i / 64`, and only the blocks
+//! holding at least one element are stored, in a `BTreeMap`. The memory
+//! used is proportional to the number of distinct blocks, so huge and
+//! sparse index spaces such as 64 bits identifiers cost little, while
+//! nearby elements still share a word as in a `BitSet`.
+//!
+use std::collections::btree_map::{BTreeMap, Entry};
+use std::iter::FromIterator;
+
+const BLOCK_BITS: u64 = 64;
+
+/// Structure that hold a set of `u64` as bit blocks
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SparseBitSet {
+    blocks: BTreeMap<u64, u64>,
+}
+
+impl SparseBitSet {
+    /// Create a new empty `SparseBitSet`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SparseBitSet;
+    /// let set = SparseBitSet::new();
+    /// # assert!(set.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        SparseBitSet {
+            blocks: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.blocks
+            .values()
+            .map(|block| block.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns `true` if the `SparseBitSet` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Returns the number of blocks stored.
+    pub fn blocks_len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Insert an element, returning `true` if it was absent.
+    ///
+    /// # Arguments
+    /// * elem - The element inserted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SparseBitSet;
+    /// let mut set = SparseBitSet::new();
+    /// assert!(set.insert(u64::MAX));
+    /// assert!(set.insert(1 << 40));
+    /// assert!(!set.insert(1 << 40));
+    /// assert!(set.contains(u64::MAX));
+    /// assert!(set.remove(u64::MAX));
+    /// assert_eq!(set.blocks_len(), 1);
+    /// ```
+    pub fn insert(&mut self, elem: u64) -> bool {
+        let (block, mask) = (elem / BLOCK_BITS, 1 << (elem % BLOCK_BITS));
+        let bits = self.blocks.entry(block).or_insert(0);
+        let absent = *bits & mask == 0;
+        *bits |= mask;
+        absent
+    }
+
+    /// Remove an element, returning `true` if it was present.
+    ///
+    /// # Arguments
+    /// * elem - The element removed
+    pub fn remove(&mut self, elem: u64) -> bool {
+        let (block, mask) = (elem / BLOCK_BITS, 1 << (elem % BLOCK_BITS));
+        match self.blocks.entry(block) {
+            Entry::Occupied(mut entry) if *entry.get() & mask != 0 => {
+                *entry.get_mut() &= !mask;
+                if *entry.get() == 0 {
+                    let _ = entry.remove();
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the element is present, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * elem - The element looked up
+    pub fn contains(&self, elem: u64) -> bool {
+        self.blocks
+            .get(&(elem / BLOCK_BITS))
+            .is_some_and(|block| block & (1 << (elem % BLOCK_BITS)) != 0)
+    }
+
+    /// Remove every element.
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+
+    /// Iterate over the elements, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.blocks.iter().flat_map(|(idx, block)| {
+            let mut block = *block;
+            std::iter::from_fn(move || {
+                if block == 0 {
+                    return None;
+                }
+                let bit = block.trailing_zeros() as u64;
+                block &= block - 1;
+                Some(idx * BLOCK_BITS + bit)
+            })
+        })
+    }
+
+    /// Returns the smallest element, `None` if empty.
+    pub fn min(&self) -> Option<u64> {
+        let (idx, block) = self.blocks.iter().next()?;
+        Some(idx * BLOCK_BITS + block.trailing_zeros() as u64)
+    }
+
+    /// Returns the largest element, `None` if empty.
+    pub fn max(&self) -> Option<u64> {
+        let (idx, block) = self.blocks.iter().next_back()?;
+        Some(idx * BLOCK_BITS + BLOCK_BITS - 1 - block.leading_zeros() as u64)
+    }
+
+    /// Returns the number of elements strictly smaller than `elem`.
+    ///
+    /// # Arguments
+    /// * elem - The upper bound, excluded
+    pub fn rank(&self, elem: u64) -> usize {
+        let (block, bit) = (elem / BLOCK_BITS, elem % BLOCK_BITS);
+        let full: usize = self
+            .blocks
+            .range(..block)
+            .map(|(_, block)| block.count_ones() as usize)
+            .sum();
+        let partial = self
+            .blocks
+            .get(&block)
+            .map_or(0, |block| (block & ((1 << bit) - 1)).count_ones() as usize);
+        full + partial
+    }
+
+    /// Returns the element of rank `rank`, the smallest being of rank 0,
+    /// `None` if there are not that many elements.
+    ///
+    /// # Arguments
+    /// * rank - The number of smaller elements
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SparseBitSet;
+    /// let set: SparseBitSet = vec![7, 1 << 33, 1 << 50].into_iter().collect();
+    /// assert_eq!(set.rank(1 << 50), 2);
+    /// assert_eq!(set.select(1), Some(1 << 33));
+    /// assert_eq!(set.select(3), None);
+    /// ```
+    pub fn select(&self, rank: usize) -> Option<u64> {
+        let mut remaining = rank;
+        for (idx, block) in self.blocks.iter() {
+            let ones = block.count_ones() as usize;
+            if remaining < ones {
+                let mut block = *block;
+                for _ in 0..remaining {
+                    block &= block - 1;
+                }
+                return Some(idx * BLOCK_BITS + block.trailing_zeros() as u64);
+            }
+            remaining -= ones;
+        }
+        None
+    }
+
+    /// Add the elements of `other` to the `SparseBitSet`.
+    ///
+    /// # Arguments
+    /// * other - The `SparseBitSet` whose elements are added
+    pub fn union_with(&mut self, other: &SparseBitSet) {
+        for (idx, block) in other.blocks.iter() {
+            *self.blocks.entry(*idx).or_insert(0) |= block;
+        }
+    }
+
+    /// Keep only the elements also in `other`.
+    ///
+    /// # Arguments
+    /// * other - The `SparseBitSet` whose elements are kept
+    pub fn intersect_with(&mut self, other: &SparseBitSet) {
+        self.blocks.retain(|idx, block| {
+            *block &= other.blocks.get(idx).cloned().unwrap_or(0);
+            *block != 0
+        });
+    }
+
+    /// Remove the elements of `other` from the `SparseBitSet`.
+    ///
+    /// # Arguments
+    /// * other - The `SparseBitSet` whose elements are removed
+    pub fn difference_with(&mut self, other: &SparseBitSet) {
+        self.blocks.retain(|idx, block| {
+            *block &= !other.blocks.get(idx).cloned().unwrap_or(0);
+            *block != 0
+        });
+    }
+
+    /// Keep only the elements in exactly one of the two sets.
+    ///
+    /// # Arguments
+    /// * other - The other `SparseBitSet`
+    pub fn symmetric_difference_with(&mut self, other: &SparseBitSet) {
+        for (idx, block) in other.blocks.iter() {
+            *self.blocks.entry(*idx).or_insert(0) ^= block;
+        }
+        self.blocks.retain(|_, block| *block != 0);
+    }
+
+    /// Returns the elements in either set.
+    ///
+    /// # Arguments
+    /// * other - The other `SparseBitSet`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::SparseBitSet;
+    /// let a: SparseBitSet = vec![1, 1 << 60].into_iter().collect();
+    /// let b: SparseBitSet = vec![1, 2].into_iter().collect();
+    /// assert_eq!(a.union(&b).iter().collect::<Vec<_>>(), vec![1, 2, 1 << 60]);
+    /// assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), vec![1]);
+    /// assert_eq!(a.difference(&b).iter().collect::<Vec<_>>(), vec![1 << 60]);
+    /// assert_eq!(a.symmetric_difference(&b).iter().collect::<Vec<_>>(), vec![2, 1 << 60]);
+    /// ```
+    pub fn union(&self, other: &SparseBitSet) -> SparseBitSet {
+        let mut set = self.clone();
+        set.union_with(other);
+        set
+    }
+
+    /// Returns the elements in both sets.
+    ///
+    /// # Arguments
+    /// * other - The other `SparseBitSet`
+    pub fn intersection(&self, other: &SparseBitSet) -> SparseBitSet {
+        let mut set = self.clone();
+        set.intersect_with(other);
+        set
+    }
+
+    /// Returns the elements not in `other`.
+    ///
+    /// # Arguments
+    /// * other - The other `SparseBitSet`
+    pub fn difference(&self, other: &SparseBitSet) -> SparseBitSet {
+        let mut set = self.clone();
+        set.difference_with(other);
+        set
+    }
+
+    /// Returns the elements in exactly one of the two sets.
+    ///
+    /// # Arguments
+    /// * other - The other `SparseBitSet`
+    pub fn symmetric_difference(&self, other: &SparseBitSet) -> SparseBitSet {
+        let mut set = self.clone();
+        set.symmetric_difference_with(other);
+        set
+    }
+
+    /// Returns `true` if every element is also in `other`, `false`
+    /// otherwise.
+    ///
+    /// # Arguments
+    /// * other - The other `SparseBitSet`
+    pub fn is_subset(&self, other: &SparseBitSet) -> bool {
+        self.blocks
+            .iter()
+            .all(|(idx, block)| block & !other.blocks.get(idx).cloned().unwrap_or(0) == 0)
+    }
+
+    /// Returns `true` if the two sets have no element in common, `false`
+    /// otherwise.
+    ///
+    /// # Arguments
+    /// * other - The other `SparseBitSet`
+    pub fn is_disjoint(&self, other: &SparseBitSet) -> bool {
+        self.blocks
+            .iter()
+            .all(|(idx, block)| block & other.blocks.get(idx).cloned().unwrap_or(0) == 0)
+    }
+}
+
+impl FromIterator<u64> for SparseBitSet {
+    fn from_iter<I: IntoIterator<Item = u64>>(iter: I) -> Self {
+        let mut set = SparseBitSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<u64> for SparseBitSet {
+    fn extend<I: IntoIterator<Item = u64>>(&mut self, iter: I) {
+        for elem in iter {
+            let _ = self.insert(elem);
+        }
+    }
+}
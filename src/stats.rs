@@ -0,0 +1,178 @@
+//! This module implements streaming summary statistics of numbers.
+//!
+//! The mean and the variance are updated with Welford's algorithm, which
+//! stays accurate where the textbook sum of squares cancels out, and two
+//! summaries merge exactly, so a stream can be summarized by several
+//! threads. Memory use does not grow with the number of values.
+//!
+use std::iter::FromIterator;
+
+/// Structure that summarize a stream of `f64` values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    count: u128,
+    mean: f64,
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Stats {
+    /// Create a new empty `Stats`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Stats;
+    /// let stats = Stats::new();
+    /// # assert_eq!(stats.count(), 0);
+    /// ```
+    pub fn new() -> Self {
+        Stats {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Add a value.
+    ///
+    /// # Arguments
+    /// * value - The value added
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Stats;
+    /// let mut latencies = Stats::new();
+    /// for ms in vec![12.0, 15.0, 9.0, 20.0] {
+    ///     latencies.push(ms);
+    /// }
+    /// assert_eq!(latencies.count(), 4);
+    /// assert_eq!(latencies.mean(), Some(14.0));
+    /// assert_eq!(latencies.min(), Some(9.0));
+    /// assert_eq!(latencies.max(), Some(20.0));
+    /// ```
+    pub fn push(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    /// Returns the number of values.
+    pub fn count(&self) -> u128 {
+        self.count
+    }
+
+    /// Returns the mean of the values, `None` if there is none.
+    pub fn mean(&self) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        Some(self.mean)
+    }
+
+    /// Returns the population variance of the values, `None` if there is
+    /// none.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Stats;
+    /// let stats: Stats = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0].into_iter().collect();
+    /// assert_eq!(stats.variance(), Some(4.0));
+    /// assert_eq!(stats.stddev(), Some(2.0));
+    /// assert_eq!(stats.sample_variance(), Some(32.0 / 7.0));
+    /// ```
+    pub fn variance(&self) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        Some(self.m2 / self.count as f64)
+    }
+
+    /// Returns the sample variance of the values, with Bessel's
+    /// correction, `None` if there are less than 2.
+    pub fn sample_variance(&self) -> Option<f64> {
+        if self.count < 2 {
+            return None;
+        }
+        Some(self.m2 / (self.count - 1) as f64)
+    }
+
+    /// Returns the population standard deviation of the values, `None` if
+    /// there is none.
+    pub fn stddev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    /// Returns the smallest value, `None` if there is none.
+    pub fn min(&self) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        Some(self.min)
+    }
+
+    /// Returns the largest value, `None` if there is none.
+    pub fn max(&self) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        Some(self.max)
+    }
+
+    /// Add the values summarized by another `Stats`.
+    ///
+    /// # Arguments
+    /// * other - The `Stats` to merge
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Stats;
+    /// let mut first: Stats = vec![1.0, 2.0, 3.0].into_iter().collect();
+    /// let second: Stats = vec![10.0, 20.0].into_iter().collect();
+    /// first.merge(&second);
+    /// let whole: Stats = vec![1.0, 2.0, 3.0, 10.0, 20.0].into_iter().collect();
+    /// assert_eq!(first.count(), 5);
+    /// assert_eq!(first.mean(), whole.mean());
+    /// assert!((first.variance().unwrap() - whole.variance().unwrap()).abs() < 1e-9);
+    /// ```
+    pub fn merge(&mut self, other: &Stats) {
+        if other.count == 0 {
+            return;
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        let weight = other.count as f64 / count as f64;
+        self.mean += delta * weight;
+        self.m2 += other.m2 + delta * delta * self.count as f64 * weight;
+        self.count = count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+}
+
+impl FromIterator<f64> for Stats {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut stats = Stats::new();
+        stats.extend(iter);
+        stats
+    }
+}
+
+impl Extend<f64> for Stats {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Stats::new()
+    }
+}
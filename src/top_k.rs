@@ -0,0 +1,182 @@
+//! This module implements the streaming selection of the best scored
+//! items.
+//!
+//! A `TopK` keeps the `k` items of highest score offered so far in a
+//! bounded min heap, so each offer costs O(log k) and memory never grows
+//! past `k` items, however long the stream. Among items of equal score,
+//! the earliest offered are kept.
+//!
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+#[derive(Debug, Clone)]
+struct Entry<T, S> {
+    score: S,
+    seq: u64,
+    item: T,
+}
+
+impl<T, S: Ord> PartialEq for Entry<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T, S: Ord> Eq for Entry<T, S> {}
+
+impl<T, S: Ord> PartialOrd for Entry<T, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, S: Ord> Ord for Entry<T, S> {
+    /// Orders by score, then earliest offered first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .cmp(&other.score)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// Structure that keep the `k` highest scored `T` items of a stream
+#[derive(Debug, Clone)]
+pub struct TopK<T, S> {
+    heap: BinaryHeap<Reverse<Entry<T, S>>>,
+    capacity: usize,
+    seq: u64,
+}
+
+impl<T, S: Ord> TopK<T, S> {
+    /// Create a new empty `TopK` keeping `capacity` items.
+    ///
+    /// # Arguments
+    /// * capacity - The number of items kept, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::TopK;
+    /// let top: TopK<&str, u32> = TopK::new(10);
+    /// # assert!(top.is_empty());
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        TopK {
+            heap: BinaryHeap::with_capacity(capacity + 1),
+            capacity,
+            seq: 0,
+        }
+    }
+
+    /// Returns the number of items kept once enough items were offered.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of items kept.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if no item was offered, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Offer an item, kept if its score is among the `k` highest so far.
+    ///
+    /// # Arguments
+    /// * item - The item offered
+    /// * score - The score of the item
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::TopK;
+    /// // The slowest requests of a log.
+    /// let mut slowest: TopK<&str, u32> = TopK::new(2);
+    /// for (path, ms) in vec![("/", 12), ("/search", 340), ("/login", 95), ("/export", 1200)] {
+    ///     slowest.offer(path, ms);
+    /// }
+    /// assert_eq!(slowest.min_score(), Some(&340));
+    /// assert_eq!(slowest.into_sorted_vec(), vec![("/export", 1200), ("/search", 340)]);
+    /// ```
+    pub fn offer(&mut self, item: T, score: S) {
+        if self.heap.len() == self.capacity {
+            match self.heap.peek() {
+                Some(Reverse(worst)) if score > worst.score => {
+                    let _ = self.heap.pop();
+                }
+                _ => return,
+            }
+        }
+        self.heap.push(Reverse(Entry {
+            score,
+            seq: self.seq,
+            item,
+        }));
+        self.seq += 1;
+    }
+
+    /// Returns the lowest score kept, that an item must exceed to be kept
+    /// once the `TopK` is full, `None` if empty.
+    pub fn min_score(&self) -> Option<&S> {
+        self.heap.peek().map(|Reverse(entry)| &entry.score)
+    }
+
+    /// Iterate over the items kept and their scores, in no particular
+    /// order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, &S)> + '_ {
+        self.heap
+            .iter()
+            .map(|Reverse(entry)| (&entry.item, &entry.score))
+    }
+
+    /// Convert into the items kept and their scores, by decreasing score.
+    pub fn into_sorted_vec(self) -> Vec<(T, S)> {
+        // Sorting the reversed entries in increasing order puts the best first.
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(entry)| (entry.item, entry.score))
+            .collect()
+    }
+
+    /// Offer the items kept by another `TopK`, so that the result keeps
+    /// the best items of both streams.
+    ///
+    /// # Arguments
+    /// * other - The `TopK` of the other stream
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, TopK};
+    /// let morning: Counter<&str> = "a b a c a b".split(' ').collect();
+    /// let evening: Counter<&str> = "c c c d".split(' ').collect();
+    /// let mut top: TopK<&str, u128> = TopK::new(2);
+    /// top.extend(morning.iter().map(|(word, count)| (*word, *count)));
+    /// let mut other: TopK<&str, u128> = TopK::new(2);
+    /// other.extend(evening.iter().map(|(word, count)| (*word, *count)));
+    /// top.merge(other);
+    /// assert_eq!(top.into_sorted_vec(), vec![("a", 3), ("c", 3)]);
+    /// ```
+    pub fn merge(&mut self, other: TopK<T, S>) {
+        let mut entries = other.heap.into_vec();
+        entries.sort_by_key(|Reverse(entry)| entry.seq);
+        for Reverse(entry) in entries {
+            self.offer(entry.item, entry.score);
+        }
+    }
+
+    /// Remove every item.
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+}
+
+impl<T, S: Ord> Extend<(T, S)> for TopK<T, S> {
+    fn extend<I: IntoIterator<Item = (T, S)>>(&mut self, iter: I) {
+        for (item, score) in iter {
+            self.offer(item, score);
+        }
+    }
+}
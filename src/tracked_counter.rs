@@ -0,0 +1,222 @@
+//! This module implements a `Counter` recording when each element was
+//! first and last seen.
+//!
+//! Positions are plain `u64` values. `update` uses the index of the update,
+//! starting at 0, while `update_at` takes any position such as a timestamp.
+//! Both shouldn't be mixed on the same `TrackedCounter`.
+//!
+use crate::Counter;
+use std::borrow::Borrow;
+use std::collections::btree_map::{BTreeMap, Iter};
+use std::iter::FromIterator;
+
+/// Count and positions of an element of a `TrackedCounter`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tracking {
+    /// Number of occurences of the element
+    pub count: u128,
+
+    /// Lowest position at which the element occured
+    pub first_seen: u64,
+
+    /// Highest position at which the element occured
+    pub last_seen: u64,
+}
+
+/// Structure that count occurences of `T` elements along with their first
+/// and last positions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackedCounter<T> {
+    state: BTreeMap<T, Tracking>,
+    updates: u64,
+}
+
+impl<T> TrackedCounter<T> {
+    /// Create a new empty `TrackedCounter`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::TrackedCounter;
+    /// let counter: TrackedCounter<u16> = TrackedCounter::new();
+    /// # assert_eq!(counter.len(), 0);
+    /// ```
+    pub fn new() -> Self {
+        TrackedCounter {
+            state: BTreeMap::new(),
+            updates: 0,
+        }
+    }
+
+    /// Iterate over the elements and their `Tracking`.
+    pub fn iter(&self) -> Iter<'_, T, Tracking> {
+        self.state.iter()
+    }
+
+    /// Returns the number of distinct elements.
+    pub fn len(&self) -> usize {
+        self.state.len()
+    }
+
+    /// Returns `true` if the `TrackedCounter` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.state.is_empty()
+    }
+
+    /// Returns the number of updates so far.
+    pub fn updates(&self) -> u64 {
+        self.updates
+    }
+}
+
+impl<T: Ord> TrackedCounter<T> {
+    /// Update the `TrackedCounter` with a value, positioned at the index of
+    /// the update.
+    ///
+    /// # Arguments
+    /// * elem - A value used to update the `TrackedCounter`
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::TrackedCounter;
+    /// let mut counter: TrackedCounter<u16> = TrackedCounter::new();
+    /// for code in vec![200, 404, 200, 500, 404] {
+    ///     counter.update(code);
+    /// }
+    /// assert_eq!(counter.first_seen(&404), Some(1));
+    /// assert_eq!(counter.last_seen(&404), Some(4));
+    /// assert_eq!(counter.count(&200), 2);
+    /// ```
+    pub fn update(&mut self, elem: T) {
+        let position = self.updates;
+        self.update_at(elem, position);
+    }
+
+    /// Update the `TrackedCounter` with a value occuring at `position`.
+    ///
+    /// Positions may be given out of order, the lowest and highest ones are
+    /// kept.
+    ///
+    /// # Arguments
+    /// * elem - A value used to update the `TrackedCounter`
+    /// * position - When the value occured, a timestamp for instance
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::TrackedCounter;
+    /// let mut counter: TrackedCounter<&str> = TrackedCounter::new();
+    /// counter.update_at("E42", 1_700_000_300);
+    /// counter.update_at("E42", 1_700_000_100);
+    /// let tracking = counter.get(&"E42").unwrap();
+    /// assert_eq!(tracking.count, 2);
+    /// assert_eq!(tracking.first_seen, 1_700_000_100);
+    /// assert_eq!(tracking.last_seen, 1_700_000_300);
+    /// ```
+    pub fn update_at(&mut self, elem: T, position: u64) {
+        self.updates += 1;
+        let tracking = self.state.entry(elem).or_insert(Tracking {
+            count: 0,
+            first_seen: position,
+            last_seen: position,
+        });
+        tracking.count += 1;
+        tracking.first_seen = tracking.first_seen.min(position);
+        tracking.last_seen = tracking.last_seen.max(position);
+    }
+
+    /// Update the `TrackedCounter` with an iterator, positioning each value
+    /// at the index of its update.
+    ///
+    /// # Arguments
+    /// * iter - An iterator used to update the `TrackedCounter`
+    pub fn update_from_iter<I>(&mut self, iter: I)
+    where
+        I: Iterator<Item = T>,
+    {
+        for elem in iter {
+            self.update(elem);
+        }
+    }
+
+    /// Returns the `Tracking` of an element, `None` if it never occured.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element to look up
+    pub fn get<Q>(&self, elem: &Q) -> Option<&Tracking>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.state.get(elem)
+    }
+
+    /// Returns the count of an element, 0 if it never occured.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element to look up
+    pub fn count<Q>(&self, elem: &Q) -> u128
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(elem).map_or(0, |tracking| tracking.count)
+    }
+
+    /// Returns the lowest position of an element, `None` if it never occured.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element to look up
+    pub fn first_seen<Q>(&self, elem: &Q) -> Option<u64>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(elem).map(|tracking| tracking.first_seen)
+    }
+
+    /// Returns the highest position of an element, `None` if it never occured.
+    ///
+    /// # Arguments
+    /// * elem - A borrowed form of the element to look up
+    pub fn last_seen<Q>(&self, elem: &Q) -> Option<u64>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(elem).map(|tracking| tracking.last_seen)
+    }
+
+    /// Returns a `Counter` holding the counts, without the positions.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::TrackedCounter;
+    /// let counter: TrackedCounter<char> = "abca".chars().collect();
+    /// assert_eq!(counter.to_counter()[&'a'], 2);
+    /// ```
+    pub fn to_counter(&self) -> Counter<T>
+    where
+        T: Clone,
+    {
+        let mut counter: Counter<T> = Counter::new();
+        counter.extend(
+            self.state
+                .iter()
+                .map(|(key, tracking)| (key.clone(), tracking.count)),
+        );
+        counter
+    }
+}
+
+impl<T: Ord> FromIterator<T> for TrackedCounter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter: TrackedCounter<T> = TrackedCounter::new();
+        counter.update_from_iter(iter.into_iter());
+        counter
+    }
+}
+
+impl<T> Default for TrackedCounter<T> {
+    fn default() -> Self {
+        TrackedCounter::new()
+    }
+}
@@ -0,0 +1,182 @@
+//! This module implements a counter of the transitions between consecutive
+//! elements of sequences, the building block of a Markov chain.
+//!
+//! Transitions are stored per source element, each source holding a
+//! `Counter` of the elements following it, so the probability of a
+//! transition is its count divided by the total count of its source.
+//!
+use crate::Counter;
+use std::borrow::Borrow;
+use std::collections::btree_map::{BTreeMap, Iter};
+use std::iter::FromIterator;
+
+/// Structure that count transitions between consecutive `T` elements
+#[derive(Debug, Clone)]
+pub struct TransitionCounter<T> {
+    rows: BTreeMap<T, Counter<T>>,
+}
+
+impl<T: Ord + Clone> TransitionCounter<T> {
+    /// Create a new empty `TransitionCounter`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::TransitionCounter;
+    /// let transitions: TransitionCounter<char> = TransitionCounter::new();
+    /// # assert!(transitions.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        TransitionCounter {
+            rows: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of elements that have at least one transition
+    /// starting from them.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns `true` if no transition was counted, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Iterate over the source elements and the `Counter` of the elements
+    /// following them.
+    pub fn iter(&self) -> Iter<'_, T, Counter<T>> {
+        self.rows.iter()
+    }
+
+    /// Count a single transition.
+    ///
+    /// # Arguments
+    /// * from - The source element
+    /// * to - The element following it
+    pub fn update(&mut self, from: T, to: T) {
+        self.rows.entry(from).or_default().update_from_value(to);
+    }
+
+    /// Count the transitions between the consecutive elements of a
+    /// sequence. Each call is a separate sequence, no transition is counted
+    /// between the last element of a call and the first of the next one.
+    ///
+    /// # Arguments
+    /// * sequence - An iterator over the sequence
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::TransitionCounter;
+    /// let mut transitions: TransitionCounter<&str> = TransitionCounter::new();
+    /// transitions.update_from_iter(vec!["home", "search", "product", "cart"].into_iter());
+    /// transitions.update_from_iter(vec!["home", "search", "home"].into_iter());
+    /// assert_eq!(transitions.count(&"home", &"search"), 2);
+    /// assert_eq!(transitions.count(&"cart", &"home"), 0);
+    /// ```
+    pub fn update_from_iter<I>(&mut self, sequence: I)
+    where
+        I: Iterator<Item = T>,
+    {
+        let mut previous: Option<T> = None;
+        for elem in sequence {
+            if let Some(prev) = previous.take() {
+                self.update(prev, elem.clone());
+            }
+            previous = Some(elem);
+        }
+    }
+
+    /// Returns the number of transitions from `from` to `to`.
+    ///
+    /// # Arguments
+    /// * from - A borrowed form of the source element
+    /// * to - A borrowed form of the following element
+    pub fn count<Q>(&self, from: &Q, to: &Q) -> u128
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.rows.get(from).map_or(0, |row| row[to])
+    }
+
+    /// Returns the `Counter` of the elements following `from`, `None` if no
+    /// transition starts from it.
+    ///
+    /// # Arguments
+    /// * from - A borrowed form of the source element
+    pub fn row<Q>(&self, from: &Q) -> Option<&Counter<T>>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.rows.get(from)
+    }
+
+    /// Returns the probability that `to` follows `from`, `None` if no
+    /// transition starts from `from`.
+    ///
+    /// # Arguments
+    /// * from - A borrowed form of the source element
+    /// * to - A borrowed form of the following element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::TransitionCounter;
+    /// let transitions: TransitionCounter<char> = "abacab".chars().collect();
+    /// assert_eq!(transitions.probability(&'a', &'b'), Some(2.0 / 3.0));
+    /// assert_eq!(transitions.probability(&'b', &'a'), Some(1.0));
+    /// assert_eq!(transitions.probability(&'z', &'a'), None);
+    /// ```
+    pub fn probability<Q>(&self, from: &Q, to: &Q) -> Option<f64>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let row = self.rows.get(from)?;
+        Some(row[to] as f64 / row.total() as f64)
+    }
+
+    /// Returns the probability of each element following `from`, in key
+    /// order, empty if no transition starts from `from`.
+    ///
+    /// # Arguments
+    /// * from - A borrowed form of the source element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::TransitionCounter;
+    /// let transitions: TransitionCounter<char> = "abacab".chars().collect();
+    /// assert_eq!(transitions.distribution(&'a'), vec![(&'b', 2.0 / 3.0), (&'c', 1.0 / 3.0)]);
+    /// ```
+    pub fn distribution<Q>(&self, from: &Q) -> Vec<(&T, f64)>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.rows.get(from) {
+            Some(row) => {
+                let total = row.total() as f64;
+                row.iter()
+                    .map(|(key, count)| (key, *count as f64 / total))
+                    .collect()
+            }
+            None => Vec::new(),
+        }
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for TransitionCounter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut transitions: TransitionCounter<T> = TransitionCounter::new();
+        transitions.update_from_iter(iter.into_iter());
+        transitions
+    }
+}
+
+impl<T> Default for TransitionCounter<T> {
+    fn default() -> Self {
+        TransitionCounter {
+            rows: BTreeMap::new(),
+        }
+    }
+}
@@ -0,0 +1,424 @@
+//! This module implements prefix trees over byte string keys.
+//!
+//! A `Trie` maps keys to values and a `CountingTrie` counts occurences of
+//! keys. Both keep, in every node, an aggregate of the keys below it, so
+//! the number of keys, or of occurences, sharing a prefix is found by
+//! walking down the prefix only.
+//!
+//! Keys are bytes, `&str` keys being stored as their UTF-8 bytes.
+//!
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+#[derive(Debug, Clone)]
+struct Node<V> {
+    children: BTreeMap<u8, Node<V>>,
+    value: Option<V>,
+    keys: usize,
+}
+
+impl<V> Node<V> {
+    fn new() -> Self {
+        Node {
+            children: BTreeMap::new(),
+            value: None,
+            keys: 0,
+        }
+    }
+
+    fn find(&self, key: &[u8]) -> Option<&Node<V>> {
+        key.iter()
+            .try_fold(self, |node, byte| node.children.get(byte))
+    }
+
+    fn find_mut(&mut self, key: &[u8]) -> Option<&mut Node<V>> {
+        key.iter()
+            .try_fold(self, |node, byte| node.children.get_mut(byte))
+    }
+
+    /// Remove the value of `key` below this node, pruning the nodes left
+    /// without keys.
+    fn remove(&mut self, key: &[u8]) -> Option<V> {
+        let value = match key.split_first() {
+            None => self.value.take()?,
+            Some((byte, rest)) => {
+                let child = self.children.get_mut(byte)?;
+                let value = child.remove(rest)?;
+                if child.keys == 0 {
+                    let _ = self.children.remove(byte);
+                }
+                value
+            }
+        };
+        self.keys -= 1;
+        Some(value)
+    }
+
+    /// Iterate over the keys and values below this node, in key order,
+    /// starting from `prefix`.
+    fn iter(&self, prefix: Vec<u8>) -> impl Iterator<Item = (Vec<u8>, &V)> + '_ {
+        let mut stack: Vec<(Vec<u8>, &Node<V>)> = vec![(prefix, self)];
+        std::iter::from_fn(move || {
+            while let Some((key, node)) = stack.pop() {
+                for (byte, child) in node.children.iter().rev() {
+                    let mut child_key = key.clone();
+                    child_key.push(*byte);
+                    stack.push((child_key, child));
+                }
+                if let Some(value) = &node.value {
+                    return Some((key, value));
+                }
+            }
+            None
+        })
+    }
+}
+
+/// Structure that map byte string keys to `V` values in a prefix tree
+#[derive(Debug, Clone)]
+pub struct Trie<V> {
+    root: Node<V>,
+}
+
+impl<V> Trie<V> {
+    /// Create a new empty `Trie`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Trie;
+    /// let trie: Trie<u32> = Trie::new();
+    /// # assert!(trie.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Trie { root: Node::new() }
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.root.keys
+    }
+
+    /// Returns `true` if the `Trie` is empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.root.keys == 0
+    }
+
+    /// Insert a value, returning the previous value of the key if any.
+    ///
+    /// # Arguments
+    /// * key - The key of the value
+    /// * value - The value inserted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Trie;
+    /// let mut routes: Trie<&str> = Trie::new();
+    /// assert_eq!(routes.insert("/users", "list"), None);
+    /// assert_eq!(routes.insert("/users", "index"), Some("list"));
+    /// assert_eq!(routes.get("/users"), Some(&"index"));
+    /// assert_eq!(routes.get("/user"), None);
+    /// ```
+    pub fn insert<K: AsRef<[u8]>>(&mut self, key: K, value: V) -> Option<V> {
+        let key = key.as_ref();
+        if let Some(node) = self.root.find_mut(key) {
+            if let Some(previous) = node.value.as_mut() {
+                return Some(std::mem::replace(previous, value));
+            }
+        }
+        let mut node = &mut self.root;
+        node.keys += 1;
+        for byte in key {
+            node = node.children.entry(*byte).or_insert_with(Node::new);
+            node.keys += 1;
+        }
+        node.value = Some(value);
+        None
+    }
+
+    /// Returns the value of a key, `None` if it is absent.
+    ///
+    /// # Arguments
+    /// * key - The key looked up
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Option<&V> {
+        self.root.find(key.as_ref())?.value.as_ref()
+    }
+
+    /// Returns a mutable reference to the value of a key, `None` if it is
+    /// absent.
+    ///
+    /// # Arguments
+    /// * key - The key looked up
+    pub fn get_mut<K: AsRef<[u8]>>(&mut self, key: K) -> Option<&mut V> {
+        self.root.find_mut(key.as_ref())?.value.as_mut()
+    }
+
+    /// Returns `true` if the key is present, `false` otherwise.
+    ///
+    /// # Arguments
+    /// * key - The key looked up
+    pub fn contains_key<K: AsRef<[u8]>>(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Remove a key, returning its value if it was present.
+    ///
+    /// # Arguments
+    /// * key - The key removed
+    pub fn remove<K: AsRef<[u8]>>(&mut self, key: K) -> Option<V> {
+        self.root.remove(key.as_ref())
+    }
+
+    /// Returns the number of keys starting with `prefix`, the keys equal to
+    /// it included.
+    ///
+    /// # Arguments
+    /// * prefix - The prefix of the keys counted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Trie;
+    /// let trie: Trie<()> = vec!["car", "cart", "cat", "dog"].into_iter().map(|key| (key, ())).collect();
+    /// assert_eq!(trie.count_prefix("ca"), 3);
+    /// assert_eq!(trie.count_prefix("car"), 2);
+    /// assert_eq!(trie.count_prefix(""), 4);
+    /// assert_eq!(trie.count_prefix("x"), 0);
+    /// ```
+    pub fn count_prefix<K: AsRef<[u8]>>(&self, prefix: K) -> usize {
+        self.root.find(prefix.as_ref()).map_or(0, |node| node.keys)
+    }
+
+    /// Iterate over the keys starting with `prefix` and their values, in
+    /// key order.
+    ///
+    /// # Arguments
+    /// * prefix - The prefix of the keys
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::Trie;
+    /// let trie: Trie<u32> = vec![("cart", 2), ("car", 1), ("cat", 3), ("dog", 4)].into_iter().collect();
+    /// let completions: Vec<(String, u32)> = trie
+    ///     .iter_prefix("car")
+    ///     .map(|(key, value)| (String::from_utf8(key).unwrap(), *value))
+    ///     .collect();
+    /// assert_eq!(completions, vec![(String::from("car"), 1), (String::from("cart"), 2)]);
+    /// ```
+    pub fn iter_prefix<K: AsRef<[u8]>>(
+        &self,
+        prefix: K,
+    ) -> impl Iterator<Item = (Vec<u8>, &V)> + '_ {
+        let prefix = prefix.as_ref().to_vec();
+        self.root
+            .find(&prefix)
+            .into_iter()
+            .flat_map(move |node| node.iter(prefix.clone()))
+    }
+
+    /// Iterate over the keys and their values, in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec<u8>, &V)> + '_ {
+        self.root.iter(Vec::new())
+    }
+}
+
+impl<K: AsRef<[u8]>, V> FromIterator<(K, V)> for Trie<V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut trie: Trie<V> = Trie::new();
+        trie.extend(iter);
+        trie
+    }
+}
+
+impl<K: AsRef<[u8]>, V> Extend<(K, V)> for Trie<V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            let _ = self.insert(key, value);
+        }
+    }
+}
+
+impl<V> Default for Trie<V> {
+    fn default() -> Self {
+        Trie::new()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct CountingNode {
+    children: BTreeMap<u8, CountingNode>,
+    count: u128,
+    total: u128,
+}
+
+/// Structure that count occurences of byte string keys in a prefix tree
+#[derive(Debug, Clone, Default)]
+pub struct CountingTrie {
+    root: CountingNode,
+    len: usize,
+}
+
+impl CountingTrie {
+    /// Create a new empty `CountingTrie`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::CountingTrie;
+    /// let trie = CountingTrie::new();
+    /// # assert!(trie.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        CountingTrie::default()
+    }
+
+    /// Returns the number of distinct keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no key was counted, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of occurences of every key.
+    pub fn total(&self) -> u128 {
+        self.root.total
+    }
+
+    /// Count an occurence of a key.
+    ///
+    /// # Arguments
+    /// * key - The key counted
+    pub fn update_from_value<K: AsRef<[u8]>>(&mut self, key: K) {
+        self.add(key, 1);
+    }
+
+    /// Count every key yielded by an iterator.
+    ///
+    /// # Arguments
+    /// * iter - An iterator over the keys counted
+    pub fn update_from_iter<K, I>(&mut self, iter: I)
+    where
+        K: AsRef<[u8]>,
+        I: Iterator<Item = K>,
+    {
+        for key in iter {
+            self.update_from_value(key);
+        }
+    }
+
+    /// Add `count` occurences of a key, counts saturating at `u128::MAX`.
+    ///
+    /// # Arguments
+    /// * key - The key counted
+    /// * count - The number of occurences added
+    pub fn add<K: AsRef<[u8]>>(&mut self, key: K, count: u128) {
+        if count == 0 {
+            return;
+        }
+        let mut node = &mut self.root;
+        node.total = node.total.saturating_add(count);
+        for byte in key.as_ref() {
+            node = node.children.entry(*byte).or_default();
+            node.total = node.total.saturating_add(count);
+        }
+        if node.count == 0 {
+            self.len += 1;
+        }
+        node.count = node.count.saturating_add(count);
+    }
+
+    /// Returns the number of occurences of a key.
+    ///
+    /// # Arguments
+    /// * key - The key looked up
+    pub fn count<K: AsRef<[u8]>>(&self, key: K) -> u128 {
+        self.find(key.as_ref()).map_or(0, |node| node.count)
+    }
+
+    /// Returns the number of occurences of the keys starting with `prefix`,
+    /// the keys equal to it included.
+    ///
+    /// # Arguments
+    /// * prefix - The prefix of the keys counted
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::CountingTrie;
+    /// let mut queries = CountingTrie::new();
+    /// queries.update_from_iter(vec!["rust", "rust", "ruby", "python"].into_iter());
+    /// assert_eq!(queries.count("rust"), 2);
+    /// assert_eq!(queries.count_prefix("ru"), 3);
+    /// assert_eq!(queries.count_prefix("rus"), 2);
+    /// assert_eq!(queries.count_prefix("java"), 0);
+    /// ```
+    pub fn count_prefix<K: AsRef<[u8]>>(&self, prefix: K) -> u128 {
+        self.find(prefix.as_ref()).map_or(0, |node| node.total)
+    }
+
+    /// Returns the `k` most counted keys starting with `prefix` along with
+    /// their counts, by decreasing count, ties sorted by key.
+    ///
+    /// # Arguments
+    /// * prefix - The prefix of the keys
+    /// * k - The maximum number of keys returned
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::CountingTrie;
+    /// let mut queries = CountingTrie::new();
+    /// queries.update_from_iter(vec!["rust", "ruby", "rust", "rusty", "ruby", "rust"].into_iter());
+    /// assert_eq!(
+    ///     queries.most_common_with_prefix("ru", 2),
+    ///     vec![(b"rust".to_vec(), 3), (b"ruby".to_vec(), 2)]
+    /// );
+    /// ```
+    pub fn most_common_with_prefix<K: AsRef<[u8]>>(
+        &self,
+        prefix: K,
+        k: usize,
+    ) -> Vec<(Vec<u8>, u128)> {
+        let mut completions: Vec<(Vec<u8>, u128)> = self.iter_prefix(prefix).collect();
+        completions.sort_by_key(|(_, count)| Reverse(*count));
+        completions.truncate(k);
+        completions
+    }
+
+    /// Iterate over the keys starting with `prefix` and their counts, in
+    /// key order.
+    ///
+    /// # Arguments
+    /// * prefix - The prefix of the keys
+    pub fn iter_prefix<K: AsRef<[u8]>>(&self, prefix: K) -> impl Iterator<Item = (Vec<u8>, u128)> {
+        let prefix = prefix.as_ref();
+        let mut keys: Vec<(Vec<u8>, u128)> = Vec::new();
+        let mut stack: Vec<(Vec<u8>, &CountingNode)> = match self.find(prefix) {
+            Some(node) => vec![(prefix.to_vec(), node)],
+            None => Vec::new(),
+        };
+        while let Some((key, node)) = stack.pop() {
+            for (byte, child) in node.children.iter().rev() {
+                let mut child_key = key.clone();
+                child_key.push(*byte);
+                stack.push((child_key, child));
+            }
+            if node.count > 0 {
+                keys.push((key, node.count));
+            }
+        }
+        keys.into_iter()
+    }
+
+    fn find(&self, key: &[u8]) -> Option<&CountingNode> {
+        key.iter()
+            .try_fold(&self.root, |node, byte| node.children.get(byte))
+    }
+}
+
+impl<K: AsRef<[u8]>> FromIterator<K> for CountingTrie {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut trie = CountingTrie::new();
+        trie.update_from_iter(iter.into_iter());
+        trie
+    }
+}
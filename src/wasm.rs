@@ -0,0 +1,163 @@
+//! This module implements JavaScript bindings for `Counter` and `Bits`,
+//! behind the `wasm` feature.
+//!
+//! The bindings are generated by wasm-bindgen, for instance with
+//! `wasm-pack build --features wasm`. JavaScript numbers can't hold every
+//! `u128`, so counts are exported as `number` and lose precision above
+//! 2^53.
+//!
+//! ```js
+//! import { Counter, Bits } from "collectors";
+//!
+//! const counter = new Counter();
+//! counter.countWords("GET /a GET /b");
+//! counter.get("GET"); // 2
+//! counter.toJson(); // '{"/a":1,"/b":1,"GET":2}'
+//!
+//! const bits = new Bits(new Uint8Array([0xf0, 0x0f]), false);
+//! bits.consumeU8(4); // 15
+//! ```
+//!
+use crate::{Bits, Counter};
+use wasm_bindgen::prelude::*;
+
+/// JavaScript class wrapping a `Counter` of strings
+#[wasm_bindgen(js_name = Counter)]
+#[derive(Debug, Default)]
+pub struct WasmCounter {
+    counter: Counter<String>,
+}
+
+#[wasm_bindgen(js_class = Counter)]
+impl WasmCounter {
+    /// Create a new empty `Counter`.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmCounter {
+        WasmCounter::default()
+    }
+
+    /// Count a value.
+    pub fn update(&mut self, value: &str) {
+        self.counter.update_from_value(String::from(value));
+    }
+
+    /// Count every value of an array of strings.
+    #[wasm_bindgen(js_name = updateFromArray)]
+    pub fn update_from_array(&mut self, values: Vec<String>) {
+        self.counter.update_from_iter(values.into_iter());
+    }
+
+    /// Count the lines of a text.
+    #[wasm_bindgen(js_name = countLines)]
+    pub fn count_lines(&mut self, text: &str) {
+        self.counter
+            .update_from_iter(text.lines().map(String::from));
+    }
+
+    /// Count the whitespace separated words of a text.
+    #[wasm_bindgen(js_name = countWords)]
+    pub fn count_words(&mut self, text: &str) {
+        self.counter
+            .update_from_iter(text.split_whitespace().map(String::from));
+    }
+
+    /// Returns the count of a value, 0 if it was never counted.
+    pub fn get(&self, value: &str) -> f64 {
+        self.counter[value] as f64
+    }
+
+    /// Returns the sum of all counts.
+    pub fn total(&self) -> f64 {
+        self.counter.total() as f64
+    }
+
+    /// Returns the number of distinct values.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.counter.len()
+    }
+
+    /// Returns the distinct values, in order.
+    pub fn keys(&self) -> Vec<String> {
+        self.counter.iter().map(|(key, _)| key.clone()).collect()
+    }
+
+    /// Returns the counts of the distinct values, in the order of `keys`.
+    pub fn counts(&self) -> Vec<f64> {
+        self.counter
+            .iter()
+            .map(|(_, count)| *count as f64)
+            .collect()
+    }
+
+    /// Serialize the counts as a JSON object.
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        self.counter.to_json_string()
+    }
+
+    /// Create a new `Counter` from a JSON object mapping values to counts.
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<WasmCounter, JsError> {
+        Ok(WasmCounter {
+            counter: Counter::from_json_str(json)?,
+        })
+    }
+}
+
+/// JavaScript class wrapping a `Bits` stream
+#[wasm_bindgen(js_name = Bits)]
+#[derive(Debug)]
+pub struct WasmBits {
+    bits: Bits,
+}
+
+#[wasm_bindgen(js_class = Bits)]
+impl WasmBits {
+    /// Create a new bit stream from the bytes of a `Uint8Array`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: &[u8], little_endian: bool) -> WasmBits {
+        let bits = if little_endian {
+            Bits::from_u8_little_endian(data)
+        } else {
+            Bits::from_u8_big_endian(data)
+        };
+        WasmBits { bits }
+    }
+
+    /// Consume the next `size` bits, at most 8, as an unsigned integer.
+    #[wasm_bindgen(js_name = consumeU8)]
+    pub fn consume_u8(&mut self, size: usize) -> Result<u8, JsError> {
+        Ok(self.bits.consume_next_data_as_u8(size)?)
+    }
+
+    /// Consume the next `size` bits, at most 16, as an unsigned integer.
+    #[wasm_bindgen(js_name = consumeU16)]
+    pub fn consume_u16(&mut self, size: usize) -> Result<u16, JsError> {
+        Ok(self.bits.consume_next_data_as_u16(size)?)
+    }
+
+    /// Consume the next `size` bits, at most 32, as an unsigned integer.
+    #[wasm_bindgen(js_name = consumeU32)]
+    pub fn consume_u32(&mut self, size: usize) -> Result<u32, JsError> {
+        Ok(self.bits.consume_next_data_as_u32(size)?)
+    }
+
+    /// Consume the next `size` bits, at most 32, as a signed integer.
+    #[wasm_bindgen(js_name = consumeI32)]
+    pub fn consume_i32(&mut self, size: usize) -> Result<i32, JsError> {
+        Ok(self.bits.consume_next_data_as_i32(size)?)
+    }
+
+    /// Returns the remaining bits as booleans.
+    #[wasm_bindgen(js_name = toBooleans)]
+    pub fn to_booleans(&self) -> Vec<u8> {
+        self.bits.as_vec_bool().into_iter().map(u8::from).collect()
+    }
+
+    /// Render the remaining bits, bytes separated by `|`.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_js_string(&self) -> String {
+        self.bits.to_string()
+    }
+}
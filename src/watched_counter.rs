@@ -0,0 +1,139 @@
+//! This module implements a `Counter` calling hooks while it is updated.
+//!
+//! Hooks are registered with `on_threshold` and fired from within the
+//! update, as soon as the count of an element reaches their threshold, so
+//! spikes can be reacted on without a second pass over the counts.
+//!
+use crate::Counter;
+use std::fmt::{self, Debug, Formatter};
+
+type Hook<'a, T> = Box<dyn FnMut(&T) + 'a>;
+
+/// Structure that count occurences of `T` elements and fire hooks when
+/// their counts reach thresholds
+pub struct WatchedCounter<'a, T> {
+    counter: Counter<T>,
+    hooks: Vec<(u128, Hook<'a, T>)>,
+}
+
+impl<'a, T: Ord> WatchedCounter<'a, T> {
+    /// Create a new empty `WatchedCounter`, without any hook.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::WatchedCounter;
+    /// let counter: WatchedCounter<u16> = WatchedCounter::new();
+    /// # assert!(counter.counter().is_empty());
+    /// ```
+    pub fn new() -> Self {
+        WatchedCounter::from_counter(Counter::new())
+    }
+
+    /// Create a new `WatchedCounter` starting from the counts of `counter`.
+    ///
+    /// Hooks only fire for thresholds reached after they are registered.
+    ///
+    /// # Arguments
+    /// * counter - The initial counts
+    pub fn from_counter(counter: Counter<T>) -> Self {
+        WatchedCounter {
+            counter,
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Register a hook called with an element whenever its count reaches
+    /// `threshold`.
+    ///
+    /// # Arguments
+    /// * threshold - The count firing the hook, must be greater than 0
+    /// * hook - The function called with the element
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::WatchedCounter;
+    /// let mut alerts: Vec<u16> = Vec::new();
+    /// {
+    ///     let mut counter: WatchedCounter<u16> = WatchedCounter::new();
+    ///     counter.on_threshold(3, |code| alerts.push(*code));
+    ///     counter.update_from_iter(vec![200, 500, 500, 200, 500, 500, 200].into_iter());
+    /// }
+    /// assert_eq!(alerts, vec![500, 200]);
+    /// ```
+    pub fn on_threshold<F>(&mut self, threshold: u128, hook: F)
+    where
+        F: FnMut(&T) + 'a,
+    {
+        assert!(threshold > 0);
+        self.hooks.push((threshold, Box::new(hook)));
+    }
+
+    /// Update the `WatchedCounter` with a value, firing the hooks whose
+    /// threshold its count reaches.
+    ///
+    /// # Panics
+    /// Panics if a count overflows under `OverflowPolicy::Checked`.
+    ///
+    /// # Arguments
+    /// * elem - A value used to update the `WatchedCounter`
+    pub fn update_from_value(&mut self, elem: T) {
+        // A count stuck at its maximum doesn't reach any new threshold.
+        if let Some(reached) = self.counter[&elem].checked_add(1) {
+            for (threshold, hook) in self.hooks.iter_mut() {
+                if *threshold == reached {
+                    hook(&elem);
+                }
+            }
+        }
+        self.counter.update_from_value(elem);
+    }
+
+    /// Update the `WatchedCounter` with an iterator, firing the hooks as
+    /// counts reach their thresholds.
+    ///
+    /// # Panics
+    /// Panics if a count overflows under `OverflowPolicy::Checked`.
+    ///
+    /// # Arguments
+    /// * iter - An iterator used to update the `WatchedCounter`
+    pub fn update_from_iter<I>(&mut self, iter: I)
+    where
+        I: Iterator<Item = T>,
+    {
+        for elem in iter {
+            self.update_from_value(elem);
+        }
+    }
+
+    /// Returns the counts collected so far.
+    pub fn counter(&self) -> &Counter<T> {
+        &self.counter
+    }
+
+    /// Convert the `WatchedCounter` into its `Counter`, dropping the hooks.
+    pub fn into_counter(self) -> Counter<T> {
+        self.counter
+    }
+}
+
+impl<T: Ord> Default for WatchedCounter<'_, T> {
+    fn default() -> Self {
+        WatchedCounter::new()
+    }
+}
+
+impl<T: Debug> Debug for WatchedCounter<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchedCounter")
+            .field("counter", &self.counter)
+            .field(
+                "thresholds",
+                &self
+                    .hooks
+                    .iter()
+                    .map(|(threshold, _)| *threshold)
+                    .collect::<Vec<u128>>(),
+            )
+            .finish()
+    }
+}
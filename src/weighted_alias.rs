@@ -0,0 +1,174 @@
+//! This module implements weighted sampling with Vose's alias method.
+//!
+//! The weights are spread over `n` columns of equal height, each column
+//! holding one element up to some height and at most one other element,
+//! its alias, above. Building the columns is O(n), and drawing then takes
+//! one random column and one random height, in O(1) whatever the number
+//! of elements, where searching cumulative weights would be O(log n).
+//! Random numbers come from an internal generator, seeded for
+//! reproducible draws.
+//!
+use crate::min_hash::xorshift;
+use crate::Counter;
+use std::iter::FromIterator;
+
+/// Structure that draw `T` elements in proportion to their weights
+#[derive(Debug, Clone)]
+pub struct WeightedAlias<T> {
+    elems: Vec<T>,
+    thresholds: Vec<f64>,
+    aliases: Vec<usize>,
+    seed: u64,
+}
+
+impl<T> WeightedAlias<T> {
+    /// Create a new `WeightedAlias` from elements and their weights.
+    ///
+    /// # Arguments
+    /// * pairs - The elements and their weights, which must be finite and
+    ///   not negative, and not all 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::WeightedAlias;
+    /// let mut dice = WeightedAlias::new(vec![("even", 1.0), ("odd", 1.0)]);
+    /// # assert_eq!(dice.len(), 2);
+    /// # let _ = dice.sample();
+    /// ```
+    pub fn new<I>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (T, f64)>,
+    {
+        WeightedAlias::with_seed(pairs, 0x9E37_79B9_7F4A_7C15)
+    }
+
+    /// Create a new `WeightedAlias` from elements and their weights, with
+    /// a given seed.
+    ///
+    /// # Arguments
+    /// * pairs - The elements and their weights, which must be finite and
+    ///   not negative, and not all 0
+    /// * seed - The seed of the random numbers, must not be 0
+    pub fn with_seed<I>(pairs: I, seed: u64) -> Self
+    where
+        I: IntoIterator<Item = (T, f64)>,
+    {
+        assert!(seed != 0);
+        let (elems, weights): (Vec<T>, Vec<f64>) = pairs.into_iter().unzip();
+        assert!(weights
+            .iter()
+            .all(|weight| weight.is_finite() && *weight >= 0.0));
+        let total: f64 = weights.iter().sum();
+        assert!(total > 0.0);
+
+        // Heights scaled so that a full column is 1.
+        let len = elems.len();
+        let mut heights: Vec<f64> = weights
+            .iter()
+            .map(|weight| weight * len as f64 / total)
+            .collect();
+        let mut thresholds: Vec<f64> = vec![1.0; len];
+        let mut aliases: Vec<usize> = (0..len).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..len).partition(|idx| heights[*idx] < 1.0);
+        while let (Some(short), Some(tall)) = (small.pop(), large.pop()) {
+            thresholds[short] = heights[short];
+            aliases[short] = tall;
+            heights[tall] -= 1.0 - heights[short];
+            if heights[tall] < 1.0 {
+                small.push(tall);
+            } else {
+                large.push(tall);
+            }
+        }
+        // Columns left are full, up to rounding errors.
+        WeightedAlias {
+            elems,
+            thresholds,
+            aliases,
+            seed,
+        }
+    }
+
+    /// Create a new `WeightedAlias` drawing the elements of a `Counter` in
+    /// proportion to their counts.
+    ///
+    /// # Arguments
+    /// * counter - The `Counter` of the elements, its total must not be 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::{Counter, WeightedAlias};
+    /// // Simulate words following the frequencies of a corpus.
+    /// let corpus: Counter<&str> = "a rose is a rose is a rose".split(' ').collect();
+    /// let mut words = WeightedAlias::from_counter(&corpus);
+    /// let simulated: Counter<&str> = words.sample_n(30_000).into_iter().copied().collect();
+    /// let share = simulated[&"rose"] as f64 / 30_000.0;
+    /// assert!((share - 3.0 / 8.0).abs() < 0.02);
+    /// ```
+    pub fn from_counter(counter: &Counter<T>) -> Self
+    where
+        T: Clone,
+    {
+        WeightedAlias::new(
+            counter
+                .iter()
+                .map(|(elem, count)| (elem.clone(), *count as f64)),
+        )
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.elems.len()
+    }
+
+    /// Returns `true` if there is no element, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.elems.is_empty()
+    }
+
+    /// Returns the elements, in the order they were given.
+    pub fn elems(&self) -> &[T] {
+        &self.elems
+    }
+
+    /// Draw an element.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::WeightedAlias;
+    /// let mut coin = WeightedAlias::new(vec![("heads", 0.0), ("tails", 2.5)]);
+    /// assert_eq!(coin.sample(), &"tails");
+    /// ```
+    pub fn sample(&mut self) -> &T {
+        let idx = self.sample_index();
+        &self.elems[idx]
+    }
+
+    /// Draw `n` elements, independently.
+    ///
+    /// # Arguments
+    /// * n - The number of elements drawn
+    pub fn sample_n(&mut self, n: usize) -> Vec<&T> {
+        let indices: Vec<usize> = (0..n).map(|_| self.sample_index()).collect();
+        let elems = &self.elems;
+        indices.into_iter().map(|idx| &elems[idx]).collect()
+    }
+
+    fn sample_index(&mut self) -> usize {
+        let column = (xorshift(&mut self.seed) % self.elems.len() as u64) as usize;
+        // 53 random bits, the precision of a f64.
+        let height = (xorshift(&mut self.seed) >> 11) as f64 / (1u64 << 53) as f64;
+        if height < self.thresholds[column] {
+            column
+        } else {
+            self.aliases[column]
+        }
+    }
+}
+
+impl<T> FromIterator<(T, f64)> for WeightedAlias<T> {
+    fn from_iter<I: IntoIterator<Item = (T, f64)>>(iter: I) -> Self {
+        WeightedAlias::new(iter)
+    }
+}
@@ -0,0 +1,187 @@
+//! This module implements the minimum and maximum over the last values of
+//! a stream.
+//!
+//! A `WindowExtrema` keeps the last `n` values pushed, along with two
+//! monotonic deques of their positions in the stream: a value no longer
+//! matters for the maximum once a value at least as large is pushed after
+//! it, so it is dropped. The front of each deque is then the extremum of
+//! the window, read in O(1), and every push is O(1) amortized. Unlike a
+//! `RollingWindow`, values only need to be ordered, not summed.
+//!
+use std::collections::VecDeque;
+
+/// Structure that track the extrema of the last `T` values of a stream
+#[derive(Debug, Clone)]
+pub struct WindowExtrema<T> {
+    values: VecDeque<T>,
+    capacity: usize,
+    pushed: u64,
+    mins: VecDeque<u64>,
+    maxs: VecDeque<u64>,
+}
+
+impl<T: Ord> WindowExtrema<T> {
+    /// Create a new empty `WindowExtrema` of length `capacity`.
+    ///
+    /// # Arguments
+    /// * capacity - The number of values tracked, must be greater than 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::WindowExtrema;
+    /// let window: WindowExtrema<u64> = WindowExtrema::new(60);
+    /// # assert!(window.is_empty());
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        WindowExtrema {
+            values: VecDeque::with_capacity(capacity),
+            capacity,
+            pushed: 0,
+            mins: VecDeque::new(),
+            maxs: VecDeque::new(),
+        }
+    }
+
+    /// Returns the length of the window.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of values in the window.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if no value was pushed, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns `true` if the window holds `capacity` values, `false`
+    /// otherwise.
+    pub fn is_full(&self) -> bool {
+        self.values.len() == self.capacity
+    }
+
+    /// Returns the number of values pushed since the creation or the last
+    /// clear.
+    pub fn pushed(&self) -> u64 {
+        self.pushed
+    }
+
+    /// Push a value, returning the oldest value if it left the window.
+    ///
+    /// # Arguments
+    /// * value - The value pushed
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::WindowExtrema;
+    /// let mut window: WindowExtrema<&str> = WindowExtrema::new(2);
+    /// assert_eq!(window.push("b"), None);
+    /// assert_eq!(window.push("c"), None);
+    /// assert_eq!(window.push("a"), Some("b"));
+    /// assert_eq!(window.min(), Some(&"a"));
+    /// assert_eq!(window.max(), Some(&"c"));
+    /// ```
+    pub fn push(&mut self, value: T) -> Option<T> {
+        let evicted = if self.is_full() {
+            self.values.pop_front()
+        } else {
+            None
+        };
+        // Values pushed before `oldest` left the window.
+        let oldest = self.pushed - self.values.len() as u64;
+        while self.mins.front().is_some_and(|idx| *idx < oldest) {
+            let _ = self.mins.pop_front();
+        }
+        while self.maxs.front().is_some_and(|idx| *idx < oldest) {
+            let _ = self.maxs.pop_front();
+        }
+        while self
+            .mins
+            .back()
+            .is_some_and(|idx| self.values[(idx - oldest) as usize] >= value)
+        {
+            let _ = self.mins.pop_back();
+        }
+        while self
+            .maxs
+            .back()
+            .is_some_and(|idx| self.values[(idx - oldest) as usize] <= value)
+        {
+            let _ = self.maxs.pop_back();
+        }
+        self.mins.push_back(self.pushed);
+        self.maxs.push_back(self.pushed);
+        self.values.push_back(value);
+        self.pushed += 1;
+        evicted
+    }
+
+    /// Returns the smallest value in the window, `None` if empty.
+    pub fn min(&self) -> Option<&T> {
+        self.mins.front().map(|idx| self.value_at(*idx))
+    }
+
+    /// Returns the largest value in the window, `None` if empty.
+    pub fn max(&self) -> Option<&T> {
+        self.maxs.front().map(|idx| self.value_at(*idx))
+    }
+
+    /// Returns the position in the stream of the smallest value in the
+    /// window, the latest one among equal values, `None` if empty.
+    pub fn min_position(&self) -> Option<u64> {
+        self.mins.front().copied()
+    }
+
+    /// Returns the position in the stream of the largest value in the
+    /// window, the latest one among equal values, `None` if empty.
+    ///
+    /// # Examples
+    /// ```
+    /// # use collectors::WindowExtrema;
+    /// // Positions that are the peak of the 5 values around them.
+    /// let signal = vec![1, 0, 3, 2, 1, 0, 4, 8, 5, 1, 2];
+    /// let mut window: WindowExtrema<i32> = WindowExtrema::new(5);
+    /// let mut peaks: Vec<u64> = Vec::new();
+    /// for value in signal {
+    ///     let _ = window.push(value);
+    ///     if window.is_full() && window.max_position() == Some(window.pushed() - 3) {
+    ///         peaks.push(window.pushed() - 3);
+    ///     }
+    /// }
+    /// assert_eq!(peaks, vec![2, 7]);
+    /// ```
+    pub fn max_position(&self) -> Option<u64> {
+        self.maxs.front().copied()
+    }
+
+    /// Iterate over the values in the window, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.values.iter()
+    }
+
+    /// Remove every value.
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.pushed = 0;
+        self.mins.clear();
+        self.maxs.clear();
+    }
+
+    /// Returns the value at a position of the stream, in the window.
+    fn value_at(&self, idx: u64) -> &T {
+        let oldest = self.pushed - self.values.len() as u64;
+        &self.values[(idx - oldest) as usize]
+    }
+}
+
+impl<T: Ord> Extend<T> for WindowExtrema<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            let _ = self.push(value);
+        }
+    }
+}